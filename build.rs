@@ -3,4 +3,8 @@ extern crate autocfg;
 fn main() {
     let ac = autocfg::new();
     ac.emit_rustc_version(1, 6);
+    ac.emit_rustc_version(1, 28);
+    ac.emit_rustc_version(1, 34);
+    ac.emit_rustc_version(1, 40);
+    ac.emit_rustc_version(1, 59);
 }
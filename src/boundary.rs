@@ -0,0 +1,258 @@
+//! Detection of UTF-8 sequence boundaries.
+
+use bounds;
+use core::ops::Range;
+
+/// A direction in which to search for a character boundary.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    /// Search toward the start of the string.
+    Backward,
+    /// Search toward the end of the string.
+    Forward,
+    /// Search for the closest boundary in either direction, preferring the backward boundary when
+    /// both are equally close.
+    Nearest,
+}
+
+/// A way in which to snap the bounds of a byte range to character boundaries.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Snap {
+    /// Snap the start forward and the end backward, excluding partially covered characters.
+    Inward,
+    /// Snap the start backward and the end forward, including partially covered characters.
+    Outward,
+    /// Snap both bounds backward.
+    Backward,
+    /// Snap both bounds forward.
+    Forward,
+}
+
+/// Returns whether `byte` is a UTF-8 continuation byte.
+pub(crate) fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+/// Returns the largest character boundary of `s` that is not greater than `index`.
+///
+/// Indices past the end of `s` are clamped to its length. At most three bytes are examined.
+pub(crate) fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    let bytes = s.as_bytes();
+    let mut boundary = index;
+    while is_continuation_byte(bytes[boundary]) {
+        boundary -= 1;
+    }
+    boundary
+}
+
+/// Returns the smallest character boundary of `s` that is not less than `index`.
+///
+/// Indices past the end of `s` are clamped to its length. At most three bytes are examined.
+pub(crate) fn ceil_char_boundary(s: &str, index: usize) -> usize {
+    let bytes = s.as_bytes();
+    let mut boundary = index;
+    while boundary < bytes.len() && is_continuation_byte(bytes[boundary]) {
+        boundary += 1;
+    }
+    if boundary > s.len() {
+        s.len()
+    } else {
+        boundary
+    }
+}
+
+/// Returns the character boundary of `s` closest to `index` in direction `dir`.
+///
+/// Indices past the end of `s` are clamped to its length. At most three bytes are examined in
+/// each direction.
+pub(crate) fn nearest_char_boundary(s: &str, index: usize, dir: Direction) -> usize {
+    match dir {
+        Direction::Backward => floor_char_boundary(s, index),
+        Direction::Forward => ceil_char_boundary(s, index),
+        Direction::Nearest => {
+            let floor = floor_char_boundary(s, index);
+            if floor == index || index >= s.len() {
+                return floor;
+            }
+            let ceil = ceil_char_boundary(s, index);
+            if ceil - index < index - floor {
+                ceil
+            } else {
+                floor
+            }
+        }
+    }
+}
+
+/// Returns the byte range `start..end` with its bounds snapped to character boundaries of `s` as
+/// specified by `snap`.
+///
+/// Indices past the end of `s` are clamped to its length. If the snapped end is before the snapped
+/// start, an empty range at the snapped start is returned.
+pub(crate) fn snap_byte_range(
+    s: &str,
+    start: usize,
+    end: Option<usize>,
+    snap: Snap,
+) -> Range<usize> {
+    // Whether each bound is snapped forward, rather than backward.
+    let (start_forward, end_forward) = match snap {
+        Snap::Inward => (true, false),
+        Snap::Outward => (false, true),
+        Snap::Backward => (false, false),
+        Snap::Forward => (true, true),
+    };
+    let snap_index = |index, forward| {
+        if forward {
+            ceil_char_boundary(s, index)
+        } else {
+            floor_char_boundary(s, index)
+        }
+    };
+    let clamped = bounds::clamp(start, end, s.len());
+    let start_byte = snap_index(clamped.start, start_forward);
+    let end_byte = snap_index(clamped.end, end_forward);
+    if end_byte < start_byte {
+        start_byte..start_byte
+    } else {
+        start_byte..end_byte
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ceil_char_boundary, floor_char_boundary, nearest_char_boundary, snap_byte_range, Direction,
+        Snap,
+    };
+
+    #[test]
+    fn test_floor_char_boundary() {
+        // "õ" occupies bytes 1..3 and "😀" occupies bytes 3..7.
+        let s = "fõ😀";
+        assert_eq!(floor_char_boundary(s, 0), 0);
+        assert_eq!(floor_char_boundary(s, 2), 1);
+        assert_eq!(floor_char_boundary(s, 3), 3);
+        assert_eq!(floor_char_boundary(s, 6), 3);
+        assert_eq!(floor_char_boundary(s, 7), 7);
+        assert_eq!(floor_char_boundary(s, 100), 7);
+    }
+
+    #[test]
+    fn test_ceil_char_boundary() {
+        let s = "fõ😀";
+        assert_eq!(ceil_char_boundary(s, 0), 0);
+        assert_eq!(ceil_char_boundary(s, 2), 3);
+        assert_eq!(ceil_char_boundary(s, 4), 7);
+        assert_eq!(ceil_char_boundary(s, 7), 7);
+        assert_eq!(ceil_char_boundary(s, 100), 7);
+    }
+
+    #[test]
+    fn test_nearest_char_boundary() {
+        // "😀" occupies bytes 1..5.
+        let s = "a😀";
+        assert_eq!(nearest_char_boundary(s, 2, Direction::Nearest), 1);
+        assert_eq!(nearest_char_boundary(s, 3, Direction::Nearest), 1);
+        assert_eq!(nearest_char_boundary(s, 4, Direction::Nearest), 5);
+        assert_eq!(nearest_char_boundary(s, 3, Direction::Backward), 1);
+        assert_eq!(nearest_char_boundary(s, 3, Direction::Forward), 5);
+        assert_eq!(nearest_char_boundary(s, 9, Direction::Nearest), 5);
+    }
+
+    // "😀" occupies bytes 1..5 of "a😀b😀c", and the second "😀" occupies bytes 6..10.
+    const SNAP_STR: &str = "a😀b😀c";
+
+    #[test]
+    fn test_snap_inward() {
+        for start in 1..5 {
+            for end in 6..10 {
+                let expected = if start == 1 { 1..6 } else { 5..6 };
+                assert_eq!(
+                    snap_byte_range(SNAP_STR, start, Some(end), Snap::Inward),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_snap_outward() {
+        for start in 1..5 {
+            for end in 6..10 {
+                let expected = if end == 6 { 1..6 } else { 1..10 };
+                assert_eq!(
+                    snap_byte_range(SNAP_STR, start, Some(end), Snap::Outward),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_snap_backward() {
+        for start in 1..5 {
+            for end in 6..10 {
+                assert_eq!(
+                    snap_byte_range(SNAP_STR, start, Some(end), Snap::Backward),
+                    1..6
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_snap_forward() {
+        for start in 1..5 {
+            for end in 6..10 {
+                let expected = match (start, end) {
+                    (1, 6) => 1..6,
+                    (1, _) => 1..10,
+                    (_, 6) => 5..6,
+                    _ => 5..10,
+                };
+                assert_eq!(
+                    snap_byte_range(SNAP_STR, start, Some(end), Snap::Forward),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_snap_within_one_char() {
+        assert_eq!(snap_byte_range(SNAP_STR, 2, Some(4), Snap::Inward), 5..5);
+        assert_eq!(snap_byte_range(SNAP_STR, 2, Some(4), Snap::Outward), 1..5);
+        assert_eq!(snap_byte_range(SNAP_STR, 2, Some(4), Snap::Backward), 1..1);
+        assert_eq!(snap_byte_range(SNAP_STR, 2, Some(4), Snap::Forward), 5..5);
+    }
+
+    #[test]
+    fn test_snap_past_end() {
+        for &snap in [Snap::Inward, Snap::Outward, Snap::Backward, Snap::Forward].iter() {
+            assert_eq!(snap_byte_range(SNAP_STR, 3, Some(100), snap).end, 11);
+            assert_eq!(snap_byte_range(SNAP_STR, 100, None, snap), 11..11);
+        }
+    }
+
+    #[test]
+    fn test_snap_inward_within_outward() {
+        for start in 0..13 {
+            for end in 0..13 {
+                let inward = snap_byte_range(SNAP_STR, start, Some(end), Snap::Inward);
+                let outward = snap_byte_range(SNAP_STR, start, Some(end), Snap::Outward);
+                if !inward.is_empty() {
+                    assert!(outward.start <= inward.start);
+                    assert!(inward.end <= outward.end);
+                }
+                assert!(outward.start <= start.min(SNAP_STR.len()));
+                if end >= start {
+                    assert!(outward.end >= end.min(SNAP_STR.len()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Resolution of range bounds into character indices.
+
+use core::ops::{Bound, Range, RangeBounds, RangeInclusive};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A range with both a start and an end bound.
+///
+/// Some methods need to know how many characters a range requests, which is not defined for ranges
+/// with an unbounded end. Those methods accept a `SizedRange` instead of any [`RangeBounds`].
+///
+/// This trait is implemented for [`Range<usize>`] and [`RangeInclusive<usize>`]. It is sealed and
+/// cannot be implemented outside of this crate.
+///
+/// [`RangeBounds`]: https://doc.rust-lang.org/core/ops/trait.RangeBounds.html
+/// [`Range<usize>`]: https://doc.rust-lang.org/core/ops/struct.Range.html
+/// [`RangeInclusive<usize>`]: https://doc.rust-lang.org/core/ops/struct.RangeInclusive.html
+pub trait SizedRange: RangeBounds<usize> + private::Sealed {}
+
+impl private::Sealed for Range<usize> {}
+impl SizedRange for Range<usize> {}
+
+impl private::Sealed for RangeInclusive<usize> {}
+impl SizedRange for RangeInclusive<usize> {}
+
+/// Converts a start bound into an inclusive index.
+pub(crate) fn start_index(bound: Bound<&usize>) -> usize {
+    match bound {
+        Bound::Included(&index) => index,
+        Bound::Excluded(&index) => index.saturating_add(1),
+        Bound::Unbounded => 0,
+    }
+}
+
+/// Converts an end bound into an exclusive index, or `None` if it is unbounded.
+pub(crate) fn end_index(bound: Bound<&usize>) -> Option<usize> {
+    match bound {
+        Bound::Included(&index) => Some(index.saturating_add(1)),
+        Bound::Excluded(&index) => Some(index),
+        Bound::Unbounded => None,
+    }
+}
+
+/// A range resolved into indices, along with how it was adjusted along the way.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Resolved {
+    /// The inclusive start index.
+    pub(crate) start: usize,
+    /// The exclusive end index, or `None` if it is unbounded.
+    pub(crate) end: Option<usize>,
+    /// Whether the requested end was before the requested start.
+    pub(crate) reversed: bool,
+    /// Whether converting the start bound into an index overflowed `usize`, in which case `start`
+    /// is `usize::MAX`, one less than the requested index.
+    pub(crate) start_overflowed: bool,
+    /// Whether converting the end bound into an index overflowed `usize`, in which case `end` is
+    /// `usize::MAX`, one less than the requested index.
+    pub(crate) end_overflowed: bool,
+}
+
+impl Resolved {
+    /// Returns whether converting either bound into an index overflowed `usize`.
+    pub(crate) fn overflowed(&self) -> bool {
+        self.start_overflowed || self.end_overflowed
+    }
+}
+
+/// Clamps the inclusive `start` and the exclusive `end`, or the unbounded end if `end` is `None`,
+/// to `limit`.
+///
+/// A reversed range is left reversed, so that callers may collapse it wherever they would place an
+/// empty range.
+pub(crate) fn clamp(start: usize, end: Option<usize>, limit: usize) -> Range<usize> {
+    start.min(limit)..end.map_or(limit, |end| end.min(limit))
+}
+
+/// Resolves the bounds of `range` into indices.
+///
+/// Indices are not clamped, as most callers do not know the length of the string ahead of time and
+/// clamp while walking it instead. Callers which do know it clamp with [`clamp()`]. Reversed ranges are reported but left as is, so that callers
+/// may collapse them wherever they would place an empty range.
+///
+/// [`clamp()`]: fn.clamp.html
+pub(crate) fn resolve<R: RangeBounds<usize>>(range: &R) -> Resolved {
+    let start = start_index(range.start_bound());
+    let end = end_index(range.end_bound());
+    let start_overflowed = match range.start_bound() {
+        Bound::Excluded(&index) => index == usize::max_value(),
+        _ => false,
+    };
+    let end_overflowed = match range.end_bound() {
+        Bound::Included(&index) => index == usize::max_value(),
+        _ => false,
+    };
+    // An overflowed index saturates at `usize::MAX`, one less than its true value, so comparing
+    // `(index, overflowed)` pairs orders the true values.
+    let reversed = end.map_or(false, |end| {
+        (end, end_overflowed) < (start, start_overflowed)
+    });
+
+    Resolved {
+        start,
+        end,
+        reversed,
+        start_overflowed,
+        end_overflowed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clamp, end_index, resolve, start_index, Resolved};
+    use core::ops::Bound;
+    use std::vec::Vec;
+    use CharSubstring;
+
+    #[test]
+    fn test_start_index() {
+        assert_eq!(start_index(Bound::Included(&3)), 3);
+        assert_eq!(start_index(Bound::Excluded(&3)), 4);
+        assert_eq!(start_index(Bound::Unbounded), 0);
+    }
+
+    #[test]
+    fn test_end_index() {
+        assert_eq!(end_index(Bound::Included(&3)), Some(4));
+        assert_eq!(end_index(Bound::Excluded(&3)), Some(3));
+        assert_eq!(end_index(Bound::Unbounded), None);
+    }
+
+    #[test]
+    fn test_saturation() {
+        assert_eq!(
+            start_index(Bound::Excluded(&::core::usize::MAX)),
+            ::core::usize::MAX
+        );
+        assert_eq!(
+            end_index(Bound::Included(&::core::usize::MAX)),
+            Some(::core::usize::MAX)
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(&(2..5)),
+            Resolved {
+                start: 2,
+                end: Some(5),
+                reversed: false,
+                start_overflowed: false,
+                end_overflowed: false,
+            }
+        );
+        assert_eq!(resolve(&(2..)).end, None);
+        let (start, end) = (4, 2);
+        assert!(resolve(&(start..=end)).reversed);
+    }
+
+    #[test]
+    fn test_clamp() {
+        assert_eq!(clamp(3, Some(9), 5), 3..5);
+        assert_eq!(clamp(7, None, 5), 5..5);
+        assert_eq!(clamp(2, None, 5), 2..5);
+        let (start, end) = (4, 2);
+        assert_eq!(clamp(start, Some(end), 5), start..end);
+    }
+
+    /// Resolves every combination of bound kinds at the edges of real strings, checking the
+    /// clamped range against the characters sliced by the public methods.
+    #[test]
+    fn test_resolve_at_string_edges() {
+        for s in ["", "a", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let boundaries = s
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain(Some(s.len()))
+                .collect::<Vec<_>>();
+            let len = boundaries.len() - 1;
+            let values = [0, 1, len.saturating_sub(1), len, len + 1];
+            let mut starts = vec![Bound::Unbounded];
+            let mut ends = vec![Bound::Unbounded];
+            for &value in &values {
+                starts.push(Bound::Included(value));
+                starts.push(Bound::Excluded(value));
+                ends.push(Bound::Included(value));
+                ends.push(Bound::Excluded(value));
+            }
+
+            for &start in &starts {
+                for &end in &ends {
+                    let resolved = resolve(&(start, end));
+                    let clamped = clamp(resolved.start, resolved.end, len);
+                    assert!(clamped.start <= len && clamped.end <= len);
+                    let expected = if clamped.end < clamped.start {
+                        boundaries[clamped.start]..boundaries[clamped.start]
+                    } else {
+                        boundaries[clamped.start]..boundaries[clamped.end]
+                    };
+                    let context = (s, start, end);
+                    assert_eq!(s.char_byte_range((start, end)), expected, "{:?}", context);
+                    assert_eq!(
+                        s.substring_idx((start, end)),
+                        &s[expected.clone()],
+                        "{:?}",
+                        context
+                    );
+                    assert_eq!(
+                        s.try_substring_idx((start, end)),
+                        Ok(&s[expected]),
+                        "{:?}",
+                        context
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_overflow_not_reversed() {
+        let max = ::core::usize::MAX;
+        let resolved = resolve(&(Bound::Excluded(max), Bound::Included(max)));
+        assert!(resolved.start_overflowed && resolved.end_overflowed);
+        assert!(!resolved.reversed);
+        let resolved = resolve(&(Bound::Excluded(max), Bound::Excluded(max)));
+        assert!(resolved.start_overflowed && !resolved.end_overflowed);
+        assert!(resolved.reversed);
+    }
+
+    /// Checks every combination of bound kinds and interesting values against the same resolution
+    /// performed without overflow.
+    #[test]
+    fn test_resolve_exhaustive() {
+        let max = ::core::usize::MAX;
+        let values = [0, 1, 5, max - 1, max];
+        let mut starts = vec![Bound::Unbounded];
+        let mut ends = vec![Bound::Unbounded];
+        for &value in &values {
+            starts.push(Bound::Included(value));
+            starts.push(Bound::Excluded(value));
+            ends.push(Bound::Included(value));
+            ends.push(Bound::Excluded(value));
+        }
+
+        for &start in &starts {
+            for &end in &ends {
+                let true_start = match start {
+                    Bound::Included(index) => index as u128,
+                    Bound::Excluded(index) => index as u128 + 1,
+                    Bound::Unbounded => 0,
+                };
+                let true_end = match end {
+                    Bound::Included(index) => Some(index as u128 + 1),
+                    Bound::Excluded(index) => Some(index as u128),
+                    Bound::Unbounded => None,
+                };
+
+                let resolved = resolve(&(start, end));
+                let context = (start, end);
+                assert_eq!(
+                    resolved.start as u128 + u128::from(resolved.start_overflowed),
+                    true_start,
+                    "{:?}",
+                    context
+                );
+                assert_eq!(
+                    resolved
+                        .end
+                        .map(|end| end as u128 + u128::from(resolved.end_overflowed)),
+                    true_end,
+                    "{:?}",
+                    context
+                );
+                assert_eq!(
+                    resolved.reversed,
+                    true_end.map_or(false, |end| end < true_start),
+                    "{:?}",
+                    context
+                );
+                assert_eq!(
+                    resolved.overflowed(),
+                    true_start > max as u128 || true_end.map_or(false, |end| end > max as u128),
+                    "{:?}",
+                    context
+                );
+            }
+        }
+    }
+}
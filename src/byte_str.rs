@@ -0,0 +1,120 @@
+//! Character-indexed substrings of byte strings which may not be valid UTF-8.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use bounds;
+use core::ops::RangeBounds;
+use lossy;
+
+/// Provides a [`utf8_substring()`] method for byte strings.
+///
+/// [`utf8_substring()`]: trait.ByteSubstring.html#tymethod.utf8_substring
+pub trait ByteSubstring {
+    /// Obtains the characters within `range` of bytes decoded as UTF-8, replacing invalid
+    /// sequences with `U+FFFD`.
+    ///
+    /// Bytes are decoded a small window at a time, only as far as the end of `range`, so the cost
+    /// does not depend on the length of the bytes following it. Each valid sequence counts as one
+    /// character, and so does each maximal invalid sequence, which is replaced by a single
+    /// `U+FFFD` just as in [`String::from_utf8_lossy()`]. The range is clamped to the bounds of
+    /// the bytes, as with [`substring()`].
+    ///
+    /// If the bytes within `range` are valid UTF-8, the result is borrowed from them.
+    ///
+    /// Example:
+    /// ```
+    /// use std::borrow::Cow;
+    /// use substring::ByteSubstring;
+    ///
+    /// assert_eq!(b"fo\xffbar".utf8_substring(1..4), "o\u{fffd}b");
+    /// assert_eq!(b"fo\xffbar".utf8_substring(3..), Cow::Borrowed("bar"));
+    /// ```
+    ///
+    /// [`String::from_utf8_lossy()`]: https://doc.rust-lang.org/std/string/struct.String.html#method.from_utf8_lossy
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    fn utf8_substring<R: RangeBounds<usize>>(&self, range: R) -> Cow<'_, str>;
+}
+
+impl ByteSubstring for [u8] {
+    fn utf8_substring<R: RangeBounds<usize>>(&self, range: R) -> Cow<'_, str> {
+        let resolved = bounds::resolve(&range);
+        let bytes = lossy::char_range_to_byte_range(self, resolved.start, resolved.end);
+        String::from_utf8_lossy(&self[bytes])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ByteSubstring;
+    use alloc::borrow::Cow;
+    use core::cell::Cell;
+    use lossy::VALIDATED;
+
+    fn is_borrowed(s: Cow<'_, str>) -> bool {
+        match s {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        let bytes = "fõøbα®".as_bytes();
+        assert_eq!(bytes.utf8_substring(1..4), "õøb");
+        assert_eq!(bytes.utf8_substring(1..4).as_ptr(), bytes[1..].as_ptr());
+        assert!(is_borrowed(bytes.utf8_substring(1..4)));
+        assert_eq!(bytes.utf8_substring(3..100), "bα®");
+    }
+
+    #[test]
+    fn test_invalid_replaced() {
+        let bytes = b"fo\xffb\x80\x80r";
+        assert_eq!(bytes.utf8_substring(..), "fo\u{fffd}b\u{fffd}\u{fffd}r");
+        assert!(!is_borrowed(bytes.utf8_substring(..)));
+    }
+
+    #[test]
+    fn test_valid_range_within_invalid_bytes_borrowed() {
+        let bytes = b"\xfffoo\xff";
+        assert_eq!(bytes.utf8_substring(1..4), "foo");
+        assert!(is_borrowed(bytes.utf8_substring(1..4)));
+    }
+
+    #[test]
+    fn test_truncated_sequences_at_range_ends() {
+        // "\xe2\x82" and "\xf0\x9f\x98" are truncated sequences, each counting as one character.
+        let bytes = b"\xe2\x82abc\xf0\x9f\x98";
+        assert_eq!(bytes.utf8_substring(..2), "\u{fffd}a");
+        assert_eq!(bytes.utf8_substring(3..), "c\u{fffd}");
+        assert_eq!(bytes.utf8_substring(..), "\u{fffd}abc\u{fffd}");
+        assert_eq!(bytes.utf8_substring(1..4), "abc");
+        assert!(is_borrowed(bytes.utf8_substring(1..4)));
+    }
+
+    #[test]
+    fn test_range_ending_inside_valid_sequence_of_slice() {
+        // The slice ends partway through "ø", which is counted only once it is complete.
+        let bytes = "fõø".as_bytes();
+        assert_eq!(bytes[..4].utf8_substring(..), "fõ\u{fffd}");
+        assert_eq!(bytes[..4].utf8_substring(1..2), "õ");
+    }
+
+    #[test]
+    fn test_empty() {
+        let (start, end) = (3, 1);
+        assert_eq!(b"foobar".utf8_substring(start..end), "");
+        assert_eq!(b"foobar".utf8_substring(10..20), "");
+        assert_eq!(b"".utf8_substring(..), "");
+    }
+
+    #[test]
+    fn test_stops_decoding_at_range_end() {
+        let mut bytes = b"fo\xffbar".to_vec();
+        bytes.extend(::std::iter::repeat(b'a').take(1 << 20));
+        bytes.push(0xff);
+        VALIDATED.with(|validated| validated.set(0));
+        assert_eq!(bytes.utf8_substring(1..4), "o\u{fffd}b");
+        assert!(VALIDATED.with(Cell::get) < 256);
+    }
+}
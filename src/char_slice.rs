@@ -0,0 +1,101 @@
+//! Substrings of slices of characters.
+
+use core::cmp;
+
+/// Provides a [`substring()`] method for slices of characters.
+///
+/// The method has the same signature as [`Substring::substring()`], and clamps its indices in the
+/// same way, but returns a slice of characters rather than a string slice. As slices of `char`s
+/// are indexed directly, a substring is found in constant time.
+///
+/// [`substring()`]: trait.CharSliceSubstring.html#tymethod.substring
+/// [`Substring::substring()`]: trait.Substring.html#tymethod.substring
+pub trait CharSliceSubstring {
+    /// Obtains a slice containing the characters within the range specified by `start_index` and
+    /// `end_index`.
+    ///
+    /// Indices past the end of the slice are clamped to its end. If `end_index` is not after
+    /// `start_index`, an empty slice positioned at `start_index` is returned.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSliceSubstring;
+    ///
+    /// let chars: Vec<char> = "fõøbα®".chars().collect();
+    /// assert_eq!(chars.substring(2, 5), ['ø', 'b', 'α']);
+    /// assert_eq!(chars.substring(4, 10), ['α', '®']);
+    /// ```
+    #[must_use]
+    fn substring(&self, start_index: usize, end_index: usize) -> &[char];
+}
+
+impl CharSliceSubstring for [char] {
+    fn substring(&self, start_index: usize, end_index: usize) -> &[char] {
+        let start = cmp::min(start_index, self.len());
+        let end = cmp::max(cmp::min(end_index, self.len()), start);
+        &self[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharSliceSubstring;
+    use std::vec::Vec;
+    use Substring;
+
+    fn chars(s: &str) -> Vec<char> {
+        s.chars().collect()
+    }
+
+    #[test]
+    fn test_substring() {
+        assert_eq!(chars("foobar").substring(0, 3), ['f', 'o', 'o']);
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        assert_eq!(chars("foobar").substring(0, 10), &chars("foobar")[..]);
+        assert!(chars("foobar").substring(6, 10).is_empty());
+    }
+
+    #[test]
+    fn test_start_less_than_end() {
+        assert!(chars("foobar").substring(3, 2).is_empty());
+    }
+
+    #[test]
+    fn test_start_and_end_equal() {
+        assert!(chars("foobar").substring(3, 3).is_empty());
+    }
+
+    #[test]
+    fn test_multiple_byte_characters() {
+        assert_eq!(chars("fõøbα®").substring(2, 5), ['ø', 'b', 'α']);
+    }
+
+    #[test]
+    fn test_empty_result_position() {
+        let slice = chars("fõøbα®");
+        let offset = |sub: &[char]| (sub.as_ptr() as usize - slice.as_ptr() as usize) / 4;
+        assert_eq!(offset(slice.substring(3, 3)), 3);
+        assert_eq!(offset(slice.substring(4, 2)), 4);
+        assert_eq!(offset(slice.substring(1, 0)), 1);
+        assert_eq!(offset(slice.substring(6, 10)), 6);
+        assert_eq!(offset(slice.substring(20, 10)), 6);
+    }
+
+    #[test]
+    fn test_matches_str() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let slice = chars(s);
+            for start in 0..10 {
+                for end in 0..10 {
+                    assert_eq!(
+                        slice.substring(start, end),
+                        &chars(s.substring(start, end))[..]
+                    );
+                }
+            }
+        }
+    }
+}
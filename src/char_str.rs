@@ -0,0 +1,200 @@
+//! A string slice indexed by characters.
+
+use bounds;
+use core::iter;
+use core::ops::{Index, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo};
+use CharSubstring;
+
+/// A string slice which is indexed by characters rather than bytes.
+///
+/// Indexing a `CharStr` with a range of character indices gives the characters within it, and
+/// panics if the range is not valid for the string, just as [`substring_strict()`] does. The
+/// [`get()`] method returns `None` instead of panicking. Wrapping a string is free, but as with
+/// [`substring()`], each index walks the string from its start.
+///
+/// Example:
+/// ```
+/// use substring::CharStr;
+///
+/// let s = CharStr::new("fõøbα®");
+/// assert_eq!(&s[1..3], "õø");
+/// assert_eq!(&s[4..], "α®");
+/// assert_eq!(s.get(2..8), None);
+/// ```
+///
+/// [`get()`]: #method.get
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+/// [`substring_strict()`]: trait.CharSubstring.html#tymethod.substring_strict
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CharStr<'a> {
+    s: &'a str,
+}
+
+impl<'a> CharStr<'a> {
+    /// Wraps `s` to be indexed by characters.
+    #[must_use]
+    pub fn new(s: &'a str) -> Self {
+        CharStr { s }
+    }
+
+    /// Returns the wrapped string slice.
+    #[must_use]
+    pub fn as_str(&self) -> &'a str {
+        self.s
+    }
+
+    /// Obtains the characters within `range`, or `None` if the range is not valid for the string.
+    ///
+    /// A range is valid when its start is not after its end, and neither bound is past the end of
+    /// the string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharStr;
+    ///
+    /// let s = CharStr::new("fõøbα®");
+    /// assert_eq!(s.get(1..=2), Some("õø"));
+    /// assert_eq!(s.get(6..), Some(""));
+    /// assert_eq!(s.get(7..), None);
+    /// ```
+    #[must_use]
+    pub fn get<R: RangeBounds<usize>>(&self, range: R) -> Option<&'a str> {
+        let resolved = bounds::resolve(&range);
+        // An index past `usize::MAX` is past the end of any string.
+        if resolved.overflowed() {
+            return None;
+        }
+        match resolved.end {
+            Some(end) => self.s.try_substring(resolved.start, end).ok(),
+            None => {
+                let start_byte = self
+                    .s
+                    .char_indices()
+                    .map(|(index, _char)| index)
+                    .chain(iter::once(self.s.len()))
+                    .nth(resolved.start)?;
+                // SAFETY: `start_byte` was obtained from the `CharIndices` of `s`, or is its
+                // length.
+                Some(slice_unchecked!(self.s, start_byte, self.s.len()))
+            }
+        }
+    }
+}
+
+impl<'a> Index<Range<usize>> for CharStr<'a> {
+    type Output = str;
+
+    fn index(&self, range: Range<usize>) -> &str {
+        self.s.substring_strict(range)
+    }
+}
+
+impl<'a> Index<RangeFrom<usize>> for CharStr<'a> {
+    type Output = str;
+
+    fn index(&self, range: RangeFrom<usize>) -> &str {
+        self.s.substring_strict(range)
+    }
+}
+
+impl<'a> Index<RangeTo<usize>> for CharStr<'a> {
+    type Output = str;
+
+    fn index(&self, range: RangeTo<usize>) -> &str {
+        self.s.substring_strict(range)
+    }
+}
+
+impl<'a> Index<RangeInclusive<usize>> for CharStr<'a> {
+    type Output = str;
+
+    fn index(&self, range: RangeInclusive<usize>) -> &str {
+        self.s.substring_strict(range)
+    }
+}
+
+impl<'a> Index<RangeFull> for CharStr<'a> {
+    type Output = str;
+
+    fn index(&self, _range: RangeFull) -> &str {
+        self.s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharStr;
+    use core::ops::Bound;
+    use Substring;
+
+    #[test]
+    fn test_index() {
+        let s = CharStr::new("fõøbα®");
+        assert_eq!(&s[1..3], "õø");
+        assert_eq!(&s[..2], "fõ");
+        assert_eq!(&s[3..], "bα®");
+        assert_eq!(&s[2..=4], "øbα");
+        assert_eq!(&s[..], "fõøbα®");
+        assert_eq!(&s[6..], "");
+        assert_eq!(s[1..3].as_ptr(), s.as_str()[1..].as_ptr());
+    }
+
+    #[test]
+    #[should_panic(expected = "end index 7 is out of bounds of string with 6 characters")]
+    fn test_index_past_end() {
+        let _ = &CharStr::new("fõøbα®")[2..7];
+    }
+
+    #[test]
+    #[should_panic(expected = "start index 7 is out of bounds of string with 6 characters")]
+    fn test_index_from_past_end() {
+        let _ = &CharStr::new("fõøbα®")[7..];
+    }
+
+    #[test]
+    #[should_panic(expected = "start index 4 is after end index 2")]
+    fn test_index_reversed() {
+        let (start, end) = (4, 2);
+        let _ = &CharStr::new("fõøbα®")[start..end];
+    }
+
+    #[test]
+    fn test_get() {
+        let s = CharStr::new("fõøbα®");
+        assert_eq!(s.get(1..3), Some("õø"));
+        assert_eq!(s.get(..=5), Some("fõøbα®"));
+        assert_eq!(s.get(6..6), Some(""));
+        assert_eq!(s.get(6..), Some(""));
+        assert_eq!(s.get((Bound::Excluded(0), Bound::Unbounded)), Some("õøbα®"));
+    }
+
+    #[test]
+    fn test_get_invalid() {
+        let s = CharStr::new("fõøbα®");
+        let (start, end) = (4, 2);
+        assert_eq!(s.get(start..end), None);
+        assert_eq!(s.get(2..7), None);
+        assert_eq!(s.get(7..), None);
+        assert_eq!(s.get(..=usize::max_value()), None);
+        assert_eq!(
+            s.get((Bound::Excluded(usize::max_value()), Bound::Unbounded)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_matches_substring_when_valid() {
+        let s = "😀a𝔘b";
+        let indexed = CharStr::new(s);
+        for start in 0..6 {
+            for end in 0..6 {
+                let expected = if start <= end && end <= 4 {
+                    Some(s.substring(start, end))
+                } else {
+                    None
+                };
+                assert_eq!(indexed.get(start..end), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,3892 @@
+//! Character-indexed methods beyond `substring()`.
+
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use boundary::{self, Direction, Snap};
+use bounds;
+#[cfg(feature = "alloc")]
+use bounds::SizedRange;
+use core::iter;
+use core::ops::{Range, RangeBounds};
+use delimiter::Delimiter;
+use display::SubstringDisplay;
+use error::{CharBoundaryError, IndexConversionError, SubstringError};
+use fold::FoldMatches;
+use index::{self, CharIndex};
+use iter::{
+    CharChunks, CharIndicesInRev, CharWindows, CharsInRev, SubstringCharIndices, SubstringChars,
+    Substrings,
+};
+use segment::{Segmenter, Segments};
+use span::ValidatedSpan;
+#[cfg(feature = "alloc")]
+use truncate::Truncator;
+use unit::{self, Unit};
+use utf16::{self, SurrogatePolicy};
+use width;
+use word;
+
+use checkpoint::{CharIndicesFrom, Checkpoint};
+use unit::char_range_to_byte_range;
+use wrap::{self, BreakOpportunities, WrapAtOpportunities};
+
+/// Provides character-indexed methods beyond [`substring()`].
+///
+/// Like [`substring()`], indices taken by these methods are character indices unless stated
+/// otherwise, and ranges extending past the end of the string are clamped to it.
+///
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+pub trait CharSubstring {
+    /// Obtains the characters within `range`, padded with `fill` to exactly the number of
+    /// characters in `range`.
+    ///
+    /// If the string covers all of `range`, the slice is borrowed. Otherwise, an owned string is
+    /// returned containing the covered characters followed by as many `fill` characters as are
+    /// needed. A range whose end is not after its start requests no characters.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("foobar".substring_padded(2..5, '.'), "oba");
+    /// assert_eq!("foobar".substring_padded(4..8, '.'), "ar..");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn substring_padded<R: SizedRange>(&self, range: R, fill: char) -> Cow<'_, str>;
+
+    /// Returns the character index of the last occurrence of `pat`, or `None` if `pat` does not
+    /// occur.
+    ///
+    /// An empty `pat` matches at the end of the string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbarfõø".rfind_char("õø"), Some(7));
+    /// ```
+    #[must_use]
+    fn rfind_char(&self, pat: &str) -> Option<usize>;
+
+    /// Returns the character range of the last occurrence of `pat`, or `None` if `pat` does not
+    /// occur.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbarfõø".last_match_char_range("õø"), Some(7..9));
+    /// ```
+    #[must_use]
+    fn last_match_char_range(&self, pat: &str) -> Option<Range<usize>>;
+
+    /// Returns the byte range of the `unit`s within `range`.
+    ///
+    /// The range is clamped to the bounds of the string, and bounds landing inside a character are
+    /// snapped inward as described on [`Unit`]. If the range's end is not after its start, the
+    /// returned range is empty and positioned at the start. An empty range is positioned at the
+    /// end of the string if the start is past its end.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, Unit};
+    ///
+    /// assert_eq!("😀a𝔘b".to_byte_range(Unit::Chars, 1..3), 4..9);
+    /// assert_eq!("😀a𝔘b".to_byte_range(Unit::Utf16, 2..5), 4..9);
+    /// assert_eq!("😀a𝔘b".to_byte_range(Unit::Bytes, 1..9), 4..9);
+    /// ```
+    ///
+    /// [`Unit`]: enum.Unit.html
+    #[must_use]
+    fn to_byte_range<R: RangeBounds<usize>>(&self, unit: Unit, range: R) -> Range<usize>;
+
+    /// Returns the range of `unit`s covering the bytes within `byte_range`.
+    ///
+    /// This is the inverse of [`to_byte_range()`]. The byte range is clamped to the bounds of the
+    /// string and snapped inward to character boundaries before being converted.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, Unit};
+    ///
+    /// assert_eq!("😀a𝔘b".from_byte_range(Unit::Chars, 4..9), 1..3);
+    /// assert_eq!("😀a𝔘b".from_byte_range(Unit::Utf16, 4..9), 2..5);
+    /// ```
+    ///
+    /// [`to_byte_range()`]: #tymethod.to_byte_range
+    #[must_use]
+    fn from_byte_range<R: RangeBounds<usize>>(&self, unit: Unit, byte_range: R) -> Range<usize>;
+
+    /// Obtains the characters within a span previously validated against this string.
+    ///
+    /// As the span's byte range was resolved during validation, this takes constant time.
+    ///
+    /// # Panics
+    /// Panics if `span` was validated against a different string. Strings are compared by
+    /// identity, so a span validated against an equal string stored elsewhere is rejected too.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, Span};
+    ///
+    /// let s = "fõøbα®";
+    /// let span = Span::new(2, 5).unwrap().validate(s).unwrap();
+    /// assert_eq!(s.substring_validated(span), "øbα");
+    /// ```
+    #[must_use]
+    fn substring_validated(&self, span: ValidatedSpan<'_>) -> &str;
+
+    /// Returns the number of characters within the bytes of `byte_range`.
+    ///
+    /// The byte range is clamped to the bounds of the string and snapped inward to character
+    /// boundaries, so characters only partially within it are not counted. Only the bytes within
+    /// the range are examined.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// // "õ" occupies bytes 1..3 and "ø" occupies bytes 3..5.
+    /// assert_eq!("fõøbα®".char_count_in(1..5), 2);
+    /// assert_eq!("fõøbα®".char_count_in(2..5), 1);
+    /// ```
+    #[must_use]
+    fn char_count_in<R: RangeBounds<usize>>(&self, byte_range: R) -> usize;
+
+    /// Returns the number of characters within the bytes of `byte_range`, or an error if either
+    /// end of the range does not lie on a character boundary.
+    ///
+    /// A range whose end is before its start contains no characters.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".try_char_count_in(1..5), Ok(2));
+    /// assert_eq!("fõøbα®".try_char_count_in(2..5).unwrap_err().index(), 2);
+    /// ```
+    fn try_char_count_in<R: RangeBounds<usize>>(
+        &self,
+        byte_range: R,
+    ) -> Result<usize, CharBoundaryError>;
+
+    /// Returns the character boundary closest to the byte index `byte_idx` in direction `dir`.
+    ///
+    /// If `byte_idx` is already on a character boundary, it is returned unchanged. Byte indices
+    /// past the end of the string are clamped to its length. Only the bytes adjacent to `byte_idx`
+    /// are examined, so this takes constant time.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, Direction};
+    ///
+    /// // "😀" occupies bytes 1..5.
+    /// assert_eq!("a😀".nearest_char_boundary(2, Direction::Backward), 1);
+    /// assert_eq!("a😀".nearest_char_boundary(2, Direction::Forward), 5);
+    /// assert_eq!("a😀".nearest_char_boundary(4, Direction::Nearest), 5);
+    /// ```
+    #[must_use]
+    fn nearest_char_boundary(&self, byte_idx: usize, dir: Direction) -> usize;
+
+    /// Returns the distance in bytes from the byte index `byte_idx` to the closest character
+    /// boundary.
+    ///
+    /// This is `0` when `byte_idx` is already on a character boundary. For byte indices past the
+    /// end of the string, this is the distance to the end.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("a😀".boundary_distance(1), 0);
+    /// assert_eq!("a😀".boundary_distance(3), 2);
+    /// assert_eq!("a😀".boundary_distance(4), 1);
+    /// ```
+    #[must_use]
+    fn boundary_distance(&self, byte_idx: usize) -> usize;
+
+    /// Returns an iterator over the characters within `range`, from last to first.
+    ///
+    /// The range is clamped to the bounds of the string. Only the bytes up to the end of the range
+    /// are examined.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert!("fõøbα®".chars_in_rev(1..4).eq("bøõ".chars()));
+    /// ```
+    fn chars_in_rev<R: RangeBounds<usize>>(&self, range: R) -> CharsInRev<'_>;
+
+    /// Returns an iterator over the characters within `range` and their character indices, from
+    /// last to first.
+    ///
+    /// The indices are those of the characters within the whole string, not within the range.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert!("fõøbα®"
+    ///     .char_indices_in_rev(1..4)
+    ///     .eq(vec![(3, 'b'), (2, 'ø'), (1, 'õ')]));
+    /// ```
+    fn char_indices_in_rev<R: RangeBounds<usize>>(&self, range: R) -> CharIndicesInRev<'_>;
+
+    /// Returns the character whose bytes contain the byte index `byte_idx`, along with its
+    /// character index and byte range.
+    ///
+    /// Returns `None` if `byte_idx` is not less than the length of the string. The string is only
+    /// walked up to the containing character.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// // "õ" occupies bytes 1..3.
+    /// assert_eq!("fõøbα®".char_containing(2), Some((1, 1..3, 'õ')));
+    /// assert_eq!("fõøbα®".char_containing(10), None);
+    /// ```
+    #[must_use]
+    fn char_containing(&self, byte_idx: usize) -> Option<(usize, Range<usize>, char)>;
+
+    /// Returns a checkpoint at the character index `char_idx`.
+    ///
+    /// Indices past the end of the string are clamped to it.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".checkpoint(2).byte_idx(), 3);
+    /// assert_eq!("fõøbα®".checkpoint(10).char_idx(), 6);
+    /// ```
+    #[must_use]
+    fn checkpoint(&self, char_idx: usize) -> Checkpoint;
+
+    /// Returns an iterator over the characters following `checkpoint`, along with their character
+    /// and byte indices within the whole string.
+    ///
+    /// Returns an error if the checkpoint's byte index is not on a character boundary. Only the
+    /// bytes following the checkpoint are examined.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let s = "fõøbα®";
+    /// let mut indices = s.char_indices_from(s.checkpoint(4)).unwrap();
+    /// assert_eq!(indices.next(), Some((4, 6, 'α')));
+    /// assert_eq!(indices.checkpoint().byte_idx(), 8);
+    /// ```
+    fn char_indices_from(
+        &self,
+        checkpoint: Checkpoint,
+    ) -> Result<CharIndicesFrom<'_>, CharBoundaryError>;
+
+    /// Obtains the characters within `range`, where the range's indices are relative to the
+    /// character index of `checkpoint`.
+    ///
+    /// Returns an error if the checkpoint's byte index is not on a character boundary. Only the
+    /// bytes following the checkpoint are examined, and the range is clamped to the end of the
+    /// string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let s = "fõøbα®";
+    /// assert_eq!(s.substring_from_checkpoint(s.checkpoint(2), 1..3), Ok("bα"));
+    /// ```
+    fn substring_from_checkpoint<R: RangeBounds<usize>>(
+        &self,
+        checkpoint: Checkpoint,
+        range: R,
+    ) -> Result<&str, CharBoundaryError>;
+
+    /// Returns an iterator over the character indices at which a line may break.
+    ///
+    /// A line may break after a run of spaces, after a hyphen-minus, or after a soft hyphen, and
+    /// must break after a newline. No break is offered before a combining mark. Only indices
+    /// strictly within the string are yielded.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let breaks: Vec<usize> = "a well-known fact".break_opportunities().collect();
+    /// assert_eq!(breaks, [2, 7, 13]);
+    /// ```
+    fn break_opportunities(&self) -> BreakOpportunities<'_>;
+
+    /// Returns an iterator over the lines of the string, wrapped greedily at its [break
+    /// opportunities] so that each line holds at most `width_chars` characters.
+    ///
+    /// Spaces at the end of a line are kept with it, but do not count against its width. A run
+    /// of characters without a break opportunity that is wider than `width_chars` is broken
+    /// wherever it overflows, keeping combining marks with the characters they follow. A
+    /// `width_chars` of `0` is treated as `1`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let lines: Vec<&str> = "a well-known fact".wrap_at_opportunities(8).collect();
+    /// assert_eq!(lines, ["a well-", "known ", "fact"]);
+    /// ```
+    ///
+    /// [break opportunities]: #tymethod.break_opportunities
+    fn wrap_at_opportunities(&self, width_chars: usize) -> WrapAtOpportunities<'_>;
+
+    /// Obtains the bytes within `byte_range`, with its start rounded up and its end rounded down
+    /// to character boundaries.
+    ///
+    /// This is [`byte_substring_snapped()`] with [`Snap::Inward`], so partially covered characters
+    /// are excluded. Indices past the end of the string are clamped to it, and a range that is
+    /// empty or reversed after snapping results in an empty string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõø".byte_substring(1..4), "õ");
+    /// assert_eq!("fõø".byte_substring(2..), "ø");
+    /// assert_eq!("fõø".byte_substring(2..4), "");
+    /// ```
+    ///
+    /// [`byte_substring_snapped()`]: #tymethod.byte_substring_snapped
+    /// [`Snap::Inward`]: enum.Snap.html#variant.Inward
+    #[must_use]
+    fn byte_substring<R: RangeBounds<usize>>(&self, byte_range: R) -> &str;
+
+    /// Obtains the bytes within `byte_range`, with its bounds snapped to character boundaries as
+    /// specified by `snap`.
+    ///
+    /// Indices past the end of the string are clamped to it. If the snapped end is before the
+    /// snapped start, an empty slice is returned.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, Snap};
+    ///
+    /// // "😀" occupies bytes 1..5.
+    /// assert_eq!("a😀b".byte_substring_snapped(2..6, Snap::Inward), "b");
+    /// assert_eq!("a😀b".byte_substring_snapped(2..6, Snap::Outward), "😀b");
+    /// ```
+    #[must_use]
+    fn byte_substring_snapped<R: RangeBounds<usize>>(&self, byte_range: R, snap: Snap) -> &str;
+
+    /// Returns a copy of the string with the characters within `range` converted to title case.
+    ///
+    /// Words are identified as by [`word_substring()`], so an apostrophe between two letters, as
+    /// in "don't" or "O'Brien", does not start a new word. The first cased character of each word
+    /// is uppercased, and the word's other characters are lowercased. Words are identified within
+    /// the whole string, so a word starting before `range` has none of its characters within
+    /// `range` uppercased. Characters outside `range`, and characters without case, are left
+    /// unchanged.
+    ///
+    /// Uppercase mappings approximate title case mappings, which differ only for a few digraph
+    /// characters such as "ǆ".
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!(
+    ///     "NAME: JOHN'S DINER".titlecase_char_range(6..),
+    ///     "NAME: John's Diner"
+    /// );
+    /// ```
+    ///
+    /// [`word_substring()`]: trait.WordSubstring.html#tymethod.word_substring
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn titlecase_char_range<R: RangeBounds<usize>>(&self, range: R) -> String;
+
+    /// Obtains the longest run of characters starting at the character index `start_char` that
+    /// all satisfy `pred`.
+    ///
+    /// Characters are only examined from `start_char` onward, and `pred` is not called again after
+    /// the first character not satisfying it. A `start_char` past the end of the string results in
+    /// an empty slice.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("let größe = 42;".span_while(4, char::is_alphanumeric), "größe");
+    /// ```
+    #[must_use]
+    fn span_while<P: FnMut(char) -> bool>(&self, start_char: usize, pred: P) -> &str;
+
+    /// Returns the character range of the longest run of characters starting at the character
+    /// index `start_char` that all satisfy `pred`.
+    ///
+    /// This is the character range of the slice returned by [`span_while()`]. A `start_char` past
+    /// the end of the string results in an empty range at the end of the string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("let größe = 42;".char_range_while(4, char::is_alphanumeric), 4..9);
+    /// ```
+    ///
+    /// [`span_while()`]: #tymethod.span_while
+    #[must_use]
+    fn char_range_while<P: FnMut(char) -> bool>(&self, start_char: usize, pred: P) -> Range<usize>;
+
+    /// Obtains the longest run of characters starting at the character index `start_char` that
+    /// do not satisfy `pred`, stopping before the first character that does.
+    ///
+    /// This is the complement of [`span_while()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("größe = 42;".span_until(0, |c| c == '='), "größe ");
+    /// ```
+    ///
+    /// [`span_while()`]: #tymethod.span_while
+    #[must_use]
+    fn span_until<P: FnMut(char) -> bool>(&self, start_char: usize, pred: P) -> &str;
+
+    /// Returns a copy of the string with each character within `range` replaced by `mask`.
+    ///
+    /// The range is clamped to the bounds of the string. The result has as many characters as the
+    /// string, though its byte length may differ.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("Jürgen".mask_char_range(1.., '*'), "J*****");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn mask_char_range<R: RangeBounds<usize>>(&self, range: R, mask: char) -> String;
+
+    /// Returns a copy of the string with each character within `range` replaced by `mask`, except
+    /// for characters satisfying `keep`.
+    ///
+    /// This is useful for keeping separators in place, so that masked values remain readable. As
+    /// with [`mask_char_range()`], the result has as many characters as the string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!(
+    ///     "4111-1111-1111-1234".mask_char_range_except(..15, '*', |c| c == '-'),
+    ///     "****-****-****-1234"
+    /// );
+    /// ```
+    ///
+    /// [`mask_char_range()`]: #tymethod.mask_char_range
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn mask_char_range_except<R: RangeBounds<usize>, F: Fn(char) -> bool>(
+        &self,
+        range: R,
+        mask: char,
+        keep: F,
+    ) -> String;
+
+    /// Obtains the longest prefix of at most `max_chars` characters that ends at the end of a
+    /// word.
+    ///
+    /// A word ends before a space or newline, after a hyphen-minus, and on either side of an
+    /// ideograph, so that text written without spaces, such as Chinese or Japanese, may be cut
+    /// between any two ideographs. If the first word alone is longer than `max_chars`, the prefix
+    /// is cut after exactly `max_chars` characters instead. Only the characters within the budget
+    /// are examined.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("hello, world".truncate_at_word(10), "hello,");
+    /// assert_eq!("incomprehensible".truncate_at_word(6), "incomp");
+    /// ```
+    #[must_use]
+    fn truncate_at_word(&self, max_chars: usize) -> &str;
+
+    /// Truncates the string at the end of a word as [`truncate_at_word()`] does, appending
+    /// `ellipsis` if anything was removed.
+    ///
+    /// The result, including `ellipsis`, has at most `max_chars` characters. If the whole string
+    /// fits, it is borrowed. If `ellipsis` alone does not fit, as many of its characters as do
+    /// fit are returned.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("hello, world".truncate_at_word_with_ellipsis(10, "…"), "hello,…");
+    /// assert_eq!("hello".truncate_at_word_with_ellipsis(10, "…"), "hello");
+    /// ```
+    ///
+    /// [`truncate_at_word()`]: #tymethod.truncate_at_word
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn truncate_at_word_with_ellipsis(&self, max_chars: usize, ellipsis: &str) -> Cow<'_, str>;
+
+    /// Obtains the characters within `range`, given as indices of UTF-16 code units.
+    ///
+    /// These are the indices used by JavaScript strings and Windows APIs, in which characters
+    /// outside the Basic Multilingual Plane occupy two code units. Bounds landing between the two
+    /// halves of a surrogate pair exclude the character it encodes, as with
+    /// [`SurrogatePolicy::SnapIn`]. Otherwise, the range is clamped as with [`substring()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("😀a𝔘b".utf16_substring(2..5), "a𝔘");
+    /// assert_eq!("😀a𝔘b".utf16_substring(1..4), "a");
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    /// [`SurrogatePolicy::SnapIn`]: enum.SurrogatePolicy.html#variant.SnapIn
+    #[must_use]
+    fn utf16_substring<R: RangeBounds<usize>>(&self, range: R) -> &str;
+
+    /// Obtains the characters within `range`, given as indices of UTF-16 code units, resolving
+    /// bounds landing between the two halves of a surrogate pair according to `policy`.
+    ///
+    /// Returns an error only under [`SurrogatePolicy::Error`], naming the first bound found inside
+    /// a surrogate pair. A range whose end is not after its start is empty, and its end is not
+    /// checked.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, SubstringError, SurrogatePolicy};
+    ///
+    /// let s = "😀a𝔘b";
+    /// assert_eq!(s.utf16_substring_with(1..4, SurrogatePolicy::SnapOut), Ok("😀a𝔘"));
+    /// assert_eq!(
+    ///     s.utf16_substring_with(1..4, SurrogatePolicy::Error),
+    ///     Err(SubstringError::SplitSurrogatePair { index: 1 })
+    /// );
+    /// ```
+    ///
+    /// [`SurrogatePolicy::Error`]: enum.SurrogatePolicy.html#variant.Error
+    fn utf16_substring_with<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+        policy: SurrogatePolicy,
+    ) -> Result<&str, SubstringError>;
+
+    /// Returns an iterator over the character ranges of the non-overlapping case-insensitive
+    /// matches of `query`.
+    ///
+    /// Both the string and `query` are case folded as they are walked, so nothing is allocated.
+    /// Folding lowercases characters, except that "ß" folds to "ss" and the final sigma folds to
+    /// "σ". As folding may change the number of characters, a range describes the matching
+    /// characters of the string, which may differ in number from the characters of `query`. A
+    /// character is never partially matched. An empty `query` has no matches.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let ranges = "Straße and STRASSE".find_fold_char_ranges("strasse").collect::<Vec<_>>();
+    /// assert_eq!(ranges, [0..6, 11..18]);
+    /// ```
+    fn find_fold_char_ranges<'a>(&'a self, query: &'a str) -> FoldMatches<'a>;
+
+    /// Obtains the characters within `start_index..end_index`, or an error if the range is not
+    /// valid for the string.
+    ///
+    /// Unlike [`substring()`], indices past the end of the string are not clamped. An error is
+    /// returned if `start_index` is after `end_index`, or otherwise if either index is past the
+    /// end of the string. Equal indices, including ones at the end of the string, result in an
+    /// empty string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, SubstringError};
+    ///
+    /// assert_eq!("foobar".try_substring(2, 5), Ok("oba"));
+    /// assert_eq!("foobar".try_substring(6, 6), Ok(""));
+    /// assert_eq!(
+    ///     "foobar".try_substring(2, 8),
+    ///     Err(SubstringError::EndOutOfBounds {
+    ///         index: 8,
+    ///         char_len: 6
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    fn try_substring(&self, start_index: usize, end_index: usize) -> Result<&str, SubstringError>;
+
+    /// Obtains the characters within `range`, panicking if the range is not valid for the string.
+    ///
+    /// This matches the semantics of slicing a string by byte range, but with character indices.
+    /// Indices past the end of the string are not clamped, and equal indices, including ones at
+    /// the end of the string, result in an empty string.
+    ///
+    /// # Panics
+    /// Panics if the start of `range` is after its end, or otherwise if either bound is past the
+    /// end of the string. The message names the offending index, as it would be were `usize`
+    /// unbounded, and the number of characters in the string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("foobar".substring_strict(2..5), "oba");
+    /// assert_eq!("foobar".substring_strict(..=2), "foo");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use substring::CharSubstring;
+    ///
+    /// // Panics with "end index 8 is out of bounds of string with 6 characters".
+    /// let _ = "foobar".substring_strict(2..8);
+    /// ```
+    #[must_use]
+    fn substring_strict<R: RangeBounds<usize>>(&self, range: R) -> &str;
+
+    /// Obtains the characters within `range`, with indices counting backward from the end of the
+    /// string.
+    ///
+    /// Index `0` is the end of the string and index `n` lies `n` characters before it, so that
+    /// `0..3` selects the last three characters, as `[-3:]` would in Python. The range is clamped
+    /// to the start of the string, and a range whose end is not after its start results in an
+    /// empty string, positioned at its start. Only the characters from the end of the string up to
+    /// the furthest bound are walked.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("foobar".substring_from_end(0..3), "bar");
+    /// assert_eq!("foobar".substring_from_end(1..3), "ba");
+    /// assert_eq!("fõøbα®".substring_from_end(2..), "fõøb");
+    /// ```
+    #[must_use]
+    fn substring_from_end<R: RangeBounds<usize>>(&self, range: R) -> &str;
+
+    /// Returns the byte range of the characters within `range`.
+    ///
+    /// This is exactly the byte range sliced by [`substring()`], so `&s[s.char_byte_range(r)]`
+    /// always equals the corresponding substring. It is shorthand for [`to_byte_range()`] with
+    /// [`Unit::Chars`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharSubstring, Substring};
+    ///
+    /// let s = "fõøbα®";
+    /// assert_eq!(s.char_byte_range(2..5), 3..8);
+    /// assert_eq!(&s[s.char_byte_range(2..5)], s.substring(2, 5));
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    /// [`to_byte_range()`]: #tymethod.to_byte_range
+    /// [`Unit::Chars`]: enum.Unit.html#variant.Chars
+    #[must_use]
+    fn char_byte_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize>;
+
+    /// Divides the string into two at the character index `n`.
+    ///
+    /// The first slice contains the first `n` characters, and the second contains the rest. If `n`
+    /// is past the end of the string, the whole string is the first slice and the second is empty.
+    /// Together, the two slices always make up the original string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbar".split_at_char(2), ("fõ", "øbar"));
+    /// assert_eq!("fõøbar".split_at_char(10), ("fõøbar", ""));
+    /// ```
+    #[must_use]
+    fn split_at_char(&self, n: usize) -> (&str, &str);
+
+    /// Divides the string into two at the character index `n`, returning `None` if `n` is past the
+    /// end of the string.
+    ///
+    /// An `n` equal to the number of characters is not past the end, and results in the whole
+    /// string and an empty slice. Otherwise, this behaves as [`split_at_char()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbar".try_split_at_char(6), Some(("fõøbar", "")));
+    /// assert_eq!("fõøbar".try_split_at_char(7), None);
+    /// ```
+    ///
+    /// [`split_at_char()`]: #tymethod.split_at_char
+    #[must_use]
+    fn try_split_at_char(&self, n: usize) -> Option<(&str, &str)>;
+
+    /// Returns an iterator over substrings of `chunk_size` characters, in order.
+    ///
+    /// Every substring contains exactly `chunk_size` characters, except the last, which contains
+    /// the remaining characters. The string is walked once in total, rather than once per
+    /// substring. An empty string has no substrings.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` is `0`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert!("fõøbα®b".char_chunks(3).eq(["fõø", "bα®", "b"].iter().cloned()));
+    /// ```
+    fn char_chunks(&self, chunk_size: usize) -> CharChunks<'_>;
+
+    /// Returns an iterator over the overlapping substrings of `size` characters, advancing by one
+    /// character at a time.
+    ///
+    /// The string is walked once in total. If it contains fewer than `size` characters, there are
+    /// no substrings.
+    ///
+    /// # Panics
+    /// Panics if `size` is `0`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert!("fõøbα".char_windows(2).eq(["fõ", "õø", "øb", "bα"].iter().cloned()));
+    /// ```
+    fn char_windows(&self, size: usize) -> CharWindows<'_>;
+
+    /// Obtains the first `n` characters, or the whole string if it has fewer than `n` characters.
+    ///
+    /// Only the first `n` characters are examined.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".take_chars(3), "fõø");
+    /// assert_eq!("fõøbα®".take_chars(10), "fõøbα®");
+    /// ```
+    #[must_use]
+    fn take_chars(&self, n: usize) -> &str;
+
+    /// Obtains everything after the first `n` characters, or an empty string at the end of the
+    /// string if it has fewer than `n` characters.
+    ///
+    /// Only the first `n` characters are examined; the rest of the string is not walked.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".skip_chars(3), "bα®");
+    /// assert_eq!("fõøbα®".skip_chars(10), "");
+    /// ```
+    #[must_use]
+    fn skip_chars(&self, n: usize) -> &str;
+
+    /// Returns a value which formats the characters within `range`, without allocating.
+    ///
+    /// The range is clamped to the bounds of the string exactly as with [`substring()`], and is
+    /// only resolved when the value is formatted.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let s = "fõøbα®";
+    /// assert_eq!(format!("{}...", s.display_substring(..3)), "fõø...");
+    /// assert_eq!(format!("{:>4}", s.display_substring(4..)), "  α®");
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    fn display_substring<R: RangeBounds<usize>>(&self, range: R) -> SubstringDisplay<'_, R>;
+
+    /// Truncates the string to at most `max_chars` characters, marking a truncation with
+    /// `suffix`.
+    ///
+    /// A string of at most `max_chars` characters is borrowed unchanged. Otherwise, the result is
+    /// the longest prefix which leaves room for `suffix`, followed by `suffix`, and has exactly
+    /// `max_chars` characters. If `suffix` alone is longer than `max_chars`, the result is its
+    /// first `max_chars` characters. This is shorthand for a [`Truncator`] with a budget of
+    /// `max_chars` and an ellipsis of `suffix`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".truncate_chars_with(5, "…"), "fõøb…");
+    /// assert_eq!("fõøbα®".truncate_chars_with(6, "…"), "fõøbα®");
+    /// ```
+    ///
+    /// [`Truncator`]: struct.Truncator.html
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn truncate_chars_with(&self, max_chars: usize, suffix: &str) -> Cow<'_, str>;
+
+    /// Obtains a string slice spanning the display columns within `range`.
+    ///
+    /// Columns are counted as a terminal displays text, so that East Asian wide characters and
+    /// most emoji occupy two columns, combining marks and other zero width characters occupy
+    /// none, and other characters occupy one, approximating [UAX #11] without its tables. A wide
+    /// character cut by the start of the range is skipped, and a wide character cut by the end of
+    /// the range is excluded, so the result never occupies more columns than the range spans.
+    /// Combining marks are kept with the character they follow. The range is clamped to the width
+    /// of the string, and a range whose end is not after its start results in an empty string,
+    /// positioned at the start.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("日本語".width_substring(2..4), "本");
+    /// assert_eq!("日本語".width_substring(1..5), "本");
+    /// assert_eq!("cafe\u{301}!".width_substring(3..), "e\u{301}!");
+    /// ```
+    ///
+    /// [UAX #11]: https://www.unicode.org/reports/tr11/
+    #[must_use]
+    fn width_substring<R: RangeBounds<usize>>(&self, range: R) -> &str;
+
+    /// Returns the longest prefix of the string that fits within `max_cols` display columns.
+    ///
+    /// This is shorthand for [`width_substring(..max_cols)`], so a wide character which would
+    /// only partly fit is excluded.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("日本語".truncate_to_width(5), "日本");
+    /// assert_eq!("foobar".truncate_to_width(3), "foo");
+    /// ```
+    ///
+    /// [`width_substring(..max_cols)`]: #tymethod.width_substring
+    #[must_use]
+    fn truncate_to_width(&self, max_cols: usize) -> &str;
+
+    /// Obtains up to `len` characters, beginning with the character at index `start`.
+    ///
+    /// This is the form of substring taking a start and a length, rather than a start and an end.
+    /// If `start` is past the end of the string, an empty string positioned at its end is
+    /// returned. Otherwise, the result ends at the end of the string if fewer than `len`
+    /// characters follow `start`, including when `start + len` would overflow a `usize`. The
+    /// string is walked once, up to the end of the result.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".substring_len(1, 3), "õøb");
+    /// assert_eq!("fõøbα®".substring_len(4, 10), "α®");
+    /// assert_eq!("fõøbα®".substring_len(3, usize::max_value()), "bα®");
+    /// ```
+    #[must_use]
+    fn substring_len(&self, start: usize, len: usize) -> &str;
+
+    /// Obtains exactly `len` characters, beginning with the character at index `start`, panicking
+    /// if they are not all within the string.
+    ///
+    /// This is the length-based form of [`substring_strict()`].
+    ///
+    /// # Panics
+    /// Panics if `start + len` is greater than the number of characters in the string. The
+    /// message names the offending index, as it would be were `usize` unbounded, and the number
+    /// of characters in the string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("foobar".substring_len_strict(2, 3), "oba");
+    /// assert_eq!("foobar".substring_len_strict(6, 0), "");
+    /// ```
+    ///
+    /// ```should_panic
+    /// use substring::CharSubstring;
+    ///
+    /// // Panics with "end index 8 is out of bounds of string with 6 characters".
+    /// let _ = "foobar".substring_len_strict(2, 6);
+    /// ```
+    ///
+    /// [`substring_strict()`]: #tymethod.substring_strict
+    #[must_use]
+    fn substring_len_strict(&self, start: usize, len: usize) -> &str;
+
+    /// Obtains the text between the first occurrence of `start` and the first occurrence of `end`
+    /// after it.
+    ///
+    /// Neither delimiter is included in the result. If `start` does not occur, or `end` does not
+    /// occur after it, `None` is returned. Adjacent delimiters result in an empty string,
+    /// positioned between them.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("key=[value];".substring_between('[', ']'), Some("value"));
+    /// assert_eq!("<b>bold</b>".substring_between("<b>", "</b>"), Some("bold"));
+    /// assert_eq!("key=[];".substring_between('[', ']'), Some(""));
+    /// assert_eq!("key=[value;".substring_between('[', ']'), None);
+    /// ```
+    #[must_use]
+    fn substring_between<S: Delimiter, E: Delimiter>(&self, start: S, end: E) -> Option<&str>;
+
+    /// Obtains the text before the first occurrence of `delimiter`, or `None` if it does not
+    /// occur.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("key:value:more".substring_before(':'), Some("key"));
+    /// assert_eq!("key value".substring_before(':'), None);
+    /// ```
+    #[must_use]
+    fn substring_before<D: Delimiter>(&self, delimiter: D) -> Option<&str>;
+
+    /// Obtains the text before the last occurrence of `delimiter`, or `None` if it does not occur.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("path/to/file".substring_before_last('/'), Some("path/to"));
+    /// ```
+    #[must_use]
+    fn substring_before_last<D: Delimiter>(&self, delimiter: D) -> Option<&str>;
+
+    /// Obtains the text after the first occurrence of `delimiter`, or `None` if it does not occur.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("key:value:more".substring_after(':'), Some("value:more"));
+    /// ```
+    #[must_use]
+    fn substring_after<D: Delimiter>(&self, delimiter: D) -> Option<&str>;
+
+    /// Obtains the text after the last occurrence of `delimiter`, or `None` if it does not occur.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("path/to/file".substring_after_last('/'), Some("file"));
+    /// assert_eq!("path::to::item".substring_after_last("::"), Some("item"));
+    /// ```
+    #[must_use]
+    fn substring_after_last<D: Delimiter>(&self, delimiter: D) -> Option<&str>;
+
+    /// Obtains the characters within `range`, where the bounds may be of any integer type.
+    ///
+    /// This behaves as [`substring()`] does, for indices which can be represented as a `usize`.
+    /// A negative start bound is treated as `0`, and a start bound too large for a `usize` is
+    /// past the end of any string. A negative end bound results in an empty string, and an end
+    /// bound too large for a `usize` is treated as unbounded. Use [`try_substring_idx()`] to
+    /// reject such bounds instead.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".substring_idx(2u32..5u32), "øbα");
+    /// assert_eq!("fõøbα®".substring_idx(-3i64..2i64), "fõ");
+    /// assert_eq!("fõøbα®".substring_idx(..-1i32), "");
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    /// [`try_substring_idx()`]: #tymethod.try_substring_idx
+    #[must_use]
+    fn substring_idx<T: CharIndex, R: RangeBounds<T>>(&self, range: R) -> &str;
+
+    /// Obtains the characters within `range`, where the bounds may be of any integer type,
+    /// returning an error if either bound cannot be represented as a `usize`.
+    ///
+    /// Bounds which can be represented are clamped to the string as with [`substring_idx()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".try_substring_idx(2u32..5u32), Ok("øbα"));
+    /// assert_eq!(
+    ///     "fõøbα®"
+    ///         .try_substring_idx(-3i64..2i64)
+    ///         .map_err(|error| error.index()),
+    ///     Err(-3)
+    /// );
+    /// ```
+    ///
+    /// [`substring_idx()`]: #tymethod.substring_idx
+    fn try_substring_idx<T: CharIndex, R: RangeBounds<T>>(
+        &self,
+        range: R,
+    ) -> Result<&str, IndexConversionError>;
+
+    /// Returns the byte index at which the character with index `n` begins.
+    ///
+    /// If `n` is the number of characters in the string, its length is returned. If `n` is
+    /// greater, `None` is returned. This is the inverse of [`byte_to_char_index()`] on character
+    /// boundaries.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".char_to_byte_index(2), Some(3));
+    /// assert_eq!("fõøbα®".char_to_byte_index(6), Some(10));
+    /// assert_eq!("fõøbα®".char_to_byte_index(7), None);
+    /// ```
+    ///
+    /// [`byte_to_char_index()`]: #tymethod.byte_to_char_index
+    #[must_use]
+    fn char_to_byte_index(&self, n: usize) -> Option<usize>;
+
+    /// Returns the index of the character whose bytes contain the byte index `byte_idx`.
+    ///
+    /// A byte index within a multi-byte character gives the index of that character. The length
+    /// of the string gives the number of characters in it, and `None` is returned for byte indices
+    /// past the end. Characters are not decoded; the bytes before `byte_idx` which begin
+    /// characters are counted instead.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// // "õ" occupies bytes 1..3.
+    /// assert_eq!("fõøbα®".byte_to_char_index(1), Some(1));
+    /// assert_eq!("fõøbα®".byte_to_char_index(2), Some(1));
+    /// assert_eq!("fõøbα®".byte_to_char_index(10), Some(6));
+    /// assert_eq!("fõøbα®".byte_to_char_index(11), None);
+    /// ```
+    #[must_use]
+    fn byte_to_char_index(&self, byte_idx: usize) -> Option<usize>;
+
+    /// Returns an iterator over the substrings within each of `ranges`, in order.
+    ///
+    /// Each substring is the one [`substring()`] would return for the same range, including how
+    /// the range is clamped. Ranges may overlap. The walk for each range continues from the
+    /// furthest bound of the previous range before its start, so ranges sorted by start are
+    /// found in a single walk of the string in total. A range starting before the previous one
+    /// is found by walking from the start of the string again.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let fields = "fõø  bα®  123".substrings(&[0..3, 5..8, 10..13]);
+    /// assert!(fields.eq(["fõø", "bα®", "123"].iter().cloned()));
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    fn substrings<'a, 'r>(&'a self, ranges: &'r [Range<usize>]) -> Substrings<'a, 'r>;
+
+    /// Returns the longest prefix of the string which is at most `max_bytes` bytes long.
+    ///
+    /// The prefix ends on a character boundary, so a character which would only partly fit is
+    /// excluded. If `max_bytes` is at least the length of the string, the whole string is
+    /// returned.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// // "õ" occupies bytes 1..3.
+    /// assert_eq!("fõøbα®".floor_substring_to_bytes(2), "f");
+    /// assert_eq!("fõøbα®".floor_substring_to_bytes(3), "fõ");
+    /// ```
+    #[must_use]
+    fn floor_substring_to_bytes(&self, max_bytes: usize) -> &str;
+
+    /// Returns the shortest prefix of the string which is at least `min_bytes` bytes long, or the
+    /// whole string if it is shorter.
+    ///
+    /// The prefix ends on a character boundary, so a character which would only partly fit is
+    /// included.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// // "õ" occupies bytes 1..3.
+    /// assert_eq!("fõøbα®".ceil_substring_to_bytes(2), "fõ");
+    /// assert_eq!("fõøbα®".ceil_substring_to_bytes(20), "fõøbα®");
+    /// ```
+    #[must_use]
+    fn ceil_substring_to_bytes(&self, min_bytes: usize) -> &str;
+
+    /// Returns the longest suffix of the string which is at most `max_bytes` bytes long.
+    ///
+    /// The suffix begins on a character boundary, so a character which would only partly fit is
+    /// excluded.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// // "®" occupies bytes 8..10.
+    /// assert_eq!("fõøbα®".floor_suffix_to_bytes(3), "®");
+    /// assert_eq!("fõøbα®".floor_suffix_to_bytes(4), "α®");
+    /// ```
+    #[must_use]
+    fn floor_suffix_to_bytes(&self, max_bytes: usize) -> &str;
+
+    /// Returns the character with index `n`, or `None` if the string has no more than `n`
+    /// characters.
+    ///
+    /// The string is only walked up to the character.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".char_at(4), Some('α'));
+    /// assert_eq!("fõøbα®".char_at(6), None);
+    /// ```
+    #[must_use]
+    fn char_at(&self, n: usize) -> Option<char>;
+
+    /// Returns the last character of the string, or `None` if it is empty.
+    ///
+    /// Only the bytes of the last character are examined.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".last_char(), Some('®'));
+    /// assert_eq!("".last_char(), None);
+    /// ```
+    #[must_use]
+    fn last_char(&self) -> Option<char>;
+
+    /// Returns a lazy iterator over the characters within `range`.
+    ///
+    /// The characters before the range are skipped, and then at most as many characters as the
+    /// range contains are yielded, walking the string only as far as they are consumed. Iterating
+    /// from the back first walks to the end of the range. The characters yielded are always those
+    /// of [`substring()`] for the same range.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let mut chars = "fõøbα®".substring_chars(1..1_000_000);
+    /// assert_eq!(chars.next(), Some('õ'));
+    /// assert_eq!(chars.next_back(), Some('®'));
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    fn substring_chars<R: RangeBounds<usize>>(&self, range: R) -> SubstringChars<'_>;
+
+    /// Returns a lazy iterator over the characters within `range` and their byte indices.
+    ///
+    /// Byte indices count from the start of the string. Characters are found as with
+    /// [`substring_chars()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// let mut indices = "fõøbα®".substring_char_indices(1..3);
+    /// assert_eq!(indices.next(), Some((1, 'õ')));
+    /// assert_eq!(indices.next(), Some((3, 'ø')));
+    /// assert_eq!(indices.next(), None);
+    /// ```
+    ///
+    /// [`substring_chars()`]: #tymethod.substring_chars
+    fn substring_char_indices<R: RangeBounds<usize>>(&self, range: R) -> SubstringCharIndices<'_>;
+
+    /// Divides the string into the text before `range`, the characters within it, and the text
+    /// after it.
+    ///
+    /// The middle slice is the one [`substring()`] would return for the same range, and the three
+    /// slices together always make up the original string. The string is walked once, up to the
+    /// end of the range.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõøbα®".partition_at_char_range(2..4), ("fõ", "øb", "α®"));
+    /// assert_eq!("fõøbα®".partition_at_char_range(4..10), ("fõøb", "α®", ""));
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    fn partition_at_char_range<R: RangeBounds<usize>>(&self, range: R) -> (&str, &str, &str);
+
+    /// Obtains a string slice spanning the segments within `range`, where the string is divided
+    /// into segments by `segmenter`.
+    ///
+    /// The slice extends from the start of the first segment to the end of the last segment,
+    /// including any text between them, but no text before the first segment or after the last.
+    /// The range is clamped to the number of segments in the string, and a range whose end is not
+    /// after its start results in an empty string, positioned at the start of the segment it
+    /// starts at. Segmenting [`ByChars`] gives the same results as [`substring()`].
+    ///
+    /// # Panics
+    /// Panics if `segmenter` returns a segment which does not satisfy the requirements of
+    /// [`Segmenter::next_segment()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{ByChars, ByWords, CharSubstring};
+    ///
+    /// assert_eq!("fõøbα®".substring_by(ByChars, 1..3), "õø");
+    /// assert_eq!("fõø, bαr baz".substring_by(ByWords, ..2), "fõø, bαr");
+    /// ```
+    ///
+    /// [`ByChars`]: struct.ByChars.html
+    /// [`Segmenter::next_segment()`]: trait.Segmenter.html#tymethod.next_segment
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    fn substring_by<S: Segmenter, R: RangeBounds<usize>>(&self, segmenter: S, range: R) -> &str;
+
+    /// Fits the string to exactly `width` characters, truncating it or padding it at the end
+    /// with `fill`.
+    ///
+    /// A string longer than `width` is truncated to its first `width` characters, as
+    /// [`substring()`] would truncate it, and is borrowed, as is a string of exactly `width`
+    /// characters. This is [`substring_padded()`] over `0..width`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõ".pad_chars(5, ' '), "fõ   ");
+    /// assert_eq!("fõøbar".pad_chars(3, ' '), "fõø");
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    /// [`substring_padded()`]: #tymethod.substring_padded
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn pad_chars(&self, width: usize, fill: char) -> Cow<'_, str>;
+
+    /// Fits the string to exactly `width` characters, truncating it or padding it on both sides
+    /// with `fill`.
+    ///
+    /// A string with fewer than `width` characters is centered, with any odd padding character
+    /// placed after it. A longer string is truncated in the same way as by [`pad_chars()`], and is
+    /// borrowed, as is a string of exactly `width` characters.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharSubstring;
+    ///
+    /// assert_eq!("fõ".center_chars(5, '·'), "·fõ··");
+    /// assert_eq!("fõøbar".center_chars(3, '·'), "fõø");
+    /// ```
+    ///
+    /// [`pad_chars()`]: #tymethod.pad_chars
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    fn center_chars(&self, width: usize, fill: char) -> Cow<'_, str>;
+}
+
+impl CharSubstring for str {
+    #[cfg(feature = "alloc")]
+    fn substring_padded<R: SizedRange>(&self, range: R, fill: char) -> Cow<'_, str> {
+        let resolved = bounds::resolve(&range);
+        let start = resolved.start;
+        // A `SizedRange` always has a bounded end.
+        let end = resolved.end.unwrap_or(start);
+        let len = end.saturating_sub(start);
+
+        let start_byte = self
+            .char_indices()
+            .nth(start)
+            .map_or(self.len(), |(index, _char)| index);
+        // SAFETY: `start_byte` was obtained from the `CharIndices` of `self`, or is the length of
+        // `self`, and is therefore on a UTF-8 sequence boundary.
+        let tail = slice_unchecked!(self, start_byte, self.len());
+
+        let mut covered = 0;
+        let mut end_byte = tail.len();
+        for (index, _char) in tail.char_indices() {
+            if covered == len {
+                end_byte = index;
+                break;
+            }
+            covered += 1;
+        }
+        // SAFETY: `end_byte` was obtained from the `CharIndices` of `tail`, or is the length of
+        // `tail`.
+        let covered_str = slice_unchecked!(tail, 0, end_byte);
+
+        if covered == len {
+            Cow::Borrowed(covered_str)
+        } else {
+            let mut padded =
+                String::with_capacity(covered_str.len() + (len - covered) * fill.len_utf8());
+            padded.push_str(covered_str);
+            for _ in covered..len {
+                padded.push(fill);
+            }
+            Cow::Owned(padded)
+        }
+    }
+
+    fn rfind_char(&self, pat: &str) -> Option<usize> {
+        // Searching backward by bytes means only the prefix preceding the match needs to be
+        // walked to obtain its character index.
+        self.rfind(pat).map(|byte_index| {
+            // SAFETY: `rfind()` always returns an index on a UTF-8 sequence boundary.
+            slice_unchecked!(self, 0, byte_index).chars().count()
+        })
+    }
+
+    fn last_match_char_range(&self, pat: &str) -> Option<Range<usize>> {
+        self.rfind_char(pat)
+            .map(|start| start..(start + pat.chars().count()))
+    }
+
+    fn to_byte_range<R: RangeBounds<usize>>(&self, unit: Unit, range: R) -> Range<usize> {
+        let resolved = bounds::resolve(&range);
+        unit::to_byte_range(self, unit, resolved.start, resolved.end)
+    }
+
+    fn from_byte_range<R: RangeBounds<usize>>(&self, unit: Unit, byte_range: R) -> Range<usize> {
+        let resolved = bounds::resolve(&byte_range);
+        unit::from_byte_range(self, unit, resolved.start, resolved.end)
+    }
+
+    fn substring_validated(&self, span: ValidatedSpan<'_>) -> &str {
+        assert!(
+            span.is_validated_against(self),
+            "span was validated against a different string"
+        );
+        // SAFETY: The span's byte range was resolved from the `CharIndices` of this same string.
+        slice_unchecked!(self, span.byte_range().start, span.byte_range().end)
+    }
+
+    fn char_count_in<R: RangeBounds<usize>>(&self, byte_range: R) -> usize {
+        let resolved = bounds::resolve(&byte_range);
+        let bytes = unit::bytes_to_byte_range(self, resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, bytes.start, bytes.end)
+            .chars()
+            .count()
+    }
+
+    fn try_char_count_in<R: RangeBounds<usize>>(
+        &self,
+        byte_range: R,
+    ) -> Result<usize, CharBoundaryError> {
+        let resolved = bounds::resolve(&byte_range);
+        let start = resolved.start;
+        let end = resolved.end.unwrap_or(self.len());
+        if !self.is_char_boundary(start) {
+            return Err(CharBoundaryError::new(start));
+        }
+        if !self.is_char_boundary(end) {
+            return Err(CharBoundaryError::new(end));
+        }
+        if end <= start {
+            return Ok(0);
+        }
+        // SAFETY: `start` and `end` were checked to lie on UTF-8 sequence boundaries.
+        Ok(slice_unchecked!(self, start, end).chars().count())
+    }
+
+    fn chars_in_rev<R: RangeBounds<usize>>(&self, range: R) -> CharsInRev<'_> {
+        let (window, _, _) = char_window(self, range);
+        CharsInRev::new(window)
+    }
+
+    fn char_indices_in_rev<R: RangeBounds<usize>>(&self, range: R) -> CharIndicesInRev<'_> {
+        let (window, start, len) = char_window(self, range);
+        CharIndicesInRev::new(window, start + len)
+    }
+
+    fn char_containing(&self, byte_idx: usize) -> Option<(usize, Range<usize>, char)> {
+        self.char_indices()
+            .enumerate()
+            .find(|&(_, (index, c))| byte_idx < index + c.len_utf8())
+            .map(|(char_index, (index, c))| (char_index, index..(index + c.len_utf8()), c))
+    }
+
+    fn nearest_char_boundary(&self, byte_idx: usize, dir: Direction) -> usize {
+        boundary::nearest_char_boundary(self, byte_idx, dir)
+    }
+
+    fn boundary_distance(&self, byte_idx: usize) -> usize {
+        let nearest = boundary::nearest_char_boundary(self, byte_idx, Direction::Nearest);
+        if nearest > byte_idx {
+            nearest - byte_idx
+        } else {
+            byte_idx - nearest
+        }
+    }
+
+    fn checkpoint(&self, char_idx: usize) -> Checkpoint {
+        let mut count = 0;
+        for (index, _char) in self.char_indices() {
+            if count == char_idx {
+                return Checkpoint::new_unchecked(count, index);
+            }
+            count += 1;
+        }
+        Checkpoint::new_unchecked(count, self.len())
+    }
+
+    fn char_indices_from(
+        &self,
+        checkpoint: Checkpoint,
+    ) -> Result<CharIndicesFrom<'_>, CharBoundaryError> {
+        checkpoint.check(self)?;
+        // SAFETY: The checkpoint's byte index was checked to lie on a UTF-8 sequence boundary.
+        let tail = slice_unchecked!(self, checkpoint.byte_idx(), self.len());
+        Ok(CharIndicesFrom::new(tail, checkpoint))
+    }
+
+    fn substring_from_checkpoint<R: RangeBounds<usize>>(
+        &self,
+        checkpoint: Checkpoint,
+        range: R,
+    ) -> Result<&str, CharBoundaryError> {
+        checkpoint.check(self)?;
+        // SAFETY: The checkpoint's byte index was checked to lie on a UTF-8 sequence boundary.
+        let tail = slice_unchecked!(self, checkpoint.byte_idx(), self.len());
+        let resolved = bounds::resolve(&range);
+        let bytes = char_range_to_byte_range(tail, resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `tail`.
+        Ok(slice_unchecked!(tail, bytes.start, bytes.end))
+    }
+
+    fn break_opportunities(&self) -> BreakOpportunities<'_> {
+        BreakOpportunities::new(self)
+    }
+
+    fn wrap_at_opportunities(&self, width_chars: usize) -> WrapAtOpportunities<'_> {
+        WrapAtOpportunities::new(self, width_chars)
+    }
+
+    fn byte_substring<R: RangeBounds<usize>>(&self, byte_range: R) -> &str {
+        let bytes = self.to_byte_range(Unit::Bytes, byte_range);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, bytes.start, bytes.end)
+    }
+
+    fn byte_substring_snapped<R: RangeBounds<usize>>(&self, byte_range: R, snap: Snap) -> &str {
+        let resolved = bounds::resolve(&byte_range);
+        let bytes = boundary::snap_byte_range(self, resolved.start, resolved.end, snap);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, bytes.start, bytes.end)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn titlecase_char_range<R: RangeBounds<usize>>(&self, range: R) -> String {
+        let resolved = bounds::resolve(&range);
+        let start = resolved.start;
+        let end = resolved.end;
+
+        let mut result = String::with_capacity(self.len());
+        let mut copied = 0;
+        let mut words = word::WordState::Outside;
+        // Whether a cased character has been seen in the current word.
+        let mut word_cased = false;
+        for (char_index, (byte_index, c)) in self.char_indices().enumerate() {
+            if end.map_or(false, |end| char_index >= end) {
+                break;
+            }
+            let (next, starts_word) = words.next(c);
+            words = next;
+            if starts_word {
+                word_cased = false;
+            }
+            let first_cased = !word_cased && (c.is_uppercase() || c.is_lowercase());
+            if first_cased {
+                word_cased = true;
+            }
+            if char_index < start {
+                continue;
+            }
+            result.push_str(&self[copied..byte_index]);
+            copied = byte_index + c.len_utf8();
+            if first_cased {
+                result.extend(c.to_uppercase());
+            } else {
+                result.extend(c.to_lowercase());
+            }
+        }
+        result.push_str(&self[copied..]);
+        result
+    }
+
+    fn span_while<P: FnMut(char) -> bool>(&self, start_char: usize, pred: P) -> &str {
+        char_run(self, start_char, pred).0
+    }
+
+    fn char_range_while<P: FnMut(char) -> bool>(&self, start_char: usize, pred: P) -> Range<usize> {
+        char_run(self, start_char, pred).1
+    }
+
+    fn span_until<P: FnMut(char) -> bool>(&self, start_char: usize, mut pred: P) -> &str {
+        char_run(self, start_char, |c| !pred(c)).0
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mask_char_range<R: RangeBounds<usize>>(&self, range: R, mask: char) -> String {
+        self.mask_char_range_except(range, mask, |_| false)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn mask_char_range_except<R: RangeBounds<usize>, F: Fn(char) -> bool>(
+        &self,
+        range: R,
+        mask: char,
+        keep: F,
+    ) -> String {
+        let resolved = bounds::resolve(&range);
+        let bytes = char_range_to_byte_range(self, resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        let (head, masked, tail) = (
+            slice_unchecked!(self, 0, bytes.start),
+            slice_unchecked!(self, bytes.start, bytes.end),
+            slice_unchecked!(self, bytes.end, self.len()),
+        );
+
+        let mut result = String::with_capacity(self.len());
+        result.push_str(head);
+        result.extend(masked.chars().map(|c| if keep(c) { c } else { mask }));
+        result.push_str(tail);
+        result
+    }
+
+    fn truncate_at_word(&self, max_chars: usize) -> &str {
+        let end = wrap::word_truncation_end(self, max_chars);
+        // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        slice_unchecked!(self, 0, end)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn truncate_at_word_with_ellipsis(&self, max_chars: usize, ellipsis: &str) -> Cow<'_, str> {
+        let whole = self.truncate_at_word(max_chars);
+        if whole.len() == self.len() {
+            return Cow::Borrowed(self);
+        }
+
+        let ellipsis_len = ellipsis.chars().count();
+        if ellipsis_len >= max_chars {
+            return Cow::Owned(ellipsis.chars().take(max_chars).collect());
+        }
+        let head = self.truncate_at_word(max_chars - ellipsis_len);
+        let mut result = String::with_capacity(head.len() + ellipsis.len());
+        result.push_str(head);
+        result.push_str(ellipsis);
+        Cow::Owned(result)
+    }
+
+    fn utf16_substring<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        let bytes = self.to_byte_range(Unit::Utf16, range);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, bytes.start, bytes.end)
+    }
+
+    fn utf16_substring_with<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+        policy: SurrogatePolicy,
+    ) -> Result<&str, SubstringError> {
+        let resolved = bounds::resolve(&range);
+        let bytes = utf16::utf16_range_to_byte_range(self, resolved.start, resolved.end, policy)?;
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        Ok(slice_unchecked!(self, bytes.start, bytes.end))
+    }
+
+    fn find_fold_char_ranges<'a>(&'a self, query: &'a str) -> FoldMatches<'a> {
+        FoldMatches::new(self, query)
+    }
+
+    fn try_substring(&self, start_index: usize, end_index: usize) -> Result<&str, SubstringError> {
+        if start_index > end_index {
+            return Err(SubstringError::StartAfterEnd {
+                start: start_index,
+                end: end_index,
+            });
+        }
+
+        let mut start_byte = None;
+        let mut char_len = 0;
+        for (index, _char) in self.char_indices() {
+            if char_len == start_index {
+                start_byte = Some(index);
+            }
+            if char_len == end_index {
+                // SAFETY: Both byte indices were obtained from the `CharIndices` of `self`.
+                return Ok(slice_unchecked!(self, start_byte.unwrap_or(index), index));
+            }
+            char_len += 1;
+        }
+        if start_index > char_len {
+            return Err(SubstringError::StartOutOfBounds {
+                index: start_index,
+                char_len,
+            });
+        }
+        if end_index > char_len {
+            return Err(SubstringError::EndOutOfBounds {
+                index: end_index,
+                char_len,
+            });
+        }
+        // SAFETY: `start_byte` was obtained from the `CharIndices` of `self`, or is its length.
+        Ok(slice_unchecked!(
+            self,
+            start_byte.unwrap_or(self.len()),
+            self.len()
+        ))
+    }
+
+    fn substring_strict<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        let resolved = bounds::resolve(&range);
+        // Indices are widened to undo any overflow, so that bounds such as `Excluded(usize::MAX)`
+        // are reported exactly.
+        let start = resolved.start as u128 + u128::from(resolved.start_overflowed);
+        let end = resolved
+            .end
+            .map(|end| end as u128 + u128::from(resolved.end_overflowed));
+        if resolved.reversed {
+            panic!(
+                "start index {} is after end index {} when slicing string with {} characters",
+                start,
+                end.unwrap_or(0),
+                self.chars().count()
+            );
+        }
+
+        let mut char_len: u128 = 0;
+        let mut start_byte = None;
+        let mut end_byte = None;
+        for (index, _char) in self.char_indices() {
+            if char_len == start {
+                start_byte = Some(index);
+            }
+            if Some(char_len) == end {
+                end_byte = Some(index);
+                break;
+            }
+            char_len += 1;
+        }
+        // If the end was not found, the whole string was walked and `char_len` is its length.
+        let start_byte = match start_byte {
+            Some(start_byte) => start_byte,
+            None if start == char_len => self.len(),
+            None => panic!(
+                "start index {} is out of bounds of string with {} characters",
+                start, char_len
+            ),
+        };
+        let end_byte = match (end_byte, end) {
+            (Some(end_byte), _) => end_byte,
+            (None, Some(end)) if end != char_len => panic!(
+                "end index {} is out of bounds of string with {} characters",
+                end, char_len
+            ),
+            (None, _) => self.len(),
+        };
+        // SAFETY: Both byte indices were obtained from the `CharIndices` of `self`, or are its
+        // length.
+        slice_unchecked!(self, start_byte, end_byte)
+    }
+
+    fn substring_from_end<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        let resolved = bounds::resolve(&range);
+        // The characters furthest from the end are reached last, so their position is the end of
+        // the slice if the range is not empty.
+        let (near, far) = match resolved.end {
+            Some(end) if end <= resolved.start => (resolved.start, resolved.start),
+            Some(end) => (resolved.start, end),
+            None => (resolved.start, usize::max_value()),
+        };
+
+        let mut near_byte = if near == 0 { self.len() } else { 0 };
+        let mut far_byte = if far == 0 { self.len() } else { 0 };
+        for (count, (index, _char)) in self.char_indices().rev().enumerate() {
+            if count + 1 == near {
+                near_byte = index;
+            }
+            if count + 1 == far {
+                far_byte = index;
+                break;
+            }
+        }
+        // SAFETY: Both byte indices were obtained from the `CharIndices` of `self`, or are its
+        // bounds.
+        slice_unchecked!(self, far_byte, near_byte)
+    }
+
+    fn char_byte_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        self.to_byte_range(Unit::Chars, range)
+    }
+
+    fn split_at_char(&self, n: usize) -> (&str, &str) {
+        let byte = ::nth_char_boundary(self, n);
+        // SAFETY: `byte` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        (
+            slice_unchecked!(self, 0, byte),
+            slice_unchecked!(self, byte, self.len()),
+        )
+    }
+
+    fn try_split_at_char(&self, n: usize) -> Option<(&str, &str)> {
+        let byte = self
+            .char_indices()
+            .map(|(index, _char)| index)
+            .chain(iter::once(self.len()))
+            .nth(n)?;
+        // SAFETY: `byte` was obtained from the `CharIndices` of `self`, or is its length.
+        Some((
+            slice_unchecked!(self, 0, byte),
+            slice_unchecked!(self, byte, self.len()),
+        ))
+    }
+
+    fn char_chunks(&self, chunk_size: usize) -> CharChunks<'_> {
+        CharChunks::new(self, chunk_size)
+    }
+
+    fn char_windows(&self, size: usize) -> CharWindows<'_> {
+        CharWindows::new(self, size)
+    }
+
+    fn take_chars(&self, n: usize) -> &str {
+        self.split_at_char(n).0
+    }
+
+    fn skip_chars(&self, n: usize) -> &str {
+        self.split_at_char(n).1
+    }
+
+    fn display_substring<R: RangeBounds<usize>>(&self, range: R) -> SubstringDisplay<'_, R> {
+        SubstringDisplay::new(self, range)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn truncate_chars_with(&self, max_chars: usize, suffix: &str) -> Cow<'_, str> {
+        Truncator::new()
+            .budget(max_chars)
+            .ellipsis(suffix)
+            .truncate(self)
+    }
+
+    fn width_substring<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        let resolved = bounds::resolve(&range);
+        let byte_range = width::width_range_to_byte_range(self, resolved.start, resolved.end);
+        // SAFETY: Both byte indices lie on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, byte_range.start, byte_range.end)
+    }
+
+    fn truncate_to_width(&self, max_cols: usize) -> &str {
+        self.width_substring(..max_cols)
+    }
+
+    fn substring_len(&self, start: usize, len: usize) -> &str {
+        let start_byte = ::nth_char_boundary(self, start);
+        // SAFETY: `start_byte` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        let rest = slice_unchecked!(self, start_byte, self.len());
+        let len_bytes = ::nth_char_boundary(rest, len);
+        // SAFETY: `len_bytes` lies on a UTF-8 sequence boundary within the bounds of `rest`.
+        slice_unchecked!(rest, 0, len_bytes)
+    }
+
+    fn substring_len_strict(&self, start: usize, len: usize) -> &str {
+        match start.checked_add(len) {
+            Some(end) => self.substring_strict(start..end),
+            None => panic!(
+                "end index {} is out of bounds of string with {} characters",
+                start as u128 + len as u128,
+                self.chars().count()
+            ),
+        }
+    }
+
+    fn substring_between<S: Delimiter, E: Delimiter>(&self, start: S, end: E) -> Option<&str> {
+        let start_byte = start.find_in(self)?.end;
+        // SAFETY: `start_byte` is the end of a match within `self`, so it lies on a UTF-8 sequence
+        // boundary.
+        let rest = slice_unchecked!(self, start_byte, self.len());
+        let end_byte = end.find_in(rest)?.start;
+        // SAFETY: `end_byte` is the start of a match within `rest`.
+        Some(slice_unchecked!(rest, 0, end_byte))
+    }
+
+    fn substring_before<D: Delimiter>(&self, delimiter: D) -> Option<&str> {
+        let found = delimiter.find_in(self)?;
+        // SAFETY: `found` is the range of a match within `self`.
+        Some(slice_unchecked!(self, 0, found.start))
+    }
+
+    fn substring_before_last<D: Delimiter>(&self, delimiter: D) -> Option<&str> {
+        let found = delimiter.rfind_in(self)?;
+        // SAFETY: `found` is the range of a match within `self`.
+        Some(slice_unchecked!(self, 0, found.start))
+    }
+
+    fn substring_after<D: Delimiter>(&self, delimiter: D) -> Option<&str> {
+        let found = delimiter.find_in(self)?;
+        // SAFETY: `found` is the range of a match within `self`.
+        Some(slice_unchecked!(self, found.end, self.len()))
+    }
+
+    fn substring_after_last<D: Delimiter>(&self, delimiter: D) -> Option<&str> {
+        let found = delimiter.rfind_in(self)?;
+        // SAFETY: `found` is the range of a match within `self`.
+        Some(slice_unchecked!(self, found.end, self.len()))
+    }
+
+    fn substring_idx<T: CharIndex, R: RangeBounds<T>>(&self, range: R) -> &str {
+        let resolved = bounds::resolve(&index::saturating_range(&range));
+        let byte_range = unit::to_byte_range(self, Unit::Chars, resolved.start, resolved.end);
+        // SAFETY: Both byte indices lie on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, byte_range.start, byte_range.end)
+    }
+
+    fn try_substring_idx<T: CharIndex, R: RangeBounds<T>>(
+        &self,
+        range: R,
+    ) -> Result<&str, IndexConversionError> {
+        let resolved = bounds::resolve(&index::try_range(&range)?);
+        let byte_range = unit::to_byte_range(self, Unit::Chars, resolved.start, resolved.end);
+        // SAFETY: Both byte indices lie on UTF-8 sequence boundaries within the bounds of `self`.
+        Ok(slice_unchecked!(self, byte_range.start, byte_range.end))
+    }
+
+    fn char_to_byte_index(&self, n: usize) -> Option<usize> {
+        self.char_indices()
+            .map(|(index, _char)| index)
+            .chain(iter::once(self.len()))
+            .nth(n)
+    }
+
+    fn byte_to_char_index(&self, byte_idx: usize) -> Option<usize> {
+        if byte_idx > self.len() {
+            return None;
+        }
+        let starts = self.as_bytes()[..byte_idx]
+            .iter()
+            .filter(|&&byte| byte & 0xc0 != 0x80)
+            .count();
+        if self.is_char_boundary(byte_idx) {
+            Some(starts)
+        } else {
+            // The start of the containing character was counted.
+            Some(starts - 1)
+        }
+    }
+
+    fn substrings<'a, 'r>(&'a self, ranges: &'r [Range<usize>]) -> Substrings<'a, 'r> {
+        Substrings::new(self, ranges)
+    }
+
+    fn floor_substring_to_bytes(&self, max_bytes: usize) -> &str {
+        let end = self.nearest_char_boundary(max_bytes, Direction::Backward);
+        // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        slice_unchecked!(self, 0, end)
+    }
+
+    fn ceil_substring_to_bytes(&self, min_bytes: usize) -> &str {
+        let end = self.nearest_char_boundary(min_bytes, Direction::Forward);
+        // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        slice_unchecked!(self, 0, end)
+    }
+
+    fn floor_suffix_to_bytes(&self, max_bytes: usize) -> &str {
+        let start =
+            self.nearest_char_boundary(self.len().saturating_sub(max_bytes), Direction::Forward);
+        // SAFETY: `start` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        slice_unchecked!(self, start, self.len())
+    }
+
+    fn char_at(&self, n: usize) -> Option<char> {
+        let start = ::nth_char_boundary(self, n);
+        // SAFETY: `start` lies on a UTF-8 sequence boundary within the bounds of `self`.
+        slice_unchecked!(self, start, self.len()).chars().next()
+    }
+
+    fn last_char(&self) -> Option<char> {
+        self.chars().next_back()
+    }
+
+    fn substring_chars<R: RangeBounds<usize>>(&self, range: R) -> SubstringChars<'_> {
+        let resolved = bounds::resolve(&range);
+        SubstringChars::new(self, resolved.start, resolved.end)
+    }
+
+    fn substring_char_indices<R: RangeBounds<usize>>(&self, range: R) -> SubstringCharIndices<'_> {
+        let resolved = bounds::resolve(&range);
+        SubstringCharIndices::new(self, resolved.start, resolved.end)
+    }
+
+    fn partition_at_char_range<R: RangeBounds<usize>>(&self, range: R) -> (&str, &str, &str) {
+        let bytes = self.char_byte_range(range);
+        // SAFETY: Both byte indices lie on UTF-8 sequence boundaries within the bounds of `self`.
+        (
+            slice_unchecked!(self, 0, bytes.start),
+            slice_unchecked!(self, bytes.start, bytes.end),
+            slice_unchecked!(self, bytes.end, self.len()),
+        )
+    }
+
+    fn substring_by<S: Segmenter, R: RangeBounds<usize>>(&self, segmenter: S, range: R) -> &str {
+        word::segment_substring(self, Segments::new(self, segmenter), &range)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn pad_chars(&self, width: usize, fill: char) -> Cow<'_, str> {
+        self.substring_padded(0..width, fill)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn center_chars(&self, width: usize, fill: char) -> Cow<'_, str> {
+        let end = ::nth_char_boundary(self, width);
+        if end < self.len() {
+            // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `self`.
+            return Cow::Borrowed(slice_unchecked!(self, 0, end));
+        }
+        let padding = width - self.chars().count();
+        if padding == 0 {
+            return Cow::Borrowed(self);
+        }
+        let before = padding / 2;
+        let mut centered = String::with_capacity(self.len() + padding * fill.len_utf8());
+        centered.extend(iter::repeat(fill).take(before));
+        centered.push_str(self);
+        centered.extend(iter::repeat(fill).take(padding - before));
+        Cow::Owned(centered)
+    }
+}
+
+/// Returns the characters of `s` within `range`, along with the clamped start index and the number
+/// of characters, in a single walk.
+fn char_window<R: RangeBounds<usize>>(s: &str, range: R) -> (&str, usize, usize) {
+    let resolved = bounds::resolve(&range);
+    let start = resolved.start;
+    let end = resolved.end;
+
+    let mut indices = s.char_indices();
+    let mut char_index = 0;
+    let mut start_byte = s.len();
+    for (index, _char) in &mut indices {
+        if char_index == start {
+            start_byte = index;
+            break;
+        }
+        char_index += 1;
+    }
+    // SAFETY: `start_byte` was obtained from the `CharIndices` of `s`, or is its length.
+    let empty = slice_unchecked!(s, start_byte, start_byte);
+    if start_byte == s.len() {
+        return (empty, char_index, 0);
+    }
+    if end.map_or(false, |end| end <= start) {
+        return (empty, start, 0);
+    }
+
+    let mut len = 1;
+    let mut end_byte = s.len();
+    for (index, _char) in indices {
+        if end.map_or(false, |end| start + len >= end) {
+            end_byte = index;
+            break;
+        }
+        len += 1;
+    }
+    // SAFETY: Both byte indices were obtained from the `CharIndices` of `s`, or are its length.
+    (slice_unchecked!(s, start_byte, end_byte), start, len)
+}
+
+/// Returns the longest run of characters of `s` starting at the character index `start` that all
+/// satisfy `pred`, along with its character range.
+///
+/// A `start` past the end of `s` is clamped to it.
+fn char_run<P: FnMut(char) -> bool>(s: &str, start: usize, mut pred: P) -> (&str, Range<usize>) {
+    let mut char_index = 0;
+    let mut start_byte = s.len();
+    for (index, _char) in s.char_indices() {
+        if char_index == start {
+            start_byte = index;
+            break;
+        }
+        char_index += 1;
+    }
+    // SAFETY: `start_byte` was obtained from the `CharIndices` of `s`, or is its length.
+    let tail = slice_unchecked!(s, start_byte, s.len());
+
+    let mut len = 0;
+    let mut end_byte = tail.len();
+    for (index, c) in tail.char_indices() {
+        if !pred(c) {
+            end_byte = index;
+            break;
+        }
+        len += 1;
+    }
+    // SAFETY: `end_byte` was obtained from the `CharIndices` of `tail`, or is its length.
+    (
+        slice_unchecked!(tail, 0, end_byte),
+        char_index..(char_index + len),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use alloc::borrow::Cow;
+    use boundary::{Direction, Snap};
+    use checkpoint::Checkpoint;
+    use core::ops::{Bound, Range};
+    use error::{CharBoundaryError, IndexConversionError, SubstringError};
+    use std::vec::Vec;
+    use unit::WALKS;
+    use ByChars;
+    use CharSubstring;
+    use Span;
+    use Substring;
+    use SurrogatePolicy;
+    use Unit;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_substring_padded_covered() {
+        let padded = "fõøbα®".substring_padded(1..4, '·');
+        assert_eq!(padded, "õøb");
+        if let Cow::Owned(_) = padded {
+            panic!("expected a borrowed substring");
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_substring_padded_partially_covered() {
+        let padded = "fõøbα®".substring_padded(4..=8, '·');
+        assert_eq!(padded, "α®···");
+        if let Cow::Borrowed(_) = padded {
+            panic!("expected an owned substring");
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_substring_padded_not_covered() {
+        assert_eq!("foobar".substring_padded(10..13, '·'), "···");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_substring_padded_empty_range() {
+        assert_eq!("foobar".substring_padded(3..3, '·'), "");
+        let (start, end) = (4, 2);
+        assert_eq!("foobar".substring_padded(start..end, '·'), "");
+    }
+
+    #[test]
+    fn test_rfind_char() {
+        assert_eq!("foobar".rfind_char("o"), Some(2));
+        assert_eq!("foobar".rfind_char("x"), None);
+    }
+
+    #[test]
+    fn test_rfind_char_at_start() {
+        assert_eq!("fõøbar".rfind_char("fõ"), Some(0));
+    }
+
+    #[test]
+    fn test_rfind_char_at_end() {
+        assert_eq!("fõøbar".rfind_char("ar"), Some(4));
+        assert_eq!("fõøbar".rfind_char(""), Some(6));
+    }
+
+    #[test]
+    fn test_rfind_char_multiple_byte_characters_before_match() {
+        // The byte index of the match is 10.
+        assert_eq!("fõøbα®bar".rfind_char("bar"), Some(6));
+    }
+
+    #[test]
+    fn test_last_match_char_range() {
+        assert_eq!("fõøbarfõø".last_match_char_range("õø"), Some(7..9));
+        assert_eq!("fõøbar".last_match_char_range("fõø"), Some(0..3));
+        assert_eq!("fõøbar".last_match_char_range("α"), None);
+    }
+
+    #[test]
+    fn test_last_match_char_range_multiple_occurrences() {
+        assert_eq!("αβαβαβ".last_match_char_range("αβ"), Some(4..6));
+        assert_eq!("ααα".last_match_char_range("αα"), Some(1..3));
+    }
+
+    #[test]
+    fn test_to_byte_range() {
+        assert_eq!("fõøbα®".to_byte_range(Unit::Chars, 2..5), 3..8);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Chars, ..=1), 0..3);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Chars, 4..), 6..10);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Bytes, 2..), 3..10);
+        assert_eq!("😀a".to_byte_range(Unit::Utf16, ..), 0..5);
+    }
+
+    #[test]
+    fn test_from_byte_range() {
+        assert_eq!("fõøbα®".from_byte_range(Unit::Chars, 3..8), 2..5);
+        assert_eq!("fõøbα®".from_byte_range(Unit::Chars, 2..), 2..6);
+        assert_eq!("fõøbα®".from_byte_range(Unit::Bytes, ..=3), 0..3);
+        assert_eq!("😀a".from_byte_range(Unit::Utf16, ..), 0..3);
+    }
+
+    #[test]
+    fn test_substring_validated() {
+        let s = "fõøbα®";
+        let span = Span::new(1, 4).unwrap().validate(s).unwrap();
+        let walks = WALKS.with(|walks| walks.get());
+        assert_eq!(s.substring_validated(span), "õøb");
+        assert_eq!(s.substring_validated(span), "õøb");
+        assert_eq!(WALKS.with(|walks| walks.get()), walks);
+    }
+
+    #[test]
+    #[should_panic(expected = "span was validated against a different string")]
+    fn test_substring_validated_different_string() {
+        let span = Span::new(1, 4).unwrap().validate("fõøbα®").unwrap();
+        let _ = "foobar".substring_validated(span);
+    }
+
+    #[test]
+    fn test_char_count_in() {
+        assert_eq!("fõøbα®".char_count_in(1..8), 4);
+        assert_eq!("fõøbα®".char_count_in(..=4), 3);
+    }
+
+    #[test]
+    fn test_char_count_in_mid_character() {
+        // "õ" occupies bytes 1..3 and "α" occupies bytes 6..8.
+        assert_eq!("fõøbα®".char_count_in(2..7), 2);
+        assert_eq!("fõøbα®".char_count_in(2..3), 0);
+        assert_eq!("fõøbα®".char_count_in(2..=6), 2);
+    }
+
+    #[test]
+    fn test_char_count_in_empty() {
+        assert_eq!("fõøbα®".char_count_in(3..3), 0);
+        assert_eq!("fõøbα®".char_count_in(20..), 0);
+        assert_eq!("".char_count_in(..), 0);
+    }
+
+    #[test]
+    fn test_char_count_in_whole_string() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            assert_eq!(s.char_count_in(..), s.chars().count());
+            assert_eq!(s.char_count_in(0..100), s.chars().count());
+        }
+    }
+
+    #[test]
+    fn test_try_char_count_in() {
+        assert_eq!("fõøbα®".try_char_count_in(1..8), Ok(4));
+        assert_eq!("fõøbα®".try_char_count_in(..), Ok(6));
+        assert_eq!("fõøbα®".try_char_count_in(10..), Ok(0));
+    }
+
+    #[test]
+    fn test_try_char_count_in_not_boundary() {
+        assert_eq!(
+            "fõøbα®".try_char_count_in(2..8),
+            Err(CharBoundaryError::new(2))
+        );
+        assert_eq!(
+            "fõøbα®".try_char_count_in(1..7),
+            Err(CharBoundaryError::new(7))
+        );
+        assert_eq!(
+            "fõøbα®".try_char_count_in(0..11),
+            Err(CharBoundaryError::new(11))
+        );
+    }
+
+    #[test]
+    fn test_nearest_char_boundary_exhaustive() {
+        // Contains characters of 1, 2, 3, and 4 bytes.
+        let s = "aõ日😀b";
+        for byte_idx in 0..(s.len() + 3) {
+            let backward = s.nearest_char_boundary(byte_idx, Direction::Backward);
+            let forward = s.nearest_char_boundary(byte_idx, Direction::Forward);
+            let nearest = s.nearest_char_boundary(byte_idx, Direction::Nearest);
+            let clamped = byte_idx.min(s.len());
+
+            assert!(s.is_char_boundary(backward));
+            assert!(s.is_char_boundary(forward));
+            assert!(backward <= clamped && clamped <= forward);
+            assert!((backward + 1..clamped).all(|i| !s.is_char_boundary(i)));
+            assert!((clamped + 1..forward).all(|i| !s.is_char_boundary(i)));
+            if s.is_char_boundary(clamped) {
+                assert_eq!(backward, clamped);
+                assert_eq!(forward, clamped);
+            }
+            if forward - clamped < clamped - backward {
+                assert_eq!(nearest, forward);
+            } else {
+                assert_eq!(nearest, backward);
+            }
+        }
+    }
+
+    #[test]
+    fn test_boundary_distance_exhaustive() {
+        let s = "aõ日😀b";
+        for byte_idx in 0..(s.len() + 3) {
+            let distance = s.boundary_distance(byte_idx);
+            if byte_idx > s.len() {
+                assert_eq!(distance, byte_idx - s.len());
+            } else {
+                assert_eq!(distance == 0, s.is_char_boundary(byte_idx));
+                assert!(
+                    s.is_char_boundary(byte_idx - distance)
+                        || s.is_char_boundary(byte_idx + distance)
+                );
+                assert!((1..distance).all(|d| {
+                    !s.is_char_boundary(byte_idx - d) && !s.is_char_boundary(byte_idx + d)
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn test_chars_in_rev() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let char_len = s.chars().count();
+            for start in 0..(char_len + 2) {
+                for end in 0..(char_len + 2) {
+                    assert!(s
+                        .chars_in_rev(start..end)
+                        .eq(s.substring(start, end).chars().rev()));
+                }
+                assert!(s
+                    .chars_in_rev(start..)
+                    .eq(s.substring(start, char_len).chars().rev()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_chars_in_rev_empty_and_clamped() {
+        assert_eq!("fõøbα®".chars_in_rev(3..3).next(), None);
+        assert_eq!("fõøbα®".chars_in_rev(10..).next(), None);
+        assert!("fõøbα®".chars_in_rev(4..100).eq("®α".chars()));
+        assert!("fõøbα®".chars_in_rev(..).eq("®αbøõf".chars()));
+    }
+
+    #[test]
+    fn test_chars_in_rev_double_ended() {
+        assert!("fõøbα®".chars_in_rev(1..4).rev().eq("õøb".chars()));
+    }
+
+    #[test]
+    fn test_char_indices_in_rev() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let char_len = s.chars().count();
+            for start in 0..(char_len + 2) {
+                for end in 0..(char_len + 2) {
+                    let expected = s
+                        .substring(start, end)
+                        .chars()
+                        .enumerate()
+                        .map(|(index, c)| (start + index, c))
+                        .collect::<Vec<_>>();
+                    assert!(s
+                        .char_indices_in_rev(start..end)
+                        .eq(expected.into_iter().rev()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_indices_in_rev_clamped() {
+        assert!("fõøbα®"
+            .char_indices_in_rev(4..=10)
+            .eq(vec![(5, '®'), (4, 'α')]));
+        assert_eq!("fõøbα®".char_indices_in_rev(6..).next(), None);
+    }
+
+    #[test]
+    fn test_char_containing() {
+        assert_eq!("foobar".char_containing(0), Some((0, 0..1, 'f')));
+        assert_eq!("foobar".char_containing(5), Some((5, 5..6, 'r')));
+    }
+
+    #[test]
+    fn test_char_containing_multiple_byte_characters() {
+        // "😀" occupies bytes 1..5.
+        let s = "a😀b";
+        assert_eq!(s.char_containing(1), Some((1, 1..5, '😀')));
+        assert_eq!(s.char_containing(2), Some((1, 1..5, '😀')));
+        assert_eq!(s.char_containing(4), Some((1, 1..5, '😀')));
+        assert_eq!(s.char_containing(5), Some((2, 5..6, 'b')));
+    }
+
+    #[test]
+    fn test_char_containing_end() {
+        assert_eq!("a😀b".char_containing(6), None);
+        assert_eq!("a😀b".char_containing(100), None);
+        assert_eq!("".char_containing(0), None);
+    }
+
+    #[test]
+    fn test_char_containing_exhaustive() {
+        let s = "aõ日😀b";
+        for byte_idx in 0..s.len() {
+            let (char_index, bytes, c) = s.char_containing(byte_idx).unwrap();
+            assert!(bytes.start <= byte_idx && byte_idx < bytes.end);
+            assert_eq!(&s[bytes.clone()], c.encode_utf8(&mut [0; 4]));
+            assert_eq!(s.substring(char_index, char_index + 1), &s[bytes]);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let s = "fõøbα®";
+        for char_idx in 0..7 {
+            let checkpoint = s.checkpoint(char_idx);
+            assert_eq!(checkpoint.char_idx(), char_idx);
+            assert_eq!(
+                checkpoint.byte_idx(),
+                s.char_indices()
+                    .nth(char_idx)
+                    .map_or(s.len(), |(index, _)| index)
+            );
+        }
+        assert_eq!(s.checkpoint(100).char_idx(), 6);
+        assert_eq!(s.checkpoint(100).byte_idx(), 10);
+    }
+
+    #[test]
+    fn test_char_indices_from_matches_absolute() {
+        let s = "fõøbα®";
+        for char_idx in 0..7 {
+            let expected = s
+                .char_indices()
+                .enumerate()
+                .skip(char_idx)
+                .map(|(char_idx, (byte_idx, c))| (char_idx, byte_idx, c));
+            assert!(s
+                .char_indices_from(s.checkpoint(char_idx))
+                .unwrap()
+                .eq(expected));
+        }
+    }
+
+    #[test]
+    fn test_char_indices_from_checkpoint_resumes() {
+        let s = "fõøbα®";
+        let mut indices = s.char_indices_from(s.checkpoint(0)).unwrap();
+        indices.next();
+        indices.next();
+        let checkpoint = indices.checkpoint();
+        assert_eq!(checkpoint, s.checkpoint(2));
+        assert!(s.char_indices_from(checkpoint).unwrap().eq(indices));
+    }
+
+    #[test]
+    fn test_char_indices_from_invalid_checkpoint() {
+        let checkpoint = "fõøbα®".checkpoint(2);
+        assert_eq!(
+            "foõ".char_indices_from(checkpoint).unwrap_err(),
+            CharBoundaryError::new(3)
+        );
+        assert_eq!(
+            "fo".char_indices_from(checkpoint).unwrap_err(),
+            CharBoundaryError::new(3)
+        );
+    }
+
+    #[test]
+    fn test_substring_from_checkpoint_matches_absolute() {
+        let s = "fõøbα®";
+        for char_idx in 0..7 {
+            let checkpoint = s.checkpoint(char_idx);
+            for start in 0..8 {
+                for end in 0..8 {
+                    assert_eq!(
+                        s.substring_from_checkpoint(checkpoint, start..end),
+                        Ok(s.substring(char_idx + start, char_idx + end))
+                    );
+                }
+                assert_eq!(
+                    s.substring_from_checkpoint(checkpoint, start..),
+                    Ok(s.substring(char_idx + start, 6))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_from_checkpoint_user_checkpoint() {
+        let s = "fõøbα®";
+        let position = s.find('b').unwrap();
+        let checkpoint = Checkpoint::new(s, 3, position).unwrap();
+        assert_eq!(s.substring_from_checkpoint(checkpoint, ..2), Ok("bα"));
+    }
+
+    #[test]
+    fn test_substring_from_checkpoint_invalid_checkpoint() {
+        let checkpoint = "fõøbα®".checkpoint(6);
+        assert_eq!(
+            "foobar".substring_from_checkpoint(checkpoint, ..),
+            Err(CharBoundaryError::new(10))
+        );
+    }
+
+    #[test]
+    fn test_break_opportunities() {
+        assert_eq!("fõø bα-®".break_opportunities().collect::<Vec<_>>(), [4, 7]);
+        assert_eq!("".break_opportunities().next(), None);
+    }
+
+    #[test]
+    fn test_wrap_at_opportunities() {
+        assert_eq!(
+            "fõø bα-® fõøbα®"
+                .wrap_at_opportunities(4)
+                .collect::<Vec<_>>(),
+            ["fõø ", "bα-® ", "fõøb", "α®"]
+        );
+    }
+
+    #[test]
+    fn test_byte_substring() {
+        let s = "fõøbα®";
+        assert_eq!(s.byte_substring(1..4), "õ");
+        assert_eq!(s.byte_substring(..), s);
+        assert_eq!(s.byte_substring(..=4), "fõø");
+        assert_eq!(s.byte_substring(5..20), "bα®");
+        assert_eq!(s.byte_substring(20..), "");
+    }
+
+    #[test]
+    fn test_byte_substring_collapsed() {
+        let s = "fõøbα®";
+        // Snapping inward leaves nothing between the bounds.
+        assert_eq!(s.byte_substring(2..4), "");
+        let (start, end) = (5, 3);
+        assert_eq!(s.byte_substring(start..end), "");
+        assert_eq!(
+            s.byte_substring(start..end).as_ptr() as usize - s.as_ptr() as usize,
+            5
+        );
+    }
+
+    #[test]
+    fn test_byte_substring_snapped() {
+        let s = "fõøbα®";
+        assert_eq!(s.byte_substring_snapped(2..4, Snap::Inward), "");
+        assert_eq!(s.byte_substring_snapped(2..4, Snap::Outward), "õø");
+        assert_eq!(s.byte_substring_snapped(2..4, Snap::Backward), "õ");
+        assert_eq!(s.byte_substring_snapped(2..4, Snap::Forward), "ø");
+        assert_eq!(s.byte_substring_snapped(7.., Snap::Outward), "α®");
+        assert_eq!(s.byte_substring_snapped(..=100, Snap::Inward), s);
+    }
+
+    #[test]
+    fn test_byte_substring_snapped_inward_matches_to_byte_range() {
+        let s = "fõøbα®";
+        for start in 0..12 {
+            for end in 0..12 {
+                assert_eq!(
+                    s.byte_substring_snapped(start..end, Snap::Inward),
+                    &s[s.to_byte_range(Unit::Bytes, start..end)]
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range() {
+        assert_eq!("hello wORLD".titlecase_char_range(..), "Hello World");
+        assert_eq!("hello world".titlecase_char_range(6..), "hello World");
+        assert_eq!(
+            "jean-luc o'neil".titlecase_char_range(..),
+            "Jean-Luc O'neil"
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range_apostrophes() {
+        assert_eq!("don't".titlecase_char_range(..), "Don't");
+        assert_eq!("JOHN'S".titlecase_char_range(..), "John's");
+        assert_eq!("rock ’n’ roll".titlecase_char_range(..), "Rock ’N’ Roll");
+        assert_eq!("'quoted'".titlecase_char_range(..), "'Quoted'");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range_straddling_words() {
+        assert_eq!("HELLO WORLD".titlecase_char_range(2..8), "HEllo WoRLD");
+        assert_eq!("hello world".titlecase_char_range(3..7), "hello World");
+        assert_eq!("DON'T PANIC".titlecase_char_range(3..8), "DON't PaNIC");
+        assert_eq!("JOHN'S".titlecase_char_range(4..), "JOHN's");
+        assert_eq!("ROOM 12B A4X".titlecase_char_range(6..), "ROOM 12B A4x");
+        assert_eq!("x2y z".titlecase_char_range(1..), "x2y Z");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range_first_cased_character() {
+        assert_eq!("1ST 2ND".titlecase_char_range(..), "1St 2Nd");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range_length_changes() {
+        assert_eq!("ßtraße".titlecase_char_range(..), "SStraße");
+        assert_eq!("İSTANBUL".titlecase_char_range(1..), "İstanbul");
+        assert_eq!("x İ".titlecase_char_range(2..), "x İ");
+        assert_eq!("xx İ".titlecase_char_range(..), "Xx İ");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range_uncased_scripts() {
+        assert_eq!("日本語 עברית".titlecase_char_range(..), "日本語 עברית");
+        assert_eq!("日本 abc".titlecase_char_range(..), "日本 Abc");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_titlecase_char_range_out_of_bounds() {
+        assert_eq!("hello".titlecase_char_range(10..), "hello");
+        let (start, end) = (3, 1);
+        assert_eq!("hello".titlecase_char_range(start..end), "hello");
+    }
+
+    #[test]
+    fn test_span_while() {
+        let s = "foo_bar1 = 42+x";
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        assert_eq!(s.span_while(0, is_ident), "foo_bar1");
+        assert_eq!(s.char_range_while(0, is_ident), 0..8);
+        assert_eq!(s.span_while(11, |c| c.is_ascii_digit()), "42");
+        assert_eq!(s.char_range_while(11, |c| c.is_ascii_digit()), 11..13);
+        assert_eq!(s.span_while(8, is_ident), "");
+        assert_eq!(s.char_range_while(8, is_ident), 8..8);
+        assert_eq!(s.span_while(14, is_ident), "x");
+    }
+
+    #[test]
+    fn test_span_while_tokenize() {
+        let s = "größe=3.5 π";
+        let mut tokens = Vec::new();
+        let mut position = 0;
+        while position < 11 {
+            let range = if s.substring(position, position + 1) == "=" {
+                position..(position + 1)
+            } else if !s.span_while(position, char::is_whitespace).is_empty() {
+                s.char_range_while(position, char::is_whitespace)
+            } else if !s.span_while(position, |c| c.is_ascii_digit()).is_empty() {
+                s.char_range_while(position, |c| c.is_ascii_digit() || c == '.')
+            } else {
+                s.char_range_while(position, char::is_alphabetic)
+            };
+            tokens.push(s.substring(range.start, range.end));
+            position = range.end;
+        }
+        assert_eq!(tokens, ["größe", "=", "3.5", " ", "π"]);
+    }
+
+    #[test]
+    fn test_span_while_stops_at_first_failure() {
+        let mut calls = 0;
+        let span = "aaab aaaa".span_while(0, |c| {
+            calls += 1;
+            c == 'a'
+        });
+        assert_eq!(span, "aaa");
+        assert_eq!(calls, 4);
+    }
+
+    #[test]
+    fn test_span_while_out_of_bounds() {
+        assert_eq!("fõøbα®".span_while(10, |_| true), "");
+        assert_eq!("fõøbα®".char_range_while(10, |_| true), 6..6);
+        assert_eq!("fõøbα®".span_until(10, |_| true), "");
+    }
+
+    #[test]
+    fn test_span_while_to_end() {
+        assert_eq!("fõøbα®".span_while(2, |_| true), "øbα®");
+        assert_eq!("fõøbα®".char_range_while(2, |_| true), 2..6);
+    }
+
+    #[test]
+    fn test_span_until() {
+        assert_eq!("fõø;bα®".span_until(1, |c| c == ';'), "õø");
+        assert_eq!("fõø;bα®".span_until(3, |c| c == ';'), "");
+        assert_eq!("fõø;bα®".span_until(4, |c| c == ';'), "bα®");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mask_char_range() {
+        assert_eq!("foobar".mask_char_range(1..4, '*'), "f***ar");
+        assert_eq!("foobar".mask_char_range(.., '*'), "******");
+        assert_eq!("foobar".mask_char_range(4..10, '*'), "foob**");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mask_char_range_multibyte() {
+        let masked = "fõøbα®".mask_char_range(1..5, '*');
+        assert_eq!(masked, "f****®");
+        assert_eq!(masked.len(), 7);
+        assert_eq!("foobar".mask_char_range(2..4, '█'), "fo██ar");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mask_char_range_empty() {
+        assert_eq!("fõøbα®".mask_char_range(10.., '*'), "fõøbα®");
+        let (start, end) = (4, 2);
+        assert_eq!("fõøbα®".mask_char_range(start..end, '*'), "fõøbα®");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mask_char_range_except_card_number() {
+        assert_eq!(
+            "4111 1111-1111 1234".mask_char_range_except(.., '#', |c| c == '-' || c == ' '),
+            "#### ####-#### ####"
+        );
+        assert_eq!(
+            "4111-1111-1111-1234".mask_char_range_except(5..14, '*', |c| c == '-'),
+            "4111-****-****-1234"
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_mask_char_range_preserves_char_count() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let char_len = s.chars().count();
+            for start in 0..(char_len + 2) {
+                for end in 0..(char_len + 2) {
+                    for &mask in ['*', 'ß', '😀'].iter() {
+                        assert_eq!(
+                            s.mask_char_range(start..end, mask).chars().count(),
+                            char_len
+                        );
+                        assert_eq!(
+                            s.mask_char_range_except(start..end, mask, char::is_alphabetic)
+                                .chars()
+                                .count(),
+                            char_len
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_byte_range_empty_position() {
+        let (start, end) = (4, 2);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Chars, start..end), 6..6);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Chars, 3..3), 5..5);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Chars, 10..12), 10..10);
+        assert_eq!("fõøbα®".to_byte_range(Unit::Bytes, 2..2), 3..3);
+    }
+
+    #[test]
+    fn test_truncate_at_word_mid_word() {
+        assert_eq!("the quick brown fox".truncate_at_word(12), "the quick");
+    }
+
+    #[test]
+    fn test_truncate_at_word_first_word_too_long() {
+        assert_eq!("internationalization".truncate_at_word(5), "inter");
+    }
+
+    #[test]
+    fn test_truncate_at_word_beyond_string() {
+        let s = "the quick brown fox";
+        let truncated = s.truncate_at_word(100);
+        assert_eq!(truncated, s);
+        assert_eq!(truncated.as_ptr(), s.as_ptr());
+    }
+
+    #[test]
+    fn test_truncate_at_word_cjk() {
+        assert_eq!("日本語のテキスト".truncate_at_word(4), "日本語の");
+        assert_eq!("中文 text".truncate_at_word(4), "中文");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_at_word_with_ellipsis() {
+        assert_eq!(
+            "the quick brown fox".truncate_at_word_with_ellipsis(12, "..."),
+            "the quick..."
+        );
+        assert_eq!(
+            "internationalization".truncate_at_word_with_ellipsis(6, "…"),
+            "inter…"
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_at_word_with_ellipsis_fits() {
+        let s = "the quick brown fox";
+        match s.truncate_at_word_with_ellipsis(19, "…") {
+            Cow::Borrowed(truncated) => assert_eq!(truncated, s),
+            Cow::Owned(_) => panic!("expected a borrowed string"),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_at_word_with_ellipsis_longer_than_budget() {
+        assert_eq!(
+            "the quick brown fox".truncate_at_word_with_ellipsis(2, "..."),
+            ".."
+        );
+    }
+
+    #[test]
+    fn test_utf16_substring() {
+        assert_eq!("😀a𝔘b".utf16_substring(..), "😀a𝔘b");
+        assert_eq!("😀a𝔘b".utf16_substring(2..=3), "a");
+        assert_eq!("😀a𝔘b".utf16_substring(3..), "𝔘b");
+        assert_eq!("😀a𝔘b".utf16_substring(7..9), "");
+    }
+
+    #[test]
+    fn test_utf16_substring_matches_default_policy() {
+        let s = "😀a𝔘b";
+        for start in 0..8 {
+            for end in 0..8 {
+                assert_eq!(
+                    s.utf16_substring_with(start..end, SurrogatePolicy::default()),
+                    Ok(s.utf16_substring(start..end))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_utf16_substring_with_policies() {
+        let s = "😀a𝔘b";
+        for &((start, end), snap_out, snap_in, error) in [
+            ((1, 3), "😀a", "a", 1),
+            ((2, 4), "a𝔘", "a", 4),
+            ((1, 6), "😀a𝔘b", "a𝔘b", 1),
+            ((4, 6), "𝔘b", "b", 4),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                s.utf16_substring_with(start..end, SurrogatePolicy::SnapOut),
+                Ok(snap_out)
+            );
+            assert_eq!(
+                s.utf16_substring_with(start..end, SurrogatePolicy::SnapIn),
+                Ok(snap_in)
+            );
+            assert_eq!(
+                s.utf16_substring_with(start..end, SurrogatePolicy::Error),
+                Err(SubstringError::SplitSurrogatePair { index: error })
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_fold_char_ranges() {
+        let s = "Ärger über ÄRGER";
+        let ranges = s.find_fold_char_ranges("ärger").collect::<Vec<_>>();
+        assert_eq!(ranges, [0..5, 11..16]);
+        for range in ranges {
+            assert_eq!(s.to_byte_range(Unit::Chars, range).len(), 6);
+        }
+    }
+
+    #[test]
+    fn test_try_substring() {
+        assert_eq!("fõøbα®".try_substring(2, 5), Ok("øbα"));
+        assert_eq!("fõøbα®".try_substring(0, 6), Ok("fõøbα®"));
+        assert_eq!("fõøbα®".try_substring(3, 3), Ok(""));
+        assert_eq!("fõøbα®".try_substring(6, 6), Ok(""));
+        assert_eq!("".try_substring(0, 0), Ok(""));
+    }
+
+    #[test]
+    fn test_try_substring_errors() {
+        assert_eq!(
+            "fõøbα®".try_substring(3, 2),
+            Err(SubstringError::StartAfterEnd { start: 3, end: 2 })
+        );
+        assert_eq!(
+            "fõøbα®".try_substring(2, 7),
+            Err(SubstringError::EndOutOfBounds {
+                index: 7,
+                char_len: 6
+            })
+        );
+        assert_eq!(
+            "fõøbα®".try_substring(7, 8),
+            Err(SubstringError::StartOutOfBounds {
+                index: 7,
+                char_len: 6
+            })
+        );
+        // Reversed indices are reported before out of bounds ones.
+        assert_eq!(
+            "fõøbα®".try_substring(9, 8),
+            Err(SubstringError::StartAfterEnd { start: 9, end: 8 })
+        );
+    }
+
+    #[test]
+    fn test_try_substring_matches_substring() {
+        let s = "fõøbα®";
+        for start in 0..=6 {
+            for end in start..=6 {
+                assert_eq!(s.try_substring(start, end), Ok(s.substring(start, end)));
+                assert_eq!(
+                    s.try_substring(start, end).unwrap().as_ptr(),
+                    s.substring(start, end).as_ptr()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_strict() {
+        let s = "fõøbα®";
+        assert_eq!(s.substring_strict(2..5), "øbα");
+        assert_eq!(s.substring_strict(2..=4), "øbα");
+        assert_eq!(s.substring_strict(..), s);
+        assert_eq!(s.substring_strict(3..), "bα®");
+        assert_eq!(s.substring_strict(..=0), "f");
+        assert_eq!(s.substring_strict(6..), "");
+        assert_eq!(s.substring_strict(6..6), "");
+        assert_eq!(
+            s.substring_strict((Bound::Excluded(1), Bound::Included(3))),
+            "øb"
+        );
+    }
+
+    #[test]
+    fn test_substring_strict_matches_substring() {
+        let s = "fõøbα®";
+        for start in 0..=6 {
+            for end in start..=6 {
+                assert_eq!(
+                    s.substring_strict(start..end).as_ptr(),
+                    s.substring(start, end).as_ptr()
+                );
+                assert_eq!(s.substring_strict(start..end), s.substring(start, end));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "start index 4 is after end index 2 when slicing string with 6 \
+                               characters"
+    )]
+    fn test_substring_strict_start_after_end() {
+        let (start, end) = (4, 2);
+        let _ = "fõøbα®".substring_strict(start..end);
+    }
+
+    #[test]
+    #[should_panic(expected = "start index 7 is out of bounds of string with 6 characters")]
+    fn test_substring_strict_start_out_of_bounds() {
+        let _ = "fõøbα®".substring_strict(7..);
+    }
+
+    #[test]
+    #[should_panic(expected = "end index 8 is out of bounds of string with 6 characters")]
+    fn test_substring_strict_end_out_of_bounds() {
+        let _ = "fõøbα®".substring_strict(2..8);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    #[should_panic(
+        expected = "start index 18446744073709551616 is out of bounds of string with 6 \
+                               characters"
+    )]
+    fn test_substring_strict_excluded_max_start() {
+        let _ = "fõøbα®".substring_strict((Bound::Excluded(::core::usize::MAX), Bound::Unbounded));
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    #[should_panic(
+        expected = "end index 18446744073709551616 is out of bounds of string with 6 \
+                               characters"
+    )]
+    fn test_substring_strict_included_max_end() {
+        let _ = "fõøbα®".substring_strict(..=(::core::usize::MAX));
+    }
+
+    #[test]
+    fn test_substring_from_end() {
+        assert_eq!("foobar".substring_from_end(0..3), "bar");
+        assert_eq!("foobar".substring_from_end(2..4), "ob");
+        assert_eq!("foobar".substring_from_end(..=0), "r");
+        assert_eq!("foobar".substring_from_end(..), "foobar");
+        assert_eq!("foobar".substring_from_end(5..), "f");
+    }
+
+    #[test]
+    fn test_substring_from_end_multiple_byte_characters() {
+        assert_eq!("fõøbα®".substring_from_end(..3), "bα®");
+        assert_eq!("😀a𝔘b".substring_from_end(1..3), "a𝔘");
+        assert_eq!("😀a𝔘b".substring_from_end(3..4), "😀");
+    }
+
+    #[test]
+    fn test_substring_from_end_clamped() {
+        assert_eq!("foobar".substring_from_end(0..100), "foobar");
+        assert_eq!("foobar".substring_from_end(3..6), "foo");
+        assert_eq!("foobar".substring_from_end(6..8), "");
+        assert_eq!("foobar".substring_from_end(10..20), "");
+    }
+
+    #[test]
+    fn test_substring_from_end_empty() {
+        let s = "foobar";
+        let (start, end) = (4, 1);
+        assert_eq!(s.substring_from_end(start..end), "");
+        assert_eq!(s.substring_from_end(start..end).as_ptr(), s[2..].as_ptr());
+        assert_eq!(s.substring_from_end(0..0).as_ptr(), s[6..].as_ptr());
+        assert_eq!(s.substring_from_end(8..8).as_ptr(), s.as_ptr());
+        assert_eq!("".substring_from_end(..), "");
+    }
+
+    #[test]
+    fn test_substring_from_end_matches_substring() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let len = s.chars().count();
+            for start in 0..(len + 2) {
+                for end in 0..(len + 2) {
+                    let expected = s.substring(len.saturating_sub(end), len.saturating_sub(start));
+                    assert_eq!(s.substring_from_end(start..end), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_byte_range() {
+        assert_eq!("fõøbα®".char_byte_range(..), 0..10);
+        assert_eq!("fõøbα®".char_byte_range(1..=2), 1..5);
+        assert_eq!("fõøbα®".char_byte_range(4..100), 6..10);
+        assert_eq!("fõøbα®".char_byte_range(7..9), 10..10);
+    }
+
+    #[test]
+    fn test_char_byte_range_empty_at_start() {
+        let (start, end) = (4, 2);
+        assert_eq!("fõøbα®".char_byte_range(start..end), 6..6);
+        assert_eq!("fõøbα®".char_byte_range(3..3), 5..5);
+    }
+
+    #[test]
+    fn test_char_byte_range_matches_substring() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            for start in 0..12 {
+                for end in 0..12 {
+                    let range = s.char_byte_range(start..end);
+                    let sub = s.substring(start, end);
+                    assert_eq!(&s[range.clone()], sub);
+                    assert_eq!(range.start, sub.as_ptr() as usize - s.as_ptr() as usize);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_at_char() {
+        assert_eq!("fõøbar".split_at_char(0), ("", "fõøbar"));
+        assert_eq!("fõøbar".split_at_char(2), ("fõ", "øbar"));
+        assert_eq!("fõøbar".split_at_char(6), ("fõøbar", ""));
+        assert_eq!("fõøbar".split_at_char(100), ("fõøbar", ""));
+        assert_eq!("".split_at_char(0), ("", ""));
+    }
+
+    #[test]
+    fn test_split_at_char_multiple_byte_characters() {
+        // Splitting at either side of "😀" or "𝔘" never splits their bytes.
+        assert_eq!("😀a𝔘b".split_at_char(1), ("😀", "a𝔘b"));
+        assert_eq!("😀a𝔘b".split_at_char(2), ("😀a", "𝔘b"));
+        assert_eq!("😀a𝔘b".split_at_char(3), ("😀a𝔘", "b"));
+    }
+
+    #[test]
+    fn test_split_at_char_reassembles() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b", "e\u{301}"].iter() {
+            for n in 0..10 {
+                let (first, second) = s.split_at_char(n);
+                assert_eq!(first.len() + second.len(), s.len());
+                assert_eq!(first.as_ptr(), s.as_ptr());
+                assert_eq!([first, second].concat(), *s);
+                assert_eq!(first, s.substring(0, n));
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_split_at_char() {
+        assert_eq!("fõøbar".try_split_at_char(0), Some(("", "fõøbar")));
+        assert_eq!("fõøbar".try_split_at_char(3), Some(("fõø", "bar")));
+        assert_eq!("fõøbar".try_split_at_char(6), Some(("fõøbar", "")));
+        assert_eq!("fõøbar".try_split_at_char(7), None);
+        assert_eq!("".try_split_at_char(0), Some(("", "")));
+        assert_eq!("".try_split_at_char(1), None);
+    }
+
+    #[test]
+    fn test_char_chunks() {
+        assert_eq!(
+            "foobarbaz".char_chunks(3).collect::<Vec<_>>(),
+            ["foo", "bar", "baz"]
+        );
+        assert_eq!(
+            "foobarba".char_chunks(3).collect::<Vec<_>>(),
+            ["foo", "bar", "ba"]
+        );
+        assert_eq!("foo".char_chunks(10).collect::<Vec<_>>(), ["foo"]);
+        assert_eq!("foo".char_chunks(1).collect::<Vec<_>>(), ["f", "o", "o"]);
+    }
+
+    #[test]
+    fn test_char_chunks_multiple_byte_characters() {
+        assert_eq!(
+            "😀a𝔘bõ".char_chunks(2).collect::<Vec<_>>(),
+            ["😀a", "𝔘b", "õ"]
+        );
+    }
+
+    #[test]
+    fn test_char_chunks_empty() {
+        assert_eq!("".char_chunks(3).next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn test_char_chunks_zero() {
+        let _ = "foo".char_chunks(0);
+    }
+
+    #[test]
+    fn test_char_chunks_size_hint() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b", "😀😀😀😀😀"].iter() {
+            for chunk_size in 1..8 {
+                let mut chunks = s.char_chunks(chunk_size);
+                loop {
+                    let (lower, upper) = chunks.size_hint();
+                    let count = chunks.clone().count();
+                    assert!(lower <= count);
+                    assert!(count <= upper.unwrap());
+                    if chunks.next().is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_windows() {
+        assert_eq!(
+            "fõøbα".char_windows(2).collect::<Vec<_>>(),
+            ["fõ", "õø", "øb", "bα"]
+        );
+        assert_eq!("😀a𝔘b".char_windows(3).collect::<Vec<_>>(), ["😀a𝔘", "a𝔘b"]);
+        assert_eq!("foo".char_windows(1).collect::<Vec<_>>(), ["f", "o", "o"]);
+        assert_eq!("foo".char_windows(3).collect::<Vec<_>>(), ["foo"]);
+    }
+
+    #[test]
+    fn test_char_windows_too_few_characters() {
+        assert_eq!("foo".char_windows(4).next(), None);
+        assert_eq!("".char_windows(1).next(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn test_char_windows_zero() {
+        let _ = "foo".char_windows(0);
+    }
+
+    #[test]
+    fn test_char_windows_matches_substring() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let len = s.chars().count();
+            for size in 1..8 {
+                let expected: Vec<&str> = (0..(len + 1).saturating_sub(size))
+                    .map(|start| s.substring(start, start + size))
+                    .collect();
+                assert_eq!(s.char_windows(size).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_windows_size_hint() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b", "😀😀😀😀😀"].iter() {
+            for size in 1..8 {
+                let mut windows = s.char_windows(size);
+                loop {
+                    let (lower, upper) = windows.size_hint();
+                    let count = windows.clone().count();
+                    assert!(lower <= count);
+                    assert!(count <= upper.unwrap());
+                    if windows.next().is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_take_chars() {
+        assert_eq!("foobar".take_chars(0), "");
+        assert_eq!("foobar".take_chars(3), "foo");
+        assert_eq!("😀a𝔘b".take_chars(3), "😀a𝔘");
+        assert_eq!("foobar".take_chars(6), "foobar");
+        assert_eq!("foobar".take_chars(usize::max_value()), "foobar");
+    }
+
+    #[test]
+    fn test_skip_chars() {
+        let s = "😀a𝔘b";
+        assert_eq!(s.skip_chars(0), s);
+        assert_eq!(s.skip_chars(1), "a𝔘b");
+        assert_eq!(s.skip_chars(4), "");
+        assert_eq!(s.skip_chars(4).as_ptr(), s[10..].as_ptr());
+        assert_eq!(s.skip_chars(usize::max_value()).as_ptr(), s[10..].as_ptr());
+    }
+
+    #[test]
+    fn test_take_and_skip_chars_match_substring() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            for n in 0..10 {
+                assert_eq!(s.take_chars(n), s.substring(0, n));
+                assert_eq!(s.skip_chars(n), s.substring(n, usize::max_value()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_substring() {
+        assert_eq!(format!("{}", "fõøbα®".display_substring(2..5)), "øbα");
+        assert_eq!(format!("{}", "fõøbα®".display_substring(4..100)), "α®");
+        let (start, end) = (4, 2);
+        assert_eq!(format!("{}", "fõøbα®".display_substring(start..end)), "");
+    }
+
+    #[test]
+    fn test_utf16_substring_astral_mixed_with_ascii() {
+        // "Hi " occupies units 0..3, each of the seven astral letters two units, then " 😀!".
+        let s = "Hi 𝔘𝔫𝔦𝔠𝔬𝔡𝔢 😀!";
+        assert_eq!(s.utf16_substring(3..17), "𝔘𝔫𝔦𝔠𝔬𝔡𝔢");
+        assert_eq!(s.utf16_substring(5..9), "𝔫𝔦");
+        assert_eq!(s.utf16_substring(18..), "😀!");
+        assert_eq!(s.utf16_substring(4..10), "𝔫𝔦");
+        assert_eq!(s.utf16_substring(19..21), "!");
+        assert_eq!(s.utf16_substring(21..30), "");
+    }
+
+    #[test]
+    fn test_utf16_substring_matches_utf16_encoding() {
+        let s = "Hi 𝔘𝔫𝔦 😀!";
+        let units: Vec<u16> = s.encode_utf16().collect();
+        for start in 0..(units.len() + 2) {
+            for end in start..(units.len() + 2) {
+                let clamped = &units[start.min(units.len())..end.min(units.len())];
+                if let Ok(expected) = ::std::string::String::from_utf16(clamped) {
+                    assert_eq!(s.utf16_substring(start..end), expected);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_chars_with() {
+        assert_eq!("😀a𝔘bõ".truncate_chars_with(4, "..."), "😀...");
+        assert_eq!("😀a𝔘bõ".truncate_chars_with(4, ""), "😀a𝔘b");
+        assert_eq!("😀a𝔘bõ".truncate_chars_with(5, "..."), "😀a𝔘bõ");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_chars_with_borrowed_when_short_enough() {
+        let s = "fõøbα®";
+        match s.truncate_chars_with(6, "…") {
+            Cow::Borrowed(borrowed) => assert_eq!(borrowed.as_ptr(), s.as_ptr()),
+            Cow::Owned(_) => panic!("expected a borrowed string"),
+        }
+        assert_eq!("".truncate_chars_with(0, "…"), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_chars_with_suffix_longer_than_max() {
+        assert_eq!("fõøbα®".truncate_chars_with(2, "..."), "..");
+        assert_eq!("fõøbα®".truncate_chars_with(3, "..."), "...");
+        assert_eq!("fõøbα®".truncate_chars_with(0, "…"), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_truncate_chars_with_never_exceeds_max() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            for suffix in ["", "…", "..."].iter() {
+                for max_chars in 0..8 {
+                    let truncated = s.truncate_chars_with(max_chars, suffix);
+                    assert!(truncated.chars().count() <= max_chars);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_width_substring_cjk() {
+        let s = "日本語";
+        assert_eq!(s.width_substring(..), s);
+        assert_eq!(s.width_substring(0..2), "日");
+        assert_eq!(s.width_substring(2..6), "本語");
+        assert_eq!(s.width_substring(1..), "本語");
+        assert_eq!(s.width_substring(..3), "日");
+        assert_eq!(s.width_substring(1..3), "");
+        assert_eq!(s.width_substring(1..3).as_ptr(), s[3..].as_ptr());
+    }
+
+    #[test]
+    fn test_width_substring_emoji() {
+        let s = "a😀b🦀c";
+        assert_eq!(s.width_substring(1..3), "😀");
+        assert_eq!(s.width_substring(2..), "b🦀c");
+        assert_eq!(s.width_substring(..5), "a😀b");
+        assert_eq!(s.width_substring(3..7), "b🦀c");
+    }
+
+    #[test]
+    fn test_width_substring_combining() {
+        let s = "cafe\u{301} au lait";
+        assert_eq!(s.width_substring(..4), "cafe\u{301}");
+        assert_eq!(s.width_substring(3..5), "e\u{301} ");
+        assert_eq!(s.width_substring(4..), " au lait");
+    }
+
+    #[test]
+    fn test_width_substring_empty() {
+        let s = "日本語";
+        let (start, end) = (4, 2);
+        assert_eq!(s.width_substring(start..end), "");
+        assert_eq!(s.width_substring(start..end).as_ptr(), s[6..].as_ptr());
+        assert_eq!(s.width_substring(10..), "");
+        assert_eq!("".width_substring(..), "");
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!("日本語".truncate_to_width(6), "日本語");
+        assert_eq!("日本語".truncate_to_width(5), "日本");
+        assert_eq!("日本語".truncate_to_width(1), "");
+        assert_eq!("a😀b".truncate_to_width(2), "a");
+        assert_eq!("e\u{301}x".truncate_to_width(1), "e\u{301}");
+        assert_eq!("foobar".truncate_to_width(0), "");
+    }
+
+    #[test]
+    fn test_substring_len() {
+        let s = "fõøbα®";
+        assert_eq!(s.substring_len(0, 6), s);
+        assert_eq!(s.substring_len(1, 3), "õøb");
+        assert_eq!(s.substring_len(2, 0), "");
+        assert_eq!(s.substring_len(2, 0).as_ptr(), s[3..].as_ptr());
+        assert_eq!(s.substring_len(4, 10), "α®");
+    }
+
+    #[test]
+    fn test_substring_len_clamped() {
+        let s = "fõøbα®";
+        assert_eq!(s.substring_len(6, 2), "");
+        assert_eq!(s.substring_len(8, 2), "");
+        assert_eq!(s.substring_len(8, 2).as_ptr(), s[10..].as_ptr());
+        assert_eq!(s.substring_len(3, usize::max_value()), "bα®");
+        assert_eq!(s.substring_len(usize::max_value(), usize::max_value()), "");
+    }
+
+    #[test]
+    fn test_substring_len_matches_substring() {
+        let s = "😀a𝔘bõ";
+        for start in 0..8 {
+            for len in 0..8 {
+                assert_eq!(s.substring_len(start, len), s.substring(start, start + len));
+                assert_eq!(
+                    s.substring_len(start, len).as_ptr(),
+                    s.substring(start, start + len).as_ptr()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_len_strict() {
+        let s = "fõøbα®";
+        assert_eq!(s.substring_len_strict(1, 3), "õøb");
+        assert_eq!(s.substring_len_strict(0, 6), s);
+        assert_eq!(s.substring_len_strict(6, 0), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "end index 8 is out of bounds of string with 6 characters")]
+    fn test_substring_len_strict_past_end() {
+        let _ = "fõøbα®".substring_len_strict(4, 4);
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    #[should_panic(
+        expected = "end index 18446744073709551616 is out of bounds of string with 6 characters"
+    )]
+    fn test_substring_len_strict_overflow() {
+        let _ = "fõøbα®".substring_len_strict(1, usize::max_value());
+    }
+
+    #[test]
+    fn test_substring_between() {
+        let s = "key=[value];";
+        assert_eq!(s.substring_between('[', ']'), Some("value"));
+        assert_eq!(s.substring_between('=', ';'), Some("[value]"));
+        assert_eq!(s.substring_between("key=", ";"), Some("[value]"));
+        assert_eq!(s.substring_between('[', "];"), Some("value"));
+        assert_eq!(
+            s.substring_between('[', ']').unwrap().as_ptr(),
+            s[5..].as_ptr()
+        );
+    }
+
+    #[test]
+    fn test_substring_between_first_occurrences() {
+        assert_eq!("(a)(b)".substring_between('(', ')'), Some("a"));
+        assert_eq!("a)b(c)".substring_between('(', ')'), Some("c"));
+        assert_eq!("--a--b--".substring_between("--", "--"), Some("a"));
+        assert_eq!("fõøbα®".substring_between('õ', 'α'), Some("øb"));
+    }
+
+    #[test]
+    fn test_substring_between_adjacent() {
+        let s = "key=[];";
+        assert_eq!(s.substring_between('[', ']'), Some(""));
+        assert_eq!(
+            s.substring_between('[', ']').unwrap().as_ptr(),
+            s[5..].as_ptr()
+        );
+        assert_eq!("foobar".substring_between("", ""), Some(""));
+    }
+
+    #[test]
+    fn test_substring_between_missing() {
+        assert_eq!("key=value]".substring_between('[', ']'), None);
+        assert_eq!("key=[value".substring_between('[', ']'), None);
+        assert_eq!("]key=[value".substring_between('[', ']'), None);
+        assert_eq!("".substring_between('[', ']'), None);
+    }
+
+    #[test]
+    fn test_substring_before_and_after() {
+        let s = "key:value:more";
+        assert_eq!(s.substring_before(':'), Some("key"));
+        assert_eq!(s.substring_before_last(':'), Some("key:value"));
+        assert_eq!(s.substring_after(':'), Some("value:more"));
+        assert_eq!(s.substring_after_last(':'), Some("more"));
+        assert_eq!(s.substring_after(':').unwrap().as_ptr(), s[4..].as_ptr());
+    }
+
+    #[test]
+    fn test_substring_before_and_after_missing() {
+        let s = "key value";
+        assert_eq!(s.substring_before(':'), None);
+        assert_eq!(s.substring_before_last(':'), None);
+        assert_eq!(s.substring_after("::"), None);
+        assert_eq!(s.substring_after_last("::"), None);
+        assert_eq!("".substring_before(':'), None);
+    }
+
+    #[test]
+    fn test_substring_before_and_after_delimiter_at_start() {
+        let s = "/path";
+        assert_eq!(s.substring_before('/'), Some(""));
+        assert_eq!(s.substring_before('/').unwrap().as_ptr(), s.as_ptr());
+        assert_eq!(s.substring_before_last('/'), Some(""));
+        assert_eq!(s.substring_after('/'), Some("path"));
+        assert_eq!(s.substring_after_last('/'), Some("path"));
+    }
+
+    #[test]
+    fn test_substring_before_and_after_delimiter_at_end() {
+        let s = "path/";
+        assert_eq!(s.substring_before('/'), Some("path"));
+        assert_eq!(s.substring_after('/'), Some(""));
+        assert_eq!(s.substring_after('/').unwrap().as_ptr(), s[5..].as_ptr());
+        assert_eq!(s.substring_after_last('/'), Some(""));
+    }
+
+    #[test]
+    fn test_substring_before_and_after_multibyte_delimiter() {
+        let s = "fõø→bα→®";
+        assert_eq!(s.substring_before('→'), Some("fõø"));
+        assert_eq!(s.substring_before_last('→'), Some("fõø→bα"));
+        assert_eq!(s.substring_after("→"), Some("bα→®"));
+        assert_eq!(s.substring_after_last("→"), Some("®"));
+    }
+
+    #[test]
+    fn test_substring_before_and_after_overlapping_delimiter() {
+        // The first occurrence of "aa" in "xaaay" starts at byte 1, and the last starts at byte 2.
+        let s = "xaaay";
+        assert_eq!(s.substring_before("aa"), Some("x"));
+        assert_eq!(s.substring_after("aa"), Some("ay"));
+        assert_eq!(s.substring_before_last("aa"), Some("xa"));
+        assert_eq!(s.substring_after_last("aa"), Some("y"));
+    }
+
+    #[test]
+    fn test_substring_idx() {
+        let s = "fõøbα®";
+        assert_eq!(s.substring_idx(2u32..5u32), "øbα");
+        assert_eq!(s.substring_idx(2u8..=4u8), "øbα");
+        assert_eq!(s.substring_idx(3i64..), "bα®");
+        assert_eq!(s.substring_idx(..2i16), "fõ");
+        assert_eq!(s.substring_idx(1usize..3usize), "õø");
+        assert_eq!(s.substring_idx(4u64..100u64), "α®");
+    }
+
+    #[test]
+    fn test_substring_idx_negative() {
+        let s = "fõøbα®";
+        assert_eq!(s.substring_idx(-3i64..2i64), "fõ");
+        assert_eq!(s.substring_idx(-3i64..), s);
+        assert_eq!(s.substring_idx(..-1i32), "");
+        assert_eq!(s.substring_idx(..-1i32).as_ptr(), s.as_ptr());
+        let (start, end) = (2i32, -1i32);
+        assert_eq!(s.substring_idx(start..end), "");
+        assert_eq!(s.substring_idx(start..end).as_ptr(), s[3..].as_ptr());
+    }
+
+    #[test]
+    fn test_substring_idx_matches_substring() {
+        let s = "😀a𝔘b";
+        for start in 0..6 {
+            for end in 0..6 {
+                assert_eq!(
+                    s.substring_idx(start as i32..end as i32),
+                    s.substring(start, end)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_substring_idx() {
+        let s = "fõøbα®";
+        assert_eq!(s.try_substring_idx(2u32..5u32), Ok("øbα"));
+        assert_eq!(s.try_substring_idx(4i64..100i64), Ok("α®"));
+        assert_eq!(
+            s.try_substring_idx(-3i64..2i64),
+            Err(IndexConversionError::new(-3))
+        );
+        assert_eq!(
+            s.try_substring_idx(..=-1i8),
+            Err(IndexConversionError::new(-1))
+        );
+    }
+
+    #[test]
+    fn test_char_to_byte_index() {
+        let s = "fõøbα®";
+        assert_eq!(s.char_to_byte_index(0), Some(0));
+        assert_eq!(s.char_to_byte_index(3), Some(5));
+        assert_eq!(s.char_to_byte_index(6), Some(10));
+        assert_eq!(s.char_to_byte_index(7), None);
+        assert_eq!("".char_to_byte_index(0), Some(0));
+        assert_eq!("".char_to_byte_index(1), None);
+    }
+
+    #[test]
+    fn test_byte_to_char_index() {
+        // "😀" occupies bytes 0..4, and "𝔘" occupies bytes 5..9.
+        let s = "😀a𝔘b";
+        assert_eq!(s.byte_to_char_index(0), Some(0));
+        assert_eq!(s.byte_to_char_index(3), Some(0));
+        assert_eq!(s.byte_to_char_index(4), Some(1));
+        assert_eq!(s.byte_to_char_index(7), Some(2));
+        assert_eq!(s.byte_to_char_index(9), Some(3));
+        assert_eq!(s.byte_to_char_index(10), Some(4));
+        assert_eq!(s.byte_to_char_index(11), None);
+        assert_eq!("".byte_to_char_index(0), Some(0));
+    }
+
+    #[test]
+    fn test_char_and_byte_index_round_trip() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b", " a\u{301} b-c "].iter() {
+            let char_len = s.chars().count();
+            for n in 0..=char_len {
+                let byte = s.char_to_byte_index(n).unwrap();
+                assert!(s.is_char_boundary(byte));
+                assert_eq!(s.byte_to_char_index(byte), Some(n));
+            }
+            for byte in 0..=s.len() {
+                let n = s.byte_to_char_index(byte).unwrap();
+                let start = s.char_to_byte_index(n).unwrap();
+                assert!(start <= byte);
+                assert_eq!(start, s.nearest_char_boundary(byte, Direction::Backward));
+                if byte < s.len() {
+                    assert_eq!(s.char_containing(byte).map(|(n, _, _)| n), Some(n));
+                }
+            }
+            assert_eq!(s.char_to_byte_index(char_len + 1), None);
+            assert_eq!(s.byte_to_char_index(s.len() + 1), None);
+        }
+    }
+
+    #[test]
+    fn test_substrings() {
+        let s = "fõø  bα®  123";
+        let fields: Vec<&str> = s.substrings(&[0..3, 5..8, 10..13]).collect();
+        assert_eq!(fields, ["fõø", "bα®", "123"]);
+        assert_eq!(s.substrings(&[]).count(), 0);
+        assert_eq!(s.substrings(&[0..3, 5..8]).len(), 2);
+    }
+
+    #[test]
+    fn test_substrings_overlapping_and_unsorted() {
+        let s = "fõøbα®";
+        let (start, end) = (4, 2);
+        let ranges = [1..4, 2..3, 0..6, 5..100, start..end, 3..5, 0..1, 7..9];
+        let fields: Vec<&str> = s.substrings(&ranges).collect();
+        assert_eq!(fields, ["õøb", "ø", "fõøbα®", "®", "", "bα", "f", ""]);
+    }
+
+    #[test]
+    fn test_substrings_matches_substring() {
+        let mut ranges = Vec::new();
+        for start in 0..7 {
+            for end in 0..7 {
+                ranges.push(start..end);
+            }
+        }
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            for (sub, range) in s.substrings(&ranges).zip(ranges.iter()) {
+                let expected = s.substring(range.start, range.end);
+                assert_eq!(sub, expected);
+                assert_eq!(sub.as_ptr(), expected.as_ptr());
+            }
+        }
+    }
+
+    #[test]
+    fn test_floor_substring_to_bytes() {
+        let s = "fõøbα®";
+        assert_eq!(s.floor_substring_to_bytes(0), "");
+        assert_eq!(s.floor_substring_to_bytes(1), "f");
+        assert_eq!(s.floor_substring_to_bytes(2), "f");
+        assert_eq!(s.floor_substring_to_bytes(5), "fõø");
+        assert_eq!(s.floor_substring_to_bytes(10), s);
+        assert_eq!(s.floor_substring_to_bytes(100), s);
+    }
+
+    #[test]
+    fn test_floor_substring_to_bytes_emoji_at_limit() {
+        // "😀" occupies bytes 2..6.
+        let s = "ab😀c";
+        assert_eq!(s.floor_substring_to_bytes(6), "ab😀");
+        for max_bytes in 2..6 {
+            assert_eq!(s.floor_substring_to_bytes(max_bytes), "ab");
+        }
+        assert_eq!(s.floor_substring_to_bytes(7), s);
+        assert_eq!("😀".floor_substring_to_bytes(3), "");
+        assert_eq!("😀".floor_substring_to_bytes(4), "😀");
+    }
+
+    #[test]
+    fn test_ceil_substring_to_bytes() {
+        let s = "ab😀c";
+        assert_eq!(s.ceil_substring_to_bytes(0), "");
+        assert_eq!(s.ceil_substring_to_bytes(2), "ab");
+        for min_bytes in 3..7 {
+            assert_eq!(s.ceil_substring_to_bytes(min_bytes), "ab😀");
+        }
+        assert_eq!(s.ceil_substring_to_bytes(7), s);
+        assert_eq!(s.ceil_substring_to_bytes(100), s);
+    }
+
+    #[test]
+    fn test_floor_suffix_to_bytes() {
+        // "😀" occupies bytes 1..5.
+        let s = "a😀bc";
+        assert_eq!(s.floor_suffix_to_bytes(0), "");
+        assert_eq!(s.floor_suffix_to_bytes(0).as_ptr(), s[7..].as_ptr());
+        assert_eq!(s.floor_suffix_to_bytes(2), "bc");
+        for max_bytes in 2..6 {
+            assert_eq!(s.floor_suffix_to_bytes(max_bytes), "bc");
+        }
+        assert_eq!(s.floor_suffix_to_bytes(6), "😀bc");
+        assert_eq!(s.floor_suffix_to_bytes(7), s);
+        assert_eq!(s.floor_suffix_to_bytes(100), s);
+    }
+
+    #[test]
+    fn test_byte_budget_lengths() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            for budget in 0..(s.len() + 2) {
+                let floor = s.floor_substring_to_bytes(budget);
+                assert!(floor.len() <= budget);
+                assert!(
+                    s.len() == floor.len()
+                        || s[floor.len()..].chars().next().unwrap().len_utf8() + floor.len()
+                            > budget
+                );
+                let ceil = s.ceil_substring_to_bytes(budget);
+                assert!(ceil.len() >= budget || ceil.len() == s.len());
+                assert!(s.starts_with(ceil));
+                let suffix = s.floor_suffix_to_bytes(budget);
+                assert!(suffix.len() <= budget && s.ends_with(suffix));
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_at() {
+        let s = "fõøbα®";
+        assert_eq!(s.char_at(0), Some('f'));
+        assert_eq!(s.char_at(1), Some('õ'));
+        assert_eq!(s.char_at(5), Some('®'));
+        assert_eq!(s.char_at(6), None);
+        assert_eq!(s.char_at(usize::max_value()), None);
+        assert_eq!("".char_at(0), None);
+    }
+
+    #[test]
+    fn test_char_at_matches_substring() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b", "a\u{301}👩\u{200d}👩"].iter() {
+            for n in 0..10 {
+                assert_eq!(s.char_at(n), s.substring(n, n + 1).chars().next());
+            }
+        }
+    }
+
+    #[test]
+    fn test_last_char() {
+        assert_eq!("fõøbα®".last_char(), Some('®'));
+        assert_eq!("a😀".last_char(), Some('😀'));
+        assert_eq!("a\u{301}".last_char(), Some('\u{301}'));
+        assert_eq!("".last_char(), None);
+    }
+
+    #[test]
+    fn test_substring_chars() {
+        let s = "fõøbα®";
+        assert!(s.substring_chars(1..4).eq("õøb".chars()));
+        assert!(s.substring_chars(3..).eq("bα®".chars()));
+        assert!(s.substring_chars(..).eq(s.chars()));
+        assert!(s.substring_chars(4..100).eq("α®".chars()));
+        let (start, end) = (4, 2);
+        assert_eq!(s.substring_chars(start..end).next(), None);
+        assert_eq!(s.substring_chars(10..).next(), None);
+    }
+
+    #[test]
+    fn test_substring_chars_lazy_end() {
+        let mut chars = "fõøbα®".substring_chars(1..usize::max_value());
+        assert_eq!(chars.next(), Some('õ'));
+        assert_eq!(chars.size_hint().1, Some(7));
+        assert_eq!(chars.next_back(), Some('®'));
+        assert!(chars.eq("øbα".chars()));
+    }
+
+    #[test]
+    fn test_substring_chars_double_ended() {
+        let s = "😀a𝔘bõ";
+        for start in 0..7 {
+            for end in 0..7 {
+                let expected: Vec<char> = s.substring(start, end).chars().collect();
+                let forward: Vec<char> = s.substring_chars(start..end).collect();
+                let mut backward: Vec<char> = s.substring_chars(start..end).rev().collect();
+                backward.reverse();
+                assert_eq!(forward, expected);
+                assert_eq!(backward, expected);
+
+                let mut chars = s.substring_chars(start..end);
+                let mut front = Vec::new();
+                let mut back = Vec::new();
+                while let Some(c) = chars.next() {
+                    front.push(c);
+                    if let Some(c) = chars.next_back() {
+                        back.push(c);
+                    }
+                }
+                back.reverse();
+                front.extend(back);
+                assert_eq!(front, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_char_indices() {
+        let s = "😀a𝔘bõ";
+        for start in 0..7 {
+            for end in 0..7 {
+                let sub = s.substring(start, end);
+                let offset = sub.as_ptr() as usize - s.as_ptr() as usize;
+                let expected: Vec<(usize, char)> = sub
+                    .char_indices()
+                    .map(|(index, c)| (offset + index, c))
+                    .collect();
+                let forward: Vec<(usize, char)> = s.substring_char_indices(start..end).collect();
+                let mut backward: Vec<(usize, char)> =
+                    s.substring_char_indices(start..end).rev().collect();
+                backward.reverse();
+                assert_eq!(forward, expected);
+                assert_eq!(backward, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_partition_at_char_range() {
+        let s = "fõøbα®";
+        assert_eq!(s.partition_at_char_range(2..4), ("fõ", "øb", "α®"));
+        assert_eq!(s.partition_at_char_range(..), ("", s, ""));
+        assert_eq!(s.partition_at_char_range(..=0), ("", "f", "õøbα®"));
+        assert_eq!(s.partition_at_char_range(4..10), ("fõøb", "α®", ""));
+        assert_eq!(s.partition_at_char_range(8..10), (s, "", ""));
+        let (start, end) = (4, 2);
+        assert_eq!(s.partition_at_char_range(start..end), ("fõøb", "", "α®"));
+    }
+
+    #[test]
+    fn test_partition_at_char_range_reassembles() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b", " a\u{301} b-c "].iter() {
+            for start in 0..12 {
+                for end in 0..12 {
+                    let (before, middle, after) = s.partition_at_char_range(start..end);
+                    assert_eq!(middle, s.substring(start, end));
+                    assert_eq!(middle.as_ptr(), s.substring(start, end).as_ptr());
+                    assert_eq!([before, middle, after].concat(), *s);
+                    assert_eq!(before.as_ptr(), s.as_ptr());
+                    assert_eq!(before.len() + middle.len() + after.len(), s.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_by_chars_matches_substring() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            for start in 0..8 {
+                for end in 0..8 {
+                    let result = s.substring_by(ByChars, start..end);
+                    assert_eq!(result, s.substring(start, end));
+                    assert_eq!(result.as_ptr(), s.substring(start, end).as_ptr());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_substring_by_custom_segmenter() {
+        // Divides a string into the runs of characters between dashes.
+        let dashes = |s: &str, from: usize| -> Option<Range<usize>> {
+            let start = from + s.get(from..)?.find(|c| c != '-')?;
+            let end = s[start..]
+                .find('-')
+                .map_or(s.len(), |offset| start + offset);
+            Some(start..end)
+        };
+        let s = "-fõø--bαr-baz";
+        assert_eq!(s.substring_by(dashes, ..), "fõø--bαr-baz");
+        assert_eq!(s.substring_by(dashes, 1..2), "bαr");
+        assert_eq!(s.substring_by(dashes, ..2), "fõø--bαr");
+        assert_eq!(s.substring_by(dashes, 2..10), "baz");
+        assert_eq!(s.substring_by(dashes, 3..), "");
+        assert_eq!(s.substring_by(dashes, 3..).as_ptr(), s[s.len()..].as_ptr());
+        let (start, end) = (2, 1);
+        assert_eq!(
+            s.substring_by(dashes, start..end).as_ptr(),
+            s[13..].as_ptr()
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_pad_chars() {
+        assert_eq!("fõ".pad_chars(5, ' '), "fõ   ");
+        assert_eq!("fõ".pad_chars(4, '·'), "fõ··");
+        assert_eq!("fõ".pad_chars(4, '·').len(), 7);
+        assert_eq!("".pad_chars(2, '😀'), "😀😀");
+        assert_eq!("fõ".pad_chars(0, ' '), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_pad_chars_truncates() {
+        let s = "fõøbar";
+        assert_eq!(s.pad_chars(3, ' '), s.substring(0, 3));
+        match s.pad_chars(3, ' ') {
+            Cow::Borrowed(borrowed) => assert_eq!(borrowed.as_ptr(), s.as_ptr()),
+            Cow::Owned(_) => panic!("expected a borrowed string"),
+        }
+        match s.pad_chars(6, ' ') {
+            Cow::Borrowed(borrowed) => assert_eq!(borrowed, s),
+            Cow::Owned(_) => panic!("expected a borrowed string"),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_center_chars() {
+        assert_eq!("fõ".center_chars(5, '·'), "·fõ··");
+        assert_eq!("fõ".center_chars(6, '·'), "··fõ··");
+        assert_eq!("fõ".center_chars(3, '😀'), "fõ😀");
+        assert_eq!("fõ".center_chars(4, '😀').len(), 11);
+        assert_eq!("".center_chars(3, '-'), "---");
+        assert_eq!("fõ".center_chars(0, '·'), "");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_center_chars_truncates() {
+        let s = "fõøbar";
+        assert_eq!(s.center_chars(3, '·'), s.substring(0, 3));
+        match s.center_chars(3, '·') {
+            Cow::Borrowed(borrowed) => assert_eq!(borrowed.as_ptr(), s.as_ptr()),
+            Cow::Owned(_) => panic!("expected a borrowed string"),
+        }
+        match s.center_chars(6, '·') {
+            Cow::Borrowed(borrowed) => assert_eq!(borrowed, s),
+            Cow::Owned(_) => panic!("expected a borrowed string"),
+        }
+    }
+}
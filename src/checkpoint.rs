@@ -0,0 +1,157 @@
+//! Resumption of character walks from known positions.
+
+use core::str::CharIndices;
+use error::CharBoundaryError;
+
+/// A known position within a string, pairing a character index with its byte index.
+///
+/// Checkpoints allow character walks to resume from a position that is already known, rather than
+/// restarting from the beginning of the string. They are obtained from [`checkpoint()`], from
+/// [`CharIndicesFrom::checkpoint()`], or by pairing a position found by other means using
+/// [`new()`].
+///
+/// Example:
+/// ```
+/// use substring::{CharSubstring, Substring};
+///
+/// let s = "fõøbα®";
+/// let checkpoint = s.checkpoint(2);
+/// assert_eq!(checkpoint.byte_idx(), 3);
+/// assert_eq!(s.substring_from_checkpoint(checkpoint, 1..3), Ok(s.substring(3, 5)));
+/// ```
+///
+/// [`checkpoint()`]: trait.CharSubstring.html#tymethod.checkpoint
+/// [`CharIndicesFrom::checkpoint()`]: struct.CharIndicesFrom.html#method.checkpoint
+/// [`new()`]: #method.new
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Checkpoint {
+    char_idx: usize,
+    byte_idx: usize,
+}
+
+impl Checkpoint {
+    /// Creates a checkpoint pairing the character index `char_idx` with the byte index `byte_idx`
+    /// within `s`.
+    ///
+    /// Returns an error if `byte_idx` is not on a character boundary of `s`. As verifying
+    /// `char_idx` would require walking `s`, it is trusted to be the number of characters before
+    /// `byte_idx`; an incorrect `char_idx` offsets the character indices resolved from the
+    /// checkpoint, but is otherwise harmless.
+    pub fn new(s: &str, char_idx: usize, byte_idx: usize) -> Result<Self, CharBoundaryError> {
+        if s.is_char_boundary(byte_idx) {
+            Ok(Checkpoint { char_idx, byte_idx })
+        } else {
+            Err(CharBoundaryError::new(byte_idx))
+        }
+    }
+
+    pub(crate) fn new_unchecked(char_idx: usize, byte_idx: usize) -> Self {
+        Checkpoint { char_idx, byte_idx }
+    }
+
+    /// Returns the character index of the checkpoint.
+    pub fn char_idx(&self) -> usize {
+        self.char_idx
+    }
+
+    /// Returns the byte index of the checkpoint.
+    pub fn byte_idx(&self) -> usize {
+        self.byte_idx
+    }
+
+    /// Returns an error if the checkpoint's byte index is not on a character boundary of `s`.
+    pub(crate) fn check(&self, s: &str) -> Result<(), CharBoundaryError> {
+        if s.is_char_boundary(self.byte_idx) {
+            Ok(())
+        } else {
+            Err(CharBoundaryError::new(self.byte_idx))
+        }
+    }
+}
+
+/// An iterator over the characters of a string following a [`Checkpoint`], along with their
+/// character and byte indices.
+///
+/// This struct is created by the [`char_indices_from()`] method on [`CharSubstring`].
+///
+/// [`Checkpoint`]: struct.Checkpoint.html
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`char_indices_from()`]: trait.CharSubstring.html#tymethod.char_indices_from
+#[derive(Clone, Debug)]
+pub struct CharIndicesFrom<'a> {
+    indices: CharIndices<'a>,
+    base: Checkpoint,
+    /// The number of characters yielded so far.
+    count: usize,
+    /// The byte index following the last character yielded, relative to the checkpoint.
+    offset: usize,
+}
+
+impl<'a> CharIndicesFrom<'a> {
+    pub(crate) fn new(tail: &'a str, base: Checkpoint) -> Self {
+        CharIndicesFrom {
+            indices: tail.char_indices(),
+            base,
+            count: 0,
+            offset: 0,
+        }
+    }
+
+    /// Returns a checkpoint at the position of the next character to be yielded.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint::new_unchecked(
+            self.base.char_idx + self.count,
+            self.base.byte_idx + self.offset,
+        )
+    }
+}
+
+impl<'a> Iterator for CharIndicesFrom<'a> {
+    type Item = (usize, usize, char);
+
+    fn next(&mut self) -> Option<(usize, usize, char)> {
+        self.indices.next().map(|(index, c)| {
+            let item = (
+                self.base.char_idx + self.count,
+                self.base.byte_idx + index,
+                c,
+            );
+            self.count += 1;
+            self.offset = index + c.len_utf8();
+            item
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Checkpoint;
+    use error::CharBoundaryError;
+
+    #[test]
+    fn test_new() {
+        let checkpoint = Checkpoint::new("fõøbα®", 2, 3).unwrap();
+        assert_eq!(checkpoint.char_idx(), 2);
+        assert_eq!(checkpoint.byte_idx(), 3);
+        assert_eq!(
+            Checkpoint::new("fõøbα®", 10, 10).map(|checkpoint| checkpoint.byte_idx()),
+            Ok(10)
+        );
+    }
+
+    #[test]
+    fn test_new_not_boundary() {
+        assert_eq!(
+            Checkpoint::new("fõøbα®", 1, 2),
+            Err(CharBoundaryError::new(2))
+        );
+        assert_eq!(
+            Checkpoint::new("fõøbα®", 6, 11),
+            Err(CharBoundaryError::new(11))
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Substrings of text split across a sequence of chunks.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use bounds;
+use core::ops::RangeBounds;
+use nth_char_boundary;
+
+/// Returns the number of characters in `s`, by counting the bytes which begin characters.
+fn char_count(s: &str) -> usize {
+    s.bytes().filter(|&byte| byte & 0xc0 != 0x80).count()
+}
+
+/// Provides substring methods for iterators over the chunks of a text.
+///
+/// Each chunk continues the text from where the previous chunk ended, as with the segments of a
+/// rope or the output of a streaming decoder. Chunks are string slices, so no character is split
+/// between two chunks. Character indices count from the start of the first chunk, and ranges are
+/// clamped to the end of the text just as with [`substring()`].
+///
+/// Chunks before the range are skipped by counting their characters, and no chunk is consumed
+/// after the end of the range is reached.
+///
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+pub trait IterSubstring<'a>: Iterator<Item = &'a str> + Sized {
+    /// Returns an iterator over the pieces of the chunks which make up the characters within
+    /// `range`.
+    ///
+    /// Every piece is a non-empty slice of a chunk. Nothing is allocated.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::IterSubstring;
+    ///
+    /// let chunks = vec!["fõ", "øbα", "®"];
+    /// assert!(chunks
+    ///     .into_iter()
+    ///     .substring_pieces(1..4)
+    ///     .eq(vec!["õ", "øb"]));
+    /// ```
+    fn substring_pieces<R: RangeBounds<usize>>(self, range: R) -> SubstringPieces<'a, Self>;
+
+    /// Collects the characters within `range` into a `String`.
+    ///
+    /// This requires the `alloc` feature.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::IterSubstring;
+    ///
+    /// let chunks = vec!["fõ", "øbα", "®"];
+    /// assert_eq!(chunks.into_iter().substring(1..5), "õøbα");
+    /// ```
+    #[cfg(feature = "alloc")]
+    fn substring<R: RangeBounds<usize>>(self, range: R) -> String;
+}
+
+impl<'a, I: Iterator<Item = &'a str>> IterSubstring<'a> for I {
+    fn substring_pieces<R: RangeBounds<usize>>(self, range: R) -> SubstringPieces<'a, Self> {
+        let resolved = bounds::resolve(&range);
+        SubstringPieces {
+            chunks: self,
+            skip: resolved.start,
+            take: resolved.end.map(|end| end.saturating_sub(resolved.start)),
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn substring<R: RangeBounds<usize>>(self, range: R) -> String {
+        self.substring_pieces(range).collect()
+    }
+}
+
+/// An iterator over the pieces of a sequence of chunks within a range of characters.
+///
+/// This struct is created by the [`substring_pieces()`] method on [`IterSubstring`].
+///
+/// [`IterSubstring`]: trait.IterSubstring.html
+/// [`substring_pieces()`]: trait.IterSubstring.html#tymethod.substring_pieces
+#[derive(Clone, Debug)]
+pub struct SubstringPieces<'a, I: Iterator<Item = &'a str>> {
+    chunks: I,
+    /// The number of characters yet to be skipped before the range.
+    skip: usize,
+    /// The number of characters yet to be taken, or `None` if the range is unbounded.
+    take: Option<usize>,
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for SubstringPieces<'a, I> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        loop {
+            if self.take == Some(0) {
+                return None;
+            }
+            let mut chunk = self.chunks.next()?;
+            if self.skip > 0 {
+                let count = char_count(chunk);
+                if count <= self.skip {
+                    self.skip -= count;
+                    continue;
+                }
+                let start = nth_char_boundary(chunk, self.skip);
+                // SAFETY: `start` lies on a UTF-8 sequence boundary within the bounds of `chunk`.
+                chunk = slice_unchecked!(chunk, start, chunk.len());
+                self.skip = 0;
+            }
+            if let Some(take) = self.take {
+                let end = nth_char_boundary(chunk, take);
+                if end == chunk.len() {
+                    self.take = Some(take - char_count(chunk));
+                } else {
+                    // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `chunk`.
+                    chunk = slice_unchecked!(chunk, 0, end);
+                    self.take = Some(0);
+                }
+            }
+            if !chunk.is_empty() {
+                return Some(chunk);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IterSubstring;
+    use std::cell::Cell;
+    use std::vec::Vec;
+    use Substring;
+
+    const CHUNKS: [&str; 6] = ["fõ", "", "øbα", "®", "😀a", "𝔘b"];
+
+    fn pieces<'a, R: ::core::ops::RangeBounds<usize>>(
+        chunks: &[&'a str],
+        range: R,
+    ) -> Vec<&'a str> {
+        chunks.iter().cloned().substring_pieces(range).collect()
+    }
+
+    #[test]
+    fn test_substring_pieces() {
+        assert_eq!(pieces(&CHUNKS, 1..4), ["õ", "øb"]);
+        assert_eq!(pieces(&CHUNKS, 2..5), ["øbα"]);
+        assert_eq!(pieces(&CHUNKS, 5..), ["®", "😀a", "𝔘b"]);
+        assert_eq!(pieces(&CHUNKS, ..=0), ["f"]);
+        assert_eq!(pieces(&CHUNKS, 7..100), ["a", "𝔘b"]);
+    }
+
+    #[test]
+    fn test_substring_pieces_empty() {
+        let (start, end) = (4, 2);
+        assert_eq!(pieces(&CHUNKS, start..end), Vec::<&str>::new());
+        assert_eq!(pieces(&CHUNKS, 3..3), Vec::<&str>::new());
+        assert_eq!(pieces(&CHUNKS, 10..), Vec::<&str>::new());
+        assert_eq!(pieces(&[], ..), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_stops_consuming_at_end() {
+        let consumed = Cell::new(0);
+        let chunks = CHUNKS
+            .iter()
+            .cloned()
+            .inspect(|_| consumed.set(consumed.get() + 1));
+        assert_eq!(chunks.substring_pieces(1..5).count(), 2);
+        assert_eq!(consumed.get(), 3);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_substring() {
+        assert_eq!(CHUNKS.iter().cloned().substring(1..5), "õøbα");
+        assert_eq!(CHUNKS.iter().cloned().substring(..), "fõøbα®😀a𝔘b");
+    }
+
+    #[test]
+    fn test_matches_substring_of_concatenation() {
+        let whole: ::std::string::String = CHUNKS.concat();
+        for start in 0..12 {
+            for end in 0..12 {
+                assert_eq!(
+                    pieces(&CHUNKS, start..end).concat(),
+                    whole.substring(start, end)
+                );
+            }
+        }
+    }
+}
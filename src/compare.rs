@@ -0,0 +1,141 @@
+//! Comparison of character ranges across different strings.
+
+use bounds;
+use core::cmp::Ordering;
+use core::iter::{Skip, Take};
+use core::ops::RangeBounds;
+use core::str::Chars;
+
+/// Returns the characters of `s` within `range`, found lazily as they are iterated.
+fn range_chars<'a, R: RangeBounds<usize>>(s: &'a str, range: &R) -> Take<Skip<Chars<'a>>> {
+    let resolved = bounds::resolve(range);
+    let len = resolved
+        .end
+        .map_or(usize::max_value(), |end| end.saturating_sub(resolved.start));
+    s.chars().skip(resolved.start).take(len)
+}
+
+/// Returns whether the characters of `a` within `range_a` equal the characters of `b` within
+/// `range_b`.
+///
+/// Ranges are clamped to the ends of their strings, as with [`substring()`]. The two strings are
+/// walked together, and the walk stops at the first differing character or as soon as one range
+/// ends before the other. Nothing is allocated.
+///
+/// Example:
+/// ```
+/// use substring::ranges_equal;
+///
+/// assert!(ranges_equal("fõøbα®", 1..3, "-õø-", 1..3));
+/// assert!(!ranges_equal("fõøbα®", 1..4, "-õø-", 1..4));
+/// ```
+///
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+pub fn ranges_equal<R, S>(a: &str, range_a: R, b: &str, range_b: S) -> bool
+where
+    R: RangeBounds<usize>,
+    S: RangeBounds<usize>,
+{
+    range_chars(a, &range_a).eq(range_chars(b, &range_b))
+}
+
+/// Compares the characters of `a` within `range_a` with the characters of `b` within `range_b`
+/// lexicographically.
+///
+/// The ordering is the same as that of the two substrings as string slices. As with
+/// [`ranges_equal()`], the strings are walked together only as far as their first difference.
+///
+/// Example:
+/// ```
+/// use std::cmp::Ordering;
+/// use substring::compare_ranges;
+///
+/// assert_eq!(compare_ranges("foobar", 3.., "bar", ..), Ordering::Equal);
+/// assert_eq!(compare_ranges("foobar", ..2, "foo", ..), Ordering::Less);
+/// ```
+///
+/// [`ranges_equal()`]: fn.ranges_equal.html
+pub fn compare_ranges<R, S>(a: &str, range_a: R, b: &str, range_b: S) -> Ordering
+where
+    R: RangeBounds<usize>,
+    S: RangeBounds<usize>,
+{
+    range_chars(a, &range_a).cmp(range_chars(b, &range_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_ranges, ranges_equal};
+    use core::cmp::Ordering;
+    use Substring;
+
+    const CORPUS: [&str; 7] = ["", "foobar", "foo", "fõøbα®", "õø", "😀a𝔘b", "bar😀"];
+
+    /// Returns a pseudorandom sequence of values, by xorshift.
+    fn values(count: usize) -> impl Iterator<Item = u64> {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        (0..count).map(move |_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+    }
+
+    #[test]
+    fn test_ranges_equal() {
+        assert!(ranges_equal("foobar", 3..6, "bar", 0..3));
+        assert!(ranges_equal("foobar", 3.., "bar", ..));
+        assert!(!ranges_equal("foobar", 2..6, "bar", ..));
+    }
+
+    #[test]
+    fn test_ranges_equal_one_ends_early() {
+        assert!(!ranges_equal("foo", .., "foobar", ..));
+        assert!(!ranges_equal("foobar", .., "foo", ..));
+    }
+
+    #[test]
+    fn test_ranges_equal_clamped() {
+        assert!(ranges_equal("foobar", 3..100, "bar", ..));
+        assert!(ranges_equal("foobar", 10..20, "", ..));
+    }
+
+    #[test]
+    fn test_ranges_equal_empty() {
+        let (start, end) = (4, 2);
+        assert!(ranges_equal("foobar", start..end, "baz", 1..1));
+        assert!(ranges_equal("", .., "foobar", 6..));
+    }
+
+    #[test]
+    fn test_compare_ranges() {
+        assert_eq!(compare_ranges("foobar", 0..3, "foo", ..), Ordering::Equal);
+        assert_eq!(compare_ranges("foobar", 3.., "foo", ..), Ordering::Less);
+        assert_eq!(compare_ranges("foobar", .., "foo", ..), Ordering::Greater);
+        assert_eq!(compare_ranges("õ", .., "😀", ..), Ordering::Less);
+    }
+
+    #[test]
+    fn test_matches_slicing() {
+        let mut values = values(40_000);
+        let mut next = |bound: u64| values.next().unwrap() % bound;
+        for _ in 0..5_000 {
+            let a = CORPUS[next(CORPUS.len() as u64) as usize];
+            let b = CORPUS[next(CORPUS.len() as u64) as usize];
+            let (start_a, end_a) = (next(9) as usize, next(9) as usize);
+            let (start_b, end_b) = (next(9) as usize, next(9) as usize);
+
+            let slice_a = a.substring(start_a, end_a);
+            let slice_b = b.substring(start_b, end_b);
+            assert_eq!(
+                ranges_equal(a, start_a..end_a, b, start_b..end_b),
+                slice_a == slice_b
+            );
+            assert_eq!(
+                compare_ranges(a, start_a..end_a, b, start_b..end_b),
+                slice_a.cmp(slice_b)
+            );
+        }
+    }
+}
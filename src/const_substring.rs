@@ -0,0 +1,131 @@
+//! Substrings in constant expressions.
+
+#[cfg(not(feature = "forbid_unsafe"))]
+use core::slice;
+use core::str;
+
+/// Returns the byte index at which the `n`th character after the byte index `from` begins, or the
+/// length of `bytes` if there are no more than `n` such characters.
+const fn nth_char_boundary_from(bytes: &[u8], from: usize, n: usize) -> usize {
+    let mut index = from;
+    let mut count = 0;
+    while index < bytes.len() {
+        // Characters begin at every byte which is not a UTF-8 continuation byte.
+        if bytes[index] & 0xc0 != 0x80 {
+            if count == n {
+                return index;
+            }
+            count += 1;
+        }
+        index += 1;
+    }
+    bytes.len()
+}
+
+/// Obtains a string slice containing the characters within the range specified by `start_char`
+/// and `end_char`, in a constant expression.
+///
+/// This has the same semantics as [`substring()`], and can be used to initialize `const` and
+/// `static` items. Characters are found by counting the bytes which are not UTF-8 continuation
+/// bytes. Requires the `const_substring` feature.
+///
+/// Example:
+/// ```
+/// use substring::const_substring;
+///
+/// const PREFIX: &str = const_substring("fõøbar", 0, 3);
+/// assert_eq!(PREFIX, "fõø");
+/// ```
+///
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+#[must_use]
+pub const fn const_substring(s: &str, start_char: usize, end_char: usize) -> &str {
+    let bytes = s.as_bytes();
+    let start_byte = nth_char_boundary_from(bytes, 0, start_char);
+    let end_byte = if end_char <= start_char {
+        start_byte
+    } else {
+        nth_char_boundary_from(bytes, start_byte, end_char - start_char)
+    };
+    slice_bytes(bytes, start_byte, end_byte)
+}
+
+/// Returns the string slice of the bytes from `start_byte` to `end_byte`, which lie on UTF-8
+/// sequence boundaries within the bounds of `bytes`, with `start_byte` not after `end_byte`.
+#[cfg(not(feature = "forbid_unsafe"))]
+const fn slice_bytes(bytes: &[u8], start_byte: usize, end_byte: usize) -> &str {
+    // SAFETY: Both byte indices lie on UTF-8 sequence boundaries within the bounds of `bytes`, and
+    // `start_byte` is not after `end_byte`, so the bytes between them are valid UTF-8.
+    unsafe {
+        str::from_utf8_unchecked(slice::from_raw_parts(
+            bytes.as_ptr().add(start_byte),
+            end_byte - start_byte,
+        ))
+    }
+}
+
+/// Returns the string slice of the bytes from `start_byte` to `end_byte`, which lie on UTF-8
+/// sequence boundaries within the bounds of `bytes`, with `start_byte` not after `end_byte`.
+///
+/// Ranges cannot be used to slice in constant expressions, so the bytes outside the slice are
+/// removed one at a time.
+#[cfg(feature = "forbid_unsafe")]
+const fn slice_bytes(mut bytes: &[u8], start_byte: usize, end_byte: usize) -> &str {
+    let mut removed = 0;
+    while removed < start_byte {
+        if let [_, rest @ ..] = bytes {
+            bytes = rest;
+        }
+        removed += 1;
+    }
+    while bytes.len() > end_byte - start_byte {
+        if let [rest @ .., _] = bytes {
+            bytes = rest;
+        }
+    }
+    match str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(_) => panic!("slice does not lie on UTF-8 sequence boundaries"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::const_substring;
+    use Substring;
+
+    const PREFIX: &str = const_substring("fõøbar", 0, 3);
+    static SUFFIX: &str = const_substring("fõøbα®", 4, 100);
+    const EMPTY: &str = const_substring("fõøbα®", 4, 2);
+
+    const CORPUS: [&str; 5] = ["", "foobar", "fõøbα®", "😀a𝔘b", " a\u{301} b-c "];
+
+    #[test]
+    fn test_const_items() {
+        assert_eq!(PREFIX, "fõø");
+        assert_eq!(SUFFIX, "α®");
+        assert_eq!(EMPTY, "");
+    }
+
+    #[test]
+    fn test_matches_substring() {
+        for s in CORPUS.iter() {
+            for start in 0..12 {
+                for end in 0..12 {
+                    assert_eq!(const_substring(s, start, end), s.substring(start, end));
+                    assert_eq!(
+                        const_substring(s, start, end).as_ptr(),
+                        s.substring(start, end).as_ptr()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_indices() {
+        let s = "fõøbα®";
+        assert_eq!(const_substring(s, 2, usize::MAX), "øbα®");
+        assert_eq!(const_substring(s, usize::MAX, usize::MAX), "");
+    }
+}
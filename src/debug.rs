@@ -0,0 +1,48 @@
+//! Bounded `Debug` output of text.
+
+use core::fmt;
+use Substring;
+
+/// The maximum number of characters of text included in `Debug` output.
+pub(crate) const MAX_DEBUG_CHARS: usize = 32;
+
+/// Text formatted as a `Debug` string literal, truncated to [`MAX_DEBUG_CHARS`] characters.
+///
+/// Truncated text is followed by an ellipsis outside of the quotes.
+pub(crate) struct TruncatedText<'a>(pub(crate) &'a str);
+
+impl<'a> fmt::Debug for TruncatedText<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let shown = self.0.substring(0, MAX_DEBUG_CHARS);
+        fmt::Debug::fmt(shown, f)?;
+        if shown.len() < self.0.len() {
+            f.write_str("…")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TruncatedText, MAX_DEBUG_CHARS};
+    use std::string::String;
+
+    #[test]
+    fn test_short() {
+        assert_eq!(format!("{:?}", TruncatedText("fõø\"bα®")), "\"fõø\\\"bα®\"");
+    }
+
+    #[test]
+    fn test_truncated() {
+        let s: String = (0..(MAX_DEBUG_CHARS + 1)).map(|_| 'é').collect();
+        let expected: String = (0..MAX_DEBUG_CHARS).map(|_| 'é').collect();
+        assert_eq!(
+            format!("{:?}", TruncatedText(&s)),
+            format!("\"{}\"…", expected)
+        );
+        assert_eq!(
+            format!("{:?}", TruncatedText(&s[2..])),
+            format!("\"{}\"", &s[2..])
+        );
+    }
+}
@@ -0,0 +1,101 @@
+//! Delimiters bounding substrings.
+
+use core::ops::Range;
+
+/// A delimiter which can be searched for within a string.
+///
+/// This is implemented for `char` and `&str`, standing in for the unstable `Pattern` trait of the
+/// standard library.
+pub trait Delimiter {
+    /// Returns the byte range of the first occurrence of the delimiter within `haystack`, if there
+    /// is one.
+    ///
+    /// An empty delimiter occurs at the start of every string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::Delimiter;
+    ///
+    /// assert_eq!('='.find_in("key=value"), Some(3..4));
+    /// assert_eq!("=>".find_in("key => value"), Some(4..6));
+    /// assert_eq!(';'.find_in("key=value"), None);
+    /// ```
+    fn find_in(&self, haystack: &str) -> Option<Range<usize>>;
+
+    /// Returns the byte range of the last occurrence of the delimiter within `haystack`, if there
+    /// is one.
+    ///
+    /// An empty delimiter occurs at the end of every string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::Delimiter;
+    ///
+    /// assert_eq!('/'.rfind_in("a/b/c"), Some(3..4));
+    /// assert_eq!("::".rfind_in("a::b::c"), Some(4..6));
+    /// assert_eq!(';'.rfind_in("a/b/c"), None);
+    /// ```
+    fn rfind_in(&self, haystack: &str) -> Option<Range<usize>>;
+}
+
+impl Delimiter for char {
+    fn find_in(&self, haystack: &str) -> Option<Range<usize>> {
+        haystack
+            .find(*self)
+            .map(|index| index..(index + self.len_utf8()))
+    }
+
+    fn rfind_in(&self, haystack: &str) -> Option<Range<usize>> {
+        haystack
+            .rfind(*self)
+            .map(|index| index..(index + self.len_utf8()))
+    }
+}
+
+// Lifetimes may only be elided from impl headers from Rust 1.31 onward.
+#[allow(clippy::needless_lifetimes)]
+impl<'a> Delimiter for &'a str {
+    fn find_in(&self, haystack: &str) -> Option<Range<usize>> {
+        haystack
+            .find(*self)
+            .map(|index| index..(index + self.len()))
+    }
+
+    fn rfind_in(&self, haystack: &str) -> Option<Range<usize>> {
+        haystack
+            .rfind(*self)
+            .map(|index| index..(index + self.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Delimiter;
+
+    #[test]
+    fn test_char() {
+        assert_eq!('['.find_in("key=[value];"), Some(4..5));
+        assert_eq!('α'.find_in("fõøbα®α"), Some(6..8));
+        assert_eq!('x'.find_in("foobar"), None);
+        assert_eq!('x'.find_in(""), None);
+        assert_eq!('α'.rfind_in("fõøbα®α"), Some(10..12));
+        assert_eq!('x'.rfind_in("foobar"), None);
+    }
+
+    #[test]
+    fn test_str() {
+        assert_eq!("<<".find_in("a << b << c"), Some(2..4));
+        assert_eq!("øb".find_in("fõøbα®"), Some(3..6));
+        assert_eq!("baz".find_in("foobar"), None);
+        assert_eq!("<<".rfind_in("a << b << c"), Some(7..9));
+        assert_eq!("aa".rfind_in("aaa"), Some(1..3));
+        assert_eq!("baz".rfind_in("foobar"), None);
+    }
+
+    #[test]
+    fn test_empty_str() {
+        assert_eq!("".find_in("foobar"), Some(0..0));
+        assert_eq!("".find_in(""), Some(0..0));
+        assert_eq!("".rfind_in("foobar"), Some(6..6));
+    }
+}
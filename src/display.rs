@@ -0,0 +1,97 @@
+//! Formatting of substrings without slicing them first.
+
+use bounds;
+use core::fmt;
+use core::ops::RangeBounds;
+use unit::char_range_to_byte_range;
+
+/// The characters of a string within a range, formatted on demand.
+///
+/// This struct is created by the [`display_substring()`] method on [`CharSubstring`]. The range is
+/// only resolved when the value is formatted, and nothing is allocated. Formatting honors width,
+/// fill, alignment, and precision flags just as formatting a `&str` does.
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`display_substring()`]: trait.CharSubstring.html#tymethod.display_substring
+#[derive(Clone, Copy)]
+pub struct SubstringDisplay<'a, R> {
+    s: &'a str,
+    range: R,
+}
+
+impl<'a, R: RangeBounds<usize>> SubstringDisplay<'a, R> {
+    pub(crate) fn new(s: &'a str, range: R) -> Self {
+        SubstringDisplay { s, range }
+    }
+
+    fn as_str(&self) -> &'a str {
+        let resolved = bounds::resolve(&self.range);
+        let bytes = char_range_to_byte_range(self.s, resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `s`.
+        slice_unchecked!(self.s, bytes.start, bytes.end)
+    }
+}
+
+impl<'a, R: RangeBounds<usize>> fmt::Display for SubstringDisplay<'a, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+/// Formats the characters within the range as a string literal, as in `"øbα"`.
+impl<'a, R: RangeBounds<usize>> fmt::Debug for SubstringDisplay<'a, R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubstringDisplay;
+    use core::fmt::Write;
+    use std::string::String;
+    use Substring;
+
+    const CORPUS: [&str; 5] = ["", "foobar", "fõøbα®", "😀a𝔘b", " a\u{301} b-c "];
+
+    #[test]
+    fn test_display_matches_substring() {
+        for s in CORPUS.iter() {
+            for start in 0..12 {
+                for end in 0..12 {
+                    assert_eq!(
+                        format!("{}", SubstringDisplay::new(s, start..end)),
+                        s.substring(start, end)
+                    );
+                }
+                assert_eq!(
+                    format!("{}", SubstringDisplay::new(s, start..)),
+                    s.substring(start, usize::max_value())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let display = SubstringDisplay::new("fõøbα®", 1..4);
+        assert_eq!(format!("[{:>5}]", display), "[  õøb]");
+        assert_eq!(format!("[{:-<5}]", display), "[õøb--]");
+        assert_eq!(format!("[{:.2}]", display), "[õø]");
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(
+            format!("{:?}", SubstringDisplay::new("f\"õøbα®", ..3)),
+            "\"f\\\"õ\""
+        );
+    }
+
+    #[test]
+    fn test_write() {
+        let mut out = String::new();
+        write!(out, "<{}>", SubstringDisplay::new("fõøbα®", 2..=3)).unwrap();
+        assert_eq!(out, "<øb>");
+    }
+}
@@ -0,0 +1,549 @@
+//! Errors returned by fallible methods.
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+#[cfg(feature = "std")]
+use std::io;
+
+/// An error indicating that a range is not valid for a string.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(rustc_1_40, non_exhaustive)]
+pub enum SubstringError {
+    /// The start index is past the end of the string.
+    StartOutOfBounds {
+        /// The requested start index.
+        index: usize,
+        /// The length of the string.
+        char_len: usize,
+    },
+    /// The end index is past the end of the string.
+    EndOutOfBounds {
+        /// The requested end index.
+        index: usize,
+        /// The length of the string.
+        char_len: usize,
+    },
+    /// The start index is after the end index.
+    StartAfterEnd {
+        /// The requested start index.
+        start: usize,
+        /// The requested end index.
+        end: usize,
+    },
+    /// A UTF-16 index lies between the two halves of a surrogate pair.
+    SplitSurrogatePair {
+        /// The requested UTF-16 index.
+        index: usize,
+    },
+}
+
+impl SubstringError {
+    /// Returns a stable numeric code identifying the variant of the error.
+    ///
+    /// The codes are:
+    ///
+    /// | Variant                | Code |
+    /// |------------------------|------|
+    /// | [`StartOutOfBounds`]   | `1`  |
+    /// | [`EndOutOfBounds`]     | `2`  |
+    /// | [`StartAfterEnd`]      | `3`  |
+    /// | [`SplitSurrogatePair`] | `4`  |
+    ///
+    /// Codes are never reassigned; variants added in the future will receive new codes.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::SubstringError;
+    ///
+    /// assert_eq!(SubstringError::StartAfterEnd { start: 3, end: 2 }.code(), 3);
+    /// ```
+    ///
+    /// [`StartOutOfBounds`]: #variant.StartOutOfBounds
+    /// [`EndOutOfBounds`]: #variant.EndOutOfBounds
+    /// [`StartAfterEnd`]: #variant.StartAfterEnd
+    /// [`SplitSurrogatePair`]: #variant.SplitSurrogatePair
+    pub fn code(&self) -> u8 {
+        match *self {
+            SubstringError::StartOutOfBounds { .. } => 1,
+            SubstringError::EndOutOfBounds { .. } => 2,
+            SubstringError::StartAfterEnd { .. } => 3,
+            SubstringError::SplitSurrogatePair { .. } => 4,
+        }
+    }
+
+    /// Reconstructs an error from its [`code()`] and the values of its fields, in the order they
+    /// are declared.
+    ///
+    /// Variants with a single field take its value from the first element of `context`, ignoring
+    /// the second.
+    ///
+    /// Returns `None` if `code` does not identify a variant.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::SubstringError;
+    ///
+    /// let error = SubstringError::EndOutOfBounds {
+    ///     index: 8,
+    ///     char_len: 6,
+    /// };
+    /// assert_eq!(SubstringError::from_code(error.code(), (8, 6)), Some(error));
+    /// assert_eq!(SubstringError::from_code(0, (8, 6)), None);
+    /// ```
+    ///
+    /// [`code()`]: #method.code
+    pub fn from_code(code: u8, context: (usize, usize)) -> Option<Self> {
+        match code {
+            1 => Some(SubstringError::StartOutOfBounds {
+                index: context.0,
+                char_len: context.1,
+            }),
+            2 => Some(SubstringError::EndOutOfBounds {
+                index: context.0,
+                char_len: context.1,
+            }),
+            3 => Some(SubstringError::StartAfterEnd {
+                start: context.0,
+                end: context.1,
+            }),
+            4 => Some(SubstringError::SplitSurrogatePair { index: context.0 }),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SubstringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SubstringError::StartOutOfBounds { index, char_len } => write!(
+                f,
+                "start index {} is out of bounds of string with {} characters",
+                index, char_len
+            ),
+            SubstringError::EndOutOfBounds { index, char_len } => write!(
+                f,
+                "end index {} is out of bounds of string with {} characters",
+                index, char_len
+            ),
+            SubstringError::StartAfterEnd { start, end } => {
+                write!(f, "start index {} is after end index {}", start, end)
+            }
+            SubstringError::SplitSurrogatePair { index } => write!(
+                f,
+                "UTF-16 index {} lies between the halves of a surrogate pair",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for SubstringError {}
+
+/// Converts into an error of kind [`InvalidInput`], displayed in the same way.
+///
+/// [`InvalidInput`]: https://doc.rust-lang.org/std/io/enum.ErrorKind.html#variant.InvalidInput
+#[cfg(feature = "std")]
+impl From<SubstringError> for io::Error {
+    fn from(error: SubstringError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// An error indicating that a byte index does not lie on a character boundary.
+///
+/// Byte indices past the end of the string do not lie on a character boundary either.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CharBoundaryError {
+    index: usize,
+}
+
+impl CharBoundaryError {
+    pub(crate) fn new(index: usize) -> Self {
+        CharBoundaryError { index }
+    }
+
+    /// Returns the byte index that does not lie on a character boundary.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Display for CharBoundaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "byte index {} is not a char boundary", self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for CharBoundaryError {}
+
+/// An error indicating that an index cannot be represented as a `usize`.
+///
+/// This is returned for negative indices, and for indices greater than `usize::MAX`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct IndexConversionError {
+    index: i128,
+}
+
+impl IndexConversionError {
+    pub(crate) fn new(index: i128) -> Self {
+        IndexConversionError { index }
+    }
+
+    /// Returns the index that cannot be represented as a `usize`.
+    pub fn index(&self) -> i128 {
+        self.index
+    }
+
+    /// Returns whether the index is negative, rather than too large.
+    pub fn is_negative(&self) -> bool {
+        self.index < 0
+    }
+}
+
+impl fmt::Display for IndexConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "index {} cannot be represented as a usize", self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for IndexConversionError {}
+
+/// An error indicating that fields could not be filled into a template.
+///
+/// Fields are identified by their index within the slice of fields given to [`fill_ranges()`].
+///
+/// [`fill_ranges()`]: fn.fill_ranges.html
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(rustc_1_40, non_exhaustive)]
+pub enum FillError {
+    /// The field's range ends past the end of the template.
+    OutOfBounds {
+        /// The index of the field.
+        field: usize,
+        /// The length of the template.
+        char_len: usize,
+    },
+    /// The field's range starts after it ends.
+    StartAfterEnd {
+        /// The index of the field.
+        field: usize,
+    },
+    /// The field's value has more characters than its range.
+    ValueTooLong {
+        /// The index of the field.
+        field: usize,
+        /// The number of characters in the value.
+        value_len: usize,
+        /// The number of characters in the range.
+        range_len: usize,
+    },
+    /// The ranges of two fields overlap.
+    Overlapping {
+        /// The index of the earlier field.
+        first: usize,
+        /// The index of the later field.
+        second: usize,
+    },
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for FillError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FillError::OutOfBounds { field, char_len } => write!(
+                f,
+                "range of field {} is out of bounds of template with {} characters",
+                field, char_len
+            ),
+            FillError::StartAfterEnd { field } => {
+                write!(f, "range of field {} starts after it ends", field)
+            }
+            FillError::ValueTooLong {
+                field,
+                value_len,
+                range_len,
+            } => write!(
+                f,
+                "value of field {} has {} characters, but its range has {}",
+                field, value_len, range_len
+            ),
+            FillError::Overlapping { first, second } => {
+                write!(f, "ranges of fields {} and {} overlap", first, second)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for FillError {}
+
+/// An error indicating that values could not be rendered into a record.
+///
+/// Fields are identified by their index within the slice of fields given to [`render_fields()`].
+///
+/// [`render_fields()`]: fn.render_fields.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(rustc_1_40, non_exhaustive)]
+pub enum RenderError {
+    /// The number of values does not match the number of fields.
+    ValueCount {
+        /// The number of fields.
+        fields: usize,
+        /// The number of values.
+        values: usize,
+    },
+    /// A field starts before the end of the field preceding it.
+    Overlapping {
+        /// The index of the earlier field.
+        first: usize,
+        /// The index of the later field.
+        second: usize,
+    },
+    /// The field does not fit within the buffer.
+    BufferTooSmall {
+        /// The index of the field.
+        field: usize,
+        /// The length of the buffer, in bytes.
+        buffer_len: usize,
+    },
+    /// The field is measured in a unit in which values cannot be padded, such as
+    /// [`Unit::Words`].
+    ///
+    /// [`Unit::Words`]: enum.Unit.html#variant.Words
+    UnsupportedUnit {
+        /// The index of the field.
+        field: usize,
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RenderError::ValueCount { fields, values } => {
+                write!(f, "{} values were given for {} fields", values, fields)
+            }
+            RenderError::Overlapping { first, second } => {
+                write!(
+                    f,
+                    "field {} starts before the end of field {}",
+                    second, first
+                )
+            }
+            RenderError::BufferTooSmall { field, buffer_len } => write!(
+                f,
+                "field {} does not fit within buffer of {} bytes",
+                field, buffer_len
+            ),
+            RenderError::UnsupportedUnit { field } => {
+                write!(
+                    f,
+                    "field {} is measured in a unit that cannot be padded",
+                    field
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RenderError {}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "alloc")]
+    use super::FillError;
+    use super::{CharBoundaryError, IndexConversionError, RenderError, SubstringError};
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            format!(
+                "{}",
+                SubstringError::StartOutOfBounds {
+                    index: 7,
+                    char_len: 6
+                }
+            ),
+            "start index 7 is out of bounds of string with 6 characters"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                SubstringError::EndOutOfBounds {
+                    index: 8,
+                    char_len: 6
+                }
+            ),
+            "end index 8 is out of bounds of string with 6 characters"
+        );
+        assert_eq!(
+            format!("{}", SubstringError::StartAfterEnd { start: 3, end: 2 }),
+            "start index 3 is after end index 2"
+        );
+        assert_eq!(
+            format!("{}", SubstringError::SplitSurrogatePair { index: 1 }),
+            "UTF-16 index 1 lies between the halves of a surrogate pair"
+        );
+    }
+
+    #[test]
+    fn test_code() {
+        // These values are stable and must never change.
+        assert_eq!(
+            SubstringError::StartOutOfBounds {
+                index: 7,
+                char_len: 6
+            }
+            .code(),
+            1
+        );
+        assert_eq!(
+            SubstringError::EndOutOfBounds {
+                index: 8,
+                char_len: 6
+            }
+            .code(),
+            2
+        );
+        assert_eq!(SubstringError::StartAfterEnd { start: 3, end: 2 }.code(), 3);
+        assert_eq!(SubstringError::SplitSurrogatePair { index: 1 }.code(), 4);
+    }
+
+    #[test]
+    fn test_from_code_round_trip() {
+        let errors = [
+            SubstringError::StartOutOfBounds {
+                index: 7,
+                char_len: 6,
+            },
+            SubstringError::EndOutOfBounds {
+                index: 8,
+                char_len: 6,
+            },
+            SubstringError::StartAfterEnd { start: 3, end: 2 },
+            SubstringError::SplitSurrogatePair { index: 1 },
+        ];
+        let contexts = [(7, 6), (8, 6), (3, 2), (1, 0)];
+        for (error, &context) in errors.iter().zip(contexts.iter()) {
+            assert_eq!(
+                SubstringError::from_code(error.code(), context),
+                Some(*error)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_code_unknown() {
+        assert_eq!(SubstringError::from_code(0, (0, 0)), None);
+        assert_eq!(SubstringError::from_code(5, (0, 0)), None);
+        assert_eq!(SubstringError::from_code(255, (0, 0)), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_into_io_error() {
+        let error = SubstringError::StartAfterEnd { start: 3, end: 2 };
+        let io_error = ::std::io::Error::from(error);
+        assert_eq!(io_error.kind(), ::std::io::ErrorKind::InvalidInput);
+        assert_eq!(format!("{}", io_error), format!("{}", error));
+    }
+
+    #[test]
+    fn test_char_boundary_error_display() {
+        assert_eq!(
+            format!("{}", CharBoundaryError::new(2)),
+            "byte index 2 is not a char boundary"
+        );
+    }
+
+    #[test]
+    fn test_index_conversion_error_display() {
+        assert_eq!(
+            format!("{}", IndexConversionError::new(-3)),
+            "index -3 cannot be represented as a usize"
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_fill_error_display() {
+        assert_eq!(
+            format!(
+                "{}",
+                FillError::OutOfBounds {
+                    field: 1,
+                    char_len: 6
+                }
+            ),
+            "range of field 1 is out of bounds of template with 6 characters"
+        );
+        assert_eq!(
+            format!("{}", FillError::StartAfterEnd { field: 0 }),
+            "range of field 0 starts after it ends"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                FillError::ValueTooLong {
+                    field: 2,
+                    value_len: 5,
+                    range_len: 3
+                }
+            ),
+            "value of field 2 has 5 characters, but its range has 3"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                FillError::Overlapping {
+                    first: 0,
+                    second: 3
+                }
+            ),
+            "ranges of fields 0 and 3 overlap"
+        );
+    }
+
+    #[test]
+    fn test_render_error_display() {
+        assert_eq!(
+            format!(
+                "{}",
+                RenderError::ValueCount {
+                    fields: 3,
+                    values: 2
+                }
+            ),
+            "2 values were given for 3 fields"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                RenderError::Overlapping {
+                    first: 0,
+                    second: 1
+                }
+            ),
+            "field 1 starts before the end of field 0"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                RenderError::BufferTooSmall {
+                    field: 2,
+                    buffer_len: 16
+                }
+            ),
+            "field 2 does not fit within buffer of 16 bytes"
+        );
+        assert_eq!(
+            format!("{}", RenderError::UnsupportedUnit { field: 1 }),
+            "field 1 is measured in a unit that cannot be padded"
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Generators of pathological strings for testing and benchmarking.
+//!
+//! Each generator deterministically produces the same string for the same arguments, so that
+//! tests and benchmarks built on these strings examine consistent data. Generators taking a `seed`
+//! produce different strings of the same shape for different seeds.
+//!
+//! This module is only available with the `testing` feature enabled.
+
+use alloc::string::String;
+use unit::Unit;
+
+/// A xorshift pseudorandom number generator.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // Xorshift never leaves a state of zero.
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a value within `start..end`.
+    fn range(&mut self, start: u32, end: u32) -> u32 {
+        start + (self.next() % u64::from(end - start)) as u32
+    }
+
+    /// Returns a character within `start..end`, skipping surrogate code points.
+    fn char_in(&mut self, start: u32, end: u32) -> char {
+        loop {
+            if let Some(c) = ::core::char::from_u32(self.range(start, end)) {
+                return c;
+            }
+        }
+    }
+
+    /// Returns a character whose UTF-8 encoding is `width` bytes long.
+    fn char_of_width(&mut self, width: usize) -> char {
+        match width {
+            1 => self.char_in(0x20, 0x7f),
+            2 => self.char_in(0x80, 0x800),
+            3 => self.char_in(0x800, 0x1_0000),
+            _ => self.char_in(0x1_0000, 0x11_0000),
+        }
+    }
+}
+
+/// Returns a string of `len` printable ASCII characters.
+///
+/// Example:
+/// ```
+/// use substring::fixtures;
+///
+/// let s = fixtures::ascii(0, 16);
+/// assert_eq!(s.len(), 16);
+/// assert!(s.is_ascii());
+/// ```
+pub fn ascii(seed: u64, len: usize) -> String {
+    let mut rng = Rng::new(seed);
+    (0..len).map(|_| rng.char_of_width(1)).collect()
+}
+
+/// Returns a string of `len` characters whose UTF-8 encodings cycle through 1, 2, 3, and 4 bytes
+/// long.
+///
+/// Example:
+/// ```
+/// use substring::fixtures;
+///
+/// let s = fixtures::every_scalar_width(0, 8);
+/// let widths: Vec<usize> = s.chars().map(char::len_utf8).collect();
+/// assert_eq!(widths, [1, 2, 3, 4, 1, 2, 3, 4]);
+/// ```
+pub fn every_scalar_width(seed: u64, len: usize) -> String {
+    let mut rng = Rng::new(seed);
+    (0..len).map(|i| rng.char_of_width(i % 4 + 1)).collect()
+}
+
+/// Returns a string of `bases` ASCII letters, each followed by `marks_per_base` combining marks.
+///
+/// Example:
+/// ```
+/// use substring::fixtures;
+///
+/// let s = fixtures::combining_storm(0, 3, 5);
+/// assert_eq!(s.chars().count(), 18);
+/// assert_eq!(s.chars().filter(char::is_ascii_alphabetic).count(), 3);
+/// ```
+pub fn combining_storm(seed: u64, bases: usize, marks_per_base: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut s = String::with_capacity(bases * (1 + marks_per_base * 2));
+    for _ in 0..bases {
+        s.push(rng.char_in(u32::from(b'a'), u32::from(b'z') + 1));
+        for _ in 0..marks_per_base {
+            s.push(rng.char_in(0x300, 0x370));
+        }
+    }
+    s
+}
+
+/// Returns a string of `len` emoji, mixing single emoji, emoji with skin tone modifiers, zero width
+/// joiner sequences, flags, and keycaps.
+///
+/// Each emoji consists of between one and five characters.
+///
+/// Example:
+/// ```
+/// use substring::fixtures;
+///
+/// let s = fixtures::emoji_zoo(0, 10);
+/// assert!(s.chars().count() >= 10);
+/// assert!(s.chars().count() <= 50);
+/// ```
+pub fn emoji_zoo(seed: u64, len: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut s = String::new();
+    for _ in 0..len {
+        match rng.range(0, 5) {
+            0 => s.push(rng.char_in(0x1_f600, 0x1_f650)),
+            1 => {
+                s.push(rng.char_in(0x1_f466, 0x1_f46a));
+                s.push(rng.char_in(0x1_f3fb, 0x1_f400));
+            }
+            2 => {
+                s.push(rng.char_in(0x1_f466, 0x1_f46a));
+                s.push(rng.char_in(0x1_f3fb, 0x1_f400));
+                s.push('\u{200d}');
+                s.push(rng.char_in(0x1_f4bb, 0x1_f4bd));
+            }
+            3 => {
+                s.push(rng.char_in(0x1_f1e6, 0x1_f200));
+                s.push(rng.char_in(0x1_f1e6, 0x1_f200));
+            }
+            _ => {
+                s.push(rng.char_in(u32::from(b'0'), u32::from(b'9') + 1));
+                s.push('\u{fe0f}');
+                s.push('\u{20e3}');
+            }
+        }
+    }
+    s
+}
+
+/// Returns a string of `len` characters mixing Hebrew, Arabic, and Latin words with directional
+/// marks.
+///
+/// Example:
+/// ```
+/// use substring::fixtures;
+///
+/// assert_eq!(fixtures::rtl_mix(0, 32).chars().count(), 32);
+/// ```
+pub fn rtl_mix(seed: u64, len: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut s = String::new();
+    let mut script = 0;
+    for i in 0..len {
+        // Words have between two and seven characters, and are separated by a space or a mark.
+        if i > 0 && rng.range(0, 5) == 0 {
+            s.push(match rng.range(0, 3) {
+                0 => '\u{200e}',
+                1 => '\u{200f}',
+                _ => ' ',
+            });
+            script = rng.range(0, 3);
+            continue;
+        }
+        s.push(match script {
+            0 => rng.char_in(0x5d0, 0x5eb),
+            1 => rng.char_in(0x627, 0x64b),
+            _ => rng.char_in(u32::from(b'a'), u32::from(b'z') + 1),
+        });
+    }
+    s
+}
+
+/// Returns a string of `len` characters that is adversarial to indexing by `unit`.
+///
+/// - For [`Unit::Bytes`], every character is four bytes long, so three of every four byte indices
+///   must be snapped to a character boundary.
+/// - For [`Unit::Chars`], character widths cycle through every UTF-8 encoding length, so no fixed
+///   stride locates a character index.
+/// - For [`Unit::Utf16`], characters alternate between one and two UTF-16 code units, so half of
+///   the characters straddle a code unit index.
+/// - For [`Unit::Width`], characters cycle through two, zero, and one display columns, so every
+///   wide character straddles a column index and is followed by a character with no column of its
+///   own.
+/// - For [`Unit::Words`], letters joined by mid-word punctuation alternate with ideographs carrying
+///   combining marks, so words are never separated by whitespace.
+///
+/// Example:
+/// ```
+/// use substring::{fixtures, Unit};
+///
+/// let s = fixtures::worst_case_for(Unit::Bytes, 4);
+/// assert_eq!(s.len(), 16);
+/// ```
+///
+/// [`Unit::Bytes`]: ../enum.Unit.html#variant.Bytes
+/// [`Unit::Chars`]: ../enum.Unit.html#variant.Chars
+/// [`Unit::Utf16`]: ../enum.Unit.html#variant.Utf16
+/// [`Unit::Width`]: ../enum.Unit.html#variant.Width
+/// [`Unit::Words`]: ../enum.Unit.html#variant.Words
+pub fn worst_case_for(unit: Unit, len: usize) -> String {
+    let mut rng = Rng::new(0);
+    match unit {
+        Unit::Bytes => (0..len).map(|_| rng.char_of_width(4)).collect(),
+        Unit::Chars => every_scalar_width(0, len),
+        Unit::Utf16 => (0..len)
+            .map(|i| rng.char_of_width(if i % 2 == 0 { 3 } else { 4 }))
+            .collect(),
+        Unit::Width => (0..len)
+            .map(|i| match i % 3 {
+                // CJK unified ideographs and combining diacritical marks.
+                0 => rng.char_in(0x4e00, 0x9fa6),
+                1 => rng.char_in(0x300, 0x370),
+                _ => rng.char_in(u32::from(b'a'), u32::from(b'z') + 1),
+            })
+            .collect(),
+        Unit::Words => (0..len)
+            .map(|i| match i % 5 {
+                1 => ['\'', '’', '.'][rng.range(0, 3) as usize],
+                3 => rng.char_in(0x4e00, 0x9fa6),
+                4 => rng.char_in(0x300, 0x370),
+                _ => rng.char_in(u32::from(b'a'), u32::from(b'z') + 1),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ascii, combining_storm, emoji_zoo, every_scalar_width, rtl_mix, worst_case_for};
+    use unit::Unit;
+    use width::char_width;
+
+    #[test]
+    fn test_deterministic() {
+        assert_eq!(ascii(7, 64), ascii(7, 64));
+        assert_eq!(every_scalar_width(7, 64), every_scalar_width(7, 64));
+        assert_eq!(combining_storm(7, 8, 8), combining_storm(7, 8, 8));
+        assert_eq!(emoji_zoo(7, 64), emoji_zoo(7, 64));
+        assert_eq!(rtl_mix(7, 64), rtl_mix(7, 64));
+        assert_eq!(
+            worst_case_for(Unit::Utf16, 64),
+            worst_case_for(Unit::Utf16, 64)
+        );
+    }
+
+    #[test]
+    fn test_seeds_differ() {
+        assert_ne!(ascii(0, 64), ascii(1, 64));
+        assert_ne!(every_scalar_width(0, 64), every_scalar_width(1, 64));
+        assert_ne!(emoji_zoo(0, 64), emoji_zoo(1, 64));
+    }
+
+    #[test]
+    fn test_lengths() {
+        for len in 0..20 {
+            assert_eq!(ascii(0, len).chars().count(), len);
+            assert_eq!(every_scalar_width(0, len).chars().count(), len);
+            assert_eq!(rtl_mix(0, len).chars().count(), len);
+            for &unit in [
+                Unit::Bytes,
+                Unit::Chars,
+                Unit::Utf16,
+                Unit::Width,
+                Unit::Words,
+            ]
+            .iter()
+            {
+                assert_eq!(worst_case_for(unit, len).chars().count(), len);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_scalar_width() {
+        for (i, c) in every_scalar_width(3, 100).chars().enumerate() {
+            assert_eq!(c.len_utf8(), i % 4 + 1);
+        }
+    }
+
+    #[test]
+    fn test_combining_storm() {
+        let s = combining_storm(0, 4, 3);
+        for (i, c) in s.chars().enumerate() {
+            assert_eq!(c.is_ascii_alphabetic(), i % 4 == 0);
+        }
+    }
+
+    #[test]
+    fn test_worst_case_for_utf16() {
+        for (i, c) in worst_case_for(Unit::Utf16, 100).chars().enumerate() {
+            assert_eq!(c.len_utf16(), i % 2 + 1);
+        }
+    }
+
+    #[test]
+    fn test_worst_case_for_width() {
+        for (i, c) in worst_case_for(Unit::Width, 100).chars().enumerate() {
+            assert_eq!(char_width(c), [2, 0, 1][i % 3]);
+        }
+    }
+
+    #[test]
+    fn test_worst_case_for_words() {
+        use WordSubstring;
+
+        let s = worst_case_for(Unit::Words, 100);
+        assert!(!s.contains(char::is_whitespace));
+        for i in 0..40 {
+            assert_eq!(s.word_substring(i..=i).chars().count(), [3, 2][i % 2]);
+        }
+        assert_eq!(s.word_substring(40..), "");
+    }
+}
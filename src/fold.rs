@@ -0,0 +1,166 @@
+//! Case-insensitive searching by case folding.
+
+use core::char::ToLowercase;
+use core::iter::Peekable;
+use core::ops::Range;
+
+/// The case folding of a single character.
+enum Fold {
+    Lowercase(ToLowercase),
+    Chars(Option<char>, Option<char>),
+}
+
+impl Iterator for Fold {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self {
+            Fold::Lowercase(lowercase) => lowercase.next(),
+            Fold::Chars(first, second) => first.take().or_else(|| second.take()),
+        }
+    }
+}
+
+/// Folds `c` by lowercasing it, except that the sharp s folds to "ss" and the final sigma folds to
+/// the ordinary lowercase sigma.
+fn fold(c: char) -> Fold {
+    match c {
+        'ß' | 'ẞ' => Fold::Chars(Some('s'), Some('s')),
+        'ς' => Fold::Chars(Some('σ'), None),
+        _ => Fold::Lowercase(c.to_lowercase()),
+    }
+}
+
+/// Returns the number of bytes and characters at the start of `haystack` whose folding equals the
+/// folding of `query`, if there are any.
+///
+/// A match must end at the end of a character's folding, so that no character is partially
+/// matched.
+fn match_len(
+    haystack: &str,
+    query: &mut Peekable<impl Iterator<Item = char>>,
+) -> Option<(usize, usize)> {
+    for (count, (index, c)) in haystack.char_indices().enumerate() {
+        for folded in fold(c) {
+            match query.next() {
+                Some(expected) if expected == folded => {}
+                _ => return None,
+            }
+        }
+        if query.peek().is_none() {
+            return Some((index + c.len_utf8(), count + 1));
+        }
+    }
+    None
+}
+
+/// An iterator over the character ranges of case-insensitive matches of a query.
+///
+/// This struct is created by the [`find_fold_char_ranges()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`find_fold_char_ranges()`]: trait.CharSubstring.html#tymethod.find_fold_char_ranges
+#[derive(Clone, Debug)]
+pub struct FoldMatches<'a> {
+    remaining: &'a str,
+    query: &'a str,
+    /// The character index of the start of `remaining`.
+    index: usize,
+}
+
+impl<'a> FoldMatches<'a> {
+    pub(crate) fn new(haystack: &'a str, query: &'a str) -> Self {
+        FoldMatches {
+            remaining: if query.is_empty() { "" } else { haystack },
+            query,
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for FoldMatches<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while let Some(c) = self.remaining.chars().next() {
+            let mut query = self.query.chars().flat_map(fold).peekable();
+            let found = match_len(self.remaining, &mut query);
+            let start = self.index;
+            let (bytes, chars) = found.unwrap_or((c.len_utf8(), 1));
+            // SAFETY: `bytes` lies on a UTF-8 sequence boundary within the bounds of `remaining`.
+            self.remaining = slice_unchecked!(self.remaining, bytes, self.remaining.len());
+            self.index += chars;
+            if found.is_some() {
+                return Some(start..self.index);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FoldMatches;
+    use std::vec::Vec;
+
+    /// Returns the starts and ends of the ranges of matches.
+    fn matches(haystack: &str, query: &str) -> Vec<(usize, usize)> {
+        FoldMatches::new(haystack, query)
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert_eq!(
+            matches("Hello hello HELLO", "hello"),
+            [(0, 5), (6, 11), (12, 17)]
+        );
+    }
+
+    #[test]
+    fn test_sharp_s() {
+        // "ß" is one character of the haystack, but matches two characters of the query.
+        assert_eq!(matches("Straße", "STRASSE"), [(0, 6)]);
+        assert_eq!(matches("STRASSE", "straße"), [(0, 7)]);
+        assert_eq!(matches("Maß und Mass", "mass"), [(0, 3), (8, 12)]);
+    }
+
+    #[test]
+    fn test_sharp_s_not_partially_matched() {
+        assert_eq!(matches("ß", "s"), []);
+        assert_eq!(matches("aßb", "as"), []);
+    }
+
+    #[test]
+    fn test_greek() {
+        assert_eq!(matches("ΟΔΥΣΣΕΥΣ", "οδυσσευς"), [(0, 8)]);
+        assert_eq!(
+            matches("Ὀδυσσεύς and ὈΔΥΣΣΕΎΣ", "ὀδυσσεύς"),
+            [(0, 8), (13, 21)]
+        );
+    }
+
+    #[test]
+    fn test_adjacent_matches() {
+        assert_eq!(matches("aAaA", "aa"), [(0, 2), (2, 4)]);
+        assert_eq!(matches("ßSS", "ss"), [(0, 1), (1, 3)]);
+    }
+
+    #[test]
+    fn test_no_match() {
+        assert_eq!(matches("foobar", "baz"), []);
+        assert_eq!(matches("", "foo"), []);
+        assert_eq!(matches("fo", "foo"), []);
+    }
+
+    #[test]
+    fn test_empty_query() {
+        assert_eq!(matches("foobar", ""), []);
+    }
+
+    #[test]
+    fn test_char_indices_across_multibyte() {
+        assert_eq!(matches("fõøBα®bα", "bα"), [(3, 5), (6, 8)]);
+    }
+}
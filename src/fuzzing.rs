@@ -0,0 +1,173 @@
+//! Helpers for fuzzing the slicing methods of this crate.
+//!
+//! A fuzz target exercising every slicing method can be written in one line:
+//!
+//! ```ignore
+//! fuzz_target!(|data: &[u8]| substring::fuzzing::fuzz_substring(data));
+//! ```
+
+use core::ops::Bound;
+use core::str;
+use unit::Unit;
+use CharSubstring;
+use Substring;
+
+/// The largest number of characters requested from methods that allocate.
+#[cfg(feature = "alloc")]
+const MAX_ALLOCATED_CHARS: usize = 1024;
+
+/// Decodes an index from a byte, weighting in extreme values.
+///
+/// Most byte values decode to small indices, while values of `0xf0` and up decode to values near
+/// `usize::MAX`.
+fn decode_index(byte: u8) -> usize {
+    if byte >= 0xf0 {
+        ::core::usize::MAX - usize::from(byte - 0xf0)
+    } else {
+        usize::from(byte)
+    }
+}
+
+/// Decodes a bound from a pair of bytes.
+fn decode_bound(kind: u8, value: u8) -> Bound<usize> {
+    match kind % 3 {
+        0 => Bound::Included(decode_index(value)),
+        1 => Bound::Excluded(decode_index(value)),
+        _ => Bound::Unbounded,
+    }
+}
+
+/// Decodes a range and a string from raw bytes.
+///
+/// The first four bytes decode to the range's bounds, and the longest valid UTF-8 prefix of the
+/// remaining bytes is used as the string. Missing bytes are treated as zero.
+pub fn decode(data: &[u8]) -> ((Bound<usize>, Bound<usize>), &str) {
+    let byte = |index: usize| data.get(index).cloned().unwrap_or(0);
+    let range = (
+        decode_bound(byte(0), byte(1)),
+        decode_bound(byte(2), byte(3)),
+    );
+    let rest = if data.len() > 4 { &data[4..] } else { &[] };
+    let s = match str::from_utf8(rest) {
+        Ok(s) => s,
+        Err(error) => {
+            // SAFETY: `valid_up_to()` is the length of the longest valid UTF-8 prefix.
+            from_utf8_unchecked!(&rest[..error.valid_up_to()])
+        }
+    };
+    (range, s)
+}
+
+/// Asserts that `slice` is a subslice of `s`.
+fn assert_within(s: &str, slice: &str) {
+    let start = s.as_ptr() as usize;
+    let slice_start = slice.as_ptr() as usize;
+    assert!(
+        slice.is_empty() || (slice_start >= start && slice_start + slice.len() <= start + s.len())
+    );
+}
+
+/// Exercises every slicing method of this crate on a string and a range decoded from `data`.
+///
+/// The input is decoded as described by [`decode()`]. This panics if any method panics or if the
+/// results of the methods are inconsistent with each other.
+///
+/// [`decode()`]: fn.decode.html
+pub fn fuzz_substring(data: &[u8]) {
+    let (range, s) = decode(data);
+
+    let char_bytes = s.to_byte_range(Unit::Chars, range);
+    assert!(char_bytes.start <= char_bytes.end);
+    assert!(s.is_char_boundary(char_bytes.start) && s.is_char_boundary(char_bytes.end));
+    let chars = s.from_byte_range(Unit::Chars, char_bytes.clone());
+    assert_eq!(s.to_byte_range(Unit::Chars, chars.clone()), char_bytes);
+    let substring = s.substring(chars.start, chars.end);
+    assert_within(s, substring);
+    assert_eq!(substring, &s[char_bytes.clone()]);
+    assert_eq!(s.char_count_in(char_bytes.clone()), chars.end - chars.start);
+    assert_eq!(
+        s.try_char_count_in(char_bytes.clone()),
+        Ok(chars.end - chars.start)
+    );
+
+    for &unit in [Unit::Bytes, Unit::Utf16, Unit::Width, Unit::Words].iter() {
+        let bytes = s.to_byte_range(unit, range);
+        assert!(bytes.start <= bytes.end);
+        assert!(s.is_char_boundary(bytes.start) && s.is_char_boundary(bytes.end));
+        let units = s.from_byte_range(unit, bytes.clone());
+        assert_eq!(s.to_byte_range(unit, units), bytes);
+    }
+
+    if let Some(last) = s.last_match_char_range(substring) {
+        assert!(last.start >= chars.start || substring.is_empty());
+        assert_eq!(s.substring(last.start, last.end), substring);
+    }
+
+    #[cfg(feature = "alloc")]
+    {
+        if let (Bound::Included(start), Bound::Excluded(end)) = range {
+            if end.saturating_sub(start) <= MAX_ALLOCATED_CHARS {
+                let padded = s.substring_padded(start..end, '\u{fffd}');
+                assert_eq!(padded.chars().count(), end.saturating_sub(start));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, fuzz_substring};
+    use core::ops::Bound;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode(b"\x00\x02\x01\x05foobar"),
+            ((Bound::Included(2), Bound::Excluded(5)), "foobar")
+        );
+        assert_eq!(
+            decode(b"\x02\x00\x00\xff"),
+            (
+                (Bound::Unbounded, Bound::Included(::core::usize::MAX - 15)),
+                ""
+            )
+        );
+        assert_eq!(decode(b""), ((Bound::Included(0), Bound::Included(0)), ""));
+    }
+
+    #[test]
+    fn test_decode_invalid_utf8() {
+        assert_eq!(decode(b"\x00\x00\x02\x00f\xc3\xb5\xc3").1, "fõ");
+    }
+
+    #[test]
+    fn test_fuzz_substring_smoke() {
+        let inputs: [&[u8]; 8] = [
+            b"",
+            b"\x00",
+            b"\x00\x02\x01\x05f\xc3\xb5\xc3\xb8b\xce\xb1\xc2\xae",
+            b"\x01\xff\x00\xff\xf0\x9f\x98\x80a",
+            b"\x02\x00\x02\x00foobar",
+            b"\x00\x05\x01\x02foobar",
+            b"\x00\x03\x01\x10fo\xc3",
+            b"\x01\xf0\x01\xf1\xe6\x97\xa5\xe6\x9c\xac",
+        ];
+        for input in inputs.iter() {
+            fuzz_substring(input);
+        }
+    }
+
+    #[test]
+    fn test_fuzz_substring_all_short_inputs() {
+        let s = "fõ😀";
+        for kinds in 0..9u8 {
+            for start in 0..8u8 {
+                for end in 0..8u8 {
+                    let mut data = [kinds % 3, start, kinds / 3, end, 0, 0, 0, 0, 0, 0, 0];
+                    data[4..].copy_from_slice(s.as_bytes());
+                    fuzz_substring(&data);
+                }
+            }
+        }
+    }
+}
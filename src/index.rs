@@ -0,0 +1,209 @@
+//! Conversion of integer indices of any type into `usize` indices.
+
+use core::ops::{Bound, RangeBounds};
+use error::IndexConversionError;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An integer type which may be used as a character index.
+///
+/// This trait is implemented for all of the primitive integer types up to 64 bits wide, along
+/// with `usize` and `isize`. It is sealed and cannot be implemented outside of this crate.
+pub trait CharIndex: Copy + private::Sealed {
+    /// Converts the index into a `usize`, or returns an error if it is negative or too large.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::CharIndex;
+    ///
+    /// assert_eq!(5u32.to_usize(), Ok(5));
+    /// assert!((-1i64).to_usize().is_err());
+    /// ```
+    fn to_usize(self) -> Result<usize, IndexConversionError>;
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl private::Sealed for $t {}
+        impl CharIndex for $t {
+            fn to_usize(self) -> Result<usize, IndexConversionError> {
+                if self as u128 <= usize::max_value() as u128 {
+                    Ok(self as usize)
+                } else {
+                    Err(IndexConversionError::new(self as i128))
+                }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl private::Sealed for $t {}
+        impl CharIndex for $t {
+            fn to_usize(self) -> Result<usize, IndexConversionError> {
+                if self >= 0 && self as i128 <= usize::max_value() as i128 {
+                    Ok(self as usize)
+                } else {
+                    Err(IndexConversionError::new(self as i128))
+                }
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64);
+impl_signed!(i8, i16, i32, i64, isize);
+
+impl private::Sealed for usize {}
+impl CharIndex for usize {
+    fn to_usize(self) -> Result<usize, IndexConversionError> {
+        Ok(self)
+    }
+}
+
+/// Converts the bounds of `range`, saturating indices which cannot be represented as a `usize` as
+/// [`saturating_start()`] and [`saturating_end()`] do.
+///
+/// [`saturating_start()`]: fn.saturating_start.html
+/// [`saturating_end()`]: fn.saturating_end.html
+pub(crate) fn saturating_range<T: CharIndex, R: RangeBounds<T>>(
+    range: &R,
+) -> (Bound<usize>, Bound<usize>) {
+    (
+        saturating_start(range.start_bound()),
+        saturating_end(range.end_bound()),
+    )
+}
+
+/// Converts the bounds of `range`, returning an error if either index cannot be represented as a
+/// `usize`.
+pub(crate) fn try_range<T: CharIndex, R: RangeBounds<T>>(
+    range: &R,
+) -> Result<(Bound<usize>, Bound<usize>), IndexConversionError> {
+    Ok((
+        try_bound(range.start_bound())?,
+        try_bound(range.end_bound())?,
+    ))
+}
+
+/// Converts a start bound, replacing a negative index with `0` and a too large index with
+/// `usize::MAX`.
+fn saturating_start<T: CharIndex>(bound: Bound<&T>) -> Bound<usize> {
+    match try_bound(bound) {
+        Ok(bound) => bound,
+        Err(error) if error.is_negative() => Bound::Included(0),
+        Err(_) => Bound::Included(usize::max_value()),
+    }
+}
+
+/// Converts an end bound, replacing a negative index with `0` and a too large index with an
+/// unbounded end.
+fn saturating_end<T: CharIndex>(bound: Bound<&T>) -> Bound<usize> {
+    match try_bound(bound) {
+        Ok(bound) => bound,
+        Err(error) if error.is_negative() => Bound::Excluded(0),
+        Err(_) => Bound::Unbounded,
+    }
+}
+
+/// Converts the index of a bound, returning an error if it cannot be represented as a `usize`.
+fn try_bound<T: CharIndex>(bound: Bound<&T>) -> Result<Bound<usize>, IndexConversionError> {
+    match bound {
+        Bound::Included(index) => index.to_usize().map(Bound::Included),
+        Bound::Excluded(index) => index.to_usize().map(Bound::Excluded),
+        Bound::Unbounded => Ok(Bound::Unbounded),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        saturating_end, saturating_range, saturating_start, try_bound, try_range, CharIndex,
+    };
+    use core::ops::Bound;
+    use error::IndexConversionError;
+
+    #[test]
+    fn test_to_usize() {
+        assert_eq!(7u8.to_usize(), Ok(7));
+        assert_eq!(7u32.to_usize(), Ok(7));
+        assert_eq!(7i64.to_usize(), Ok(7));
+        assert_eq!(0isize.to_usize(), Ok(0));
+        assert_eq!(usize::max_value().to_usize(), Ok(usize::max_value()));
+    }
+
+    #[test]
+    fn test_to_usize_negative() {
+        assert_eq!((-1i8).to_usize(), Err(IndexConversionError::new(-1)));
+        assert_eq!(
+            i64::min_value().to_usize(),
+            Err(IndexConversionError::new(i128::from(i64::min_value())))
+        );
+    }
+
+    #[cfg(target_pointer_width = "32")]
+    #[test]
+    fn test_to_usize_too_large() {
+        assert_eq!(
+            u64::max_value().to_usize(),
+            Err(IndexConversionError::new(i128::from(u64::max_value())))
+        );
+    }
+
+    #[test]
+    fn test_saturating_start() {
+        assert_eq!(saturating_start(Bound::Included(&3i32)), Bound::Included(3));
+        assert_eq!(saturating_start(Bound::Excluded(&3u16)), Bound::Excluded(3));
+        assert_eq!(
+            saturating_start(Bound::Included(&-3i32)),
+            Bound::Included(0)
+        );
+        assert_eq!(
+            saturating_start(Bound::Excluded(&-1i32)),
+            Bound::Included(0)
+        );
+        assert_eq!(saturating_start::<i32>(Bound::Unbounded), Bound::Unbounded);
+    }
+
+    #[test]
+    fn test_saturating_end() {
+        assert_eq!(saturating_end(Bound::Excluded(&3i32)), Bound::Excluded(3));
+        assert_eq!(saturating_end(Bound::Included(&-3i32)), Bound::Excluded(0));
+        assert_eq!(saturating_end(Bound::Excluded(&-1i64)), Bound::Excluded(0));
+        assert_eq!(saturating_end::<i32>(Bound::Unbounded), Bound::Unbounded);
+    }
+
+    #[test]
+    fn test_try_bound() {
+        assert_eq!(try_bound(Bound::Included(&2u64)), Ok(Bound::Included(2)));
+        assert_eq!(
+            try_bound(Bound::Excluded(&-2i16)),
+            Err(IndexConversionError::new(-2))
+        );
+    }
+
+    #[test]
+    fn test_saturating_range() {
+        assert_eq!(
+            saturating_range(&(-2i32..=7)),
+            (Bound::Included(0), Bound::Included(7))
+        );
+        assert_eq!(
+            saturating_range(&(3u8..)),
+            (Bound::Included(3), Bound::Unbounded)
+        );
+    }
+
+    #[test]
+    fn test_try_range() {
+        assert_eq!(
+            try_range(&(2u64..5)),
+            Ok((Bound::Included(2), Bound::Excluded(5)))
+        );
+        let (start, end) = (2i64, -5i64);
+        assert_eq!(try_range(&(start..end)), Err(IndexConversionError::new(-5)));
+    }
+}
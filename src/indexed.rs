@@ -0,0 +1,200 @@
+//! Precomputed character indices for repeated slicing.
+
+use alloc::vec::Vec;
+use core::fmt;
+use debug::TruncatedText;
+use nth_char_boundary;
+use Substring;
+
+/// A string slice with a table of the byte offsets of its characters, making slicing by character
+/// index independent of the length of the string.
+///
+/// The table is built in a single pass on construction. By default, the byte offset of every
+/// character is recorded, and finding a character index takes constant time. To bound memory use
+/// on large strings, [`with_stride()`] records only every `stride`th offset, and finding a
+/// character index then counts at most `stride` characters from the nearest recorded offset.
+///
+/// Example:
+/// ```
+/// use substring::{CharIndexedStr, Substring};
+///
+/// let indexed = CharIndexedStr::new("fõøbα®");
+/// assert_eq!(indexed.char_len(), 6);
+/// assert_eq!(indexed.char_to_byte(3), 5);
+/// assert_eq!(indexed.substring(2, 5), "øbα");
+/// ```
+///
+/// [`with_stride()`]: #method.with_stride
+#[derive(Clone, Eq, Hash, PartialEq)]
+pub struct CharIndexedStr<'a> {
+    s: &'a str,
+    char_len: usize,
+    /// The number of characters between recorded offsets.
+    stride: usize,
+    /// The byte offset of every `stride`th character.
+    offsets: Vec<usize>,
+}
+
+impl<'a> CharIndexedStr<'a> {
+    /// Indexes every character of `s` in a single pass.
+    pub fn new(s: &'a str) -> Self {
+        Self::with_stride(s, 1)
+    }
+
+    /// Indexes every `stride`th character of `s` in a single pass.
+    ///
+    /// A `stride` of `0` is treated as `1`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{CharIndexedStr, Substring};
+    ///
+    /// let indexed = CharIndexedStr::with_stride("fõøbα®", 4);
+    /// assert_eq!(indexed.substring(2, 5), "øbα");
+    /// ```
+    pub fn with_stride(s: &'a str, stride: usize) -> Self {
+        let stride = if stride == 0 { 1 } else { stride };
+        let mut char_len = 0;
+        let mut offsets = Vec::new();
+        for (char_index, (byte_index, _char)) in s.char_indices().enumerate() {
+            if char_index % stride == 0 {
+                offsets.push(byte_index);
+            }
+            char_len += 1;
+        }
+        CharIndexedStr {
+            s,
+            char_len,
+            stride,
+            offsets,
+        }
+    }
+
+    /// Returns the underlying string slice.
+    pub fn as_str(&self) -> &'a str {
+        self.s
+    }
+
+    /// Returns the number of characters in the string.
+    pub fn char_len(&self) -> usize {
+        self.char_len
+    }
+
+    /// Returns the byte index of the character at `char_index`, or the length of the string if
+    /// `char_index` is past its end.
+    pub fn char_to_byte(&self, char_index: usize) -> usize {
+        if char_index >= self.char_len {
+            return self.s.len();
+        }
+        let offset = self.offsets[char_index / self.stride];
+        let remainder = char_index % self.stride;
+        if remainder == 0 {
+            return offset;
+        }
+        // SAFETY: Each offset was obtained from the `CharIndices` of the string.
+        offset + nth_char_boundary(slice_unchecked!(self.s, offset, self.s.len()), remainder)
+    }
+}
+
+/// Formats the number of characters along with the text, as in
+/// `CharIndexedStr { char_len: 6, text: "fõøbα®" }`.
+///
+/// Text longer than 32 characters is truncated and followed by an ellipsis.
+impl<'a> fmt::Debug for CharIndexedStr<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CharIndexedStr")
+            .field("char_len", &self.char_len)
+            .field("text", &TruncatedText(self.s))
+            .finish()
+    }
+}
+
+impl<'a> Substring for CharIndexedStr<'a> {
+    fn substring(&self, start_index: usize, end_index: usize) -> &str {
+        let start_byte = self.char_to_byte(start_index);
+        let end_byte = if end_index <= start_index {
+            start_byte
+        } else {
+            self.char_to_byte(end_index)
+        };
+        // SAFETY: Both byte indices are character boundaries of the string, or are its length.
+        slice_unchecked!(self.s, start_byte, end_byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharIndexedStr;
+    use Substring;
+
+    const CORPUS: [&str; 6] = [
+        "",
+        "a",
+        "foobar",
+        "fõøbα®",
+        "😀a𝔘b",
+        "日本語😀 fõøbα® foobar 😀a𝔘b",
+    ];
+
+    fn assert_equivalent(s: &str, stride: usize) {
+        let indexed = CharIndexedStr::with_stride(s, stride);
+        let char_len = s.chars().count();
+        assert_eq!(indexed.char_len(), char_len);
+        for start in 0..(char_len + 2) {
+            for end in 0..(char_len + 2) {
+                assert_eq!(indexed.substring(start, end), s.substring(start, end));
+                assert_eq!(
+                    indexed.substring(start, end).as_ptr(),
+                    s.substring(start, end).as_ptr()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_equivalent_to_str() {
+        for s in CORPUS.iter() {
+            for &stride in [0, 1, 2, 3, 16, 100].iter() {
+                assert_equivalent(s, stride);
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_to_byte() {
+        for s in CORPUS.iter() {
+            for &stride in [1, 3].iter() {
+                let indexed = CharIndexedStr::with_stride(s, stride);
+                for (char_index, (byte_index, _char)) in s.char_indices().enumerate() {
+                    assert_eq!(indexed.char_to_byte(char_index), byte_index);
+                }
+                assert_eq!(indexed.char_to_byte(indexed.char_len()), s.len());
+                assert_eq!(indexed.char_to_byte(usize::max_value()), s.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_usable_as_substring() {
+        fn first_three<S: Substring + ?Sized>(s: &S) -> &str {
+            s.substring(0, 3)
+        }
+
+        let indexed = CharIndexedStr::new("fõøbα®");
+        assert_eq!(first_three(&indexed), first_three("fõøbα®"));
+    }
+
+    #[test]
+    fn test_as_str() {
+        let s = "fõøbα®";
+        assert_eq!(CharIndexedStr::new(s).as_str(), s);
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(
+            format!("{:?}", CharIndexedStr::new("fõøbα®")),
+            "CharIndexedStr { char_len: 6, text: \"fõøbα®\" }"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! Deduplicating storage of substrings.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use bounds;
+use core::ops::{Range, RangeBounds};
+use unit::char_range_to_byte_range;
+
+/// A handle to a substring stored in a [`SubstringInterner`].
+///
+/// Handles are cheap to copy, and equal handles from the same interner always resolve to equal
+/// strings.
+///
+/// [`SubstringInterner`]: struct.SubstringInterner.html
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Interned(usize);
+
+/// Storage for substrings which deduplicates equal content.
+///
+/// Each distinct substring is stored once, in a single buffer shared by all substrings. Looking up
+/// a substring does not allocate; only the first insertion of a substring copies it into the
+/// buffer.
+///
+/// Example:
+/// ```
+/// use substring::SubstringInterner;
+///
+/// let mut interner = SubstringInterner::new();
+/// let foo = interner.intern_range("foobar", 0..3);
+/// let bar = interner.intern_range("barfoo", 3..);
+///
+/// assert_eq!(foo, bar);
+/// assert_eq!(interner.resolve(foo), "foo");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SubstringInterner {
+    buffer: String,
+    spans: Vec<Range<usize>>,
+    /// Handles ordered by the content they resolve to.
+    sorted: Vec<Interned>,
+}
+
+impl SubstringInterner {
+    /// Creates a new, empty interner.
+    pub fn new() -> Self {
+        SubstringInterner::default()
+    }
+
+    /// Interns the characters of `s` within `range`, returning a handle to them.
+    ///
+    /// The range is clamped to the bounds of `s` in the same way as [`substring()`]. If equal
+    /// content has been interned before, the existing handle is returned.
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    pub fn intern_range<R: RangeBounds<usize>>(&mut self, s: &str, range: R) -> Interned {
+        let resolved = bounds::resolve(&range);
+        let bytes = char_range_to_byte_range(s, resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `s`.
+        self.intern(slice_unchecked!(s, bytes.start, bytes.end))
+    }
+
+    /// Interns `s`, returning a handle to it.
+    ///
+    /// If equal content has been interned before, the existing handle is returned.
+    pub fn intern(&mut self, s: &str) -> Interned {
+        match self.search(s) {
+            Ok(position) => self.sorted[position],
+            Err(position) => {
+                let handle = Interned(self.spans.len());
+                let start = self.buffer.len();
+                self.buffer.push_str(s);
+                self.spans.push(start..self.buffer.len());
+                self.sorted.insert(position, handle);
+                handle
+            }
+        }
+    }
+
+    /// Returns the handle to `s`, if it has been interned.
+    pub fn get(&self, s: &str) -> Option<Interned> {
+        self.search(s).ok().map(|position| self.sorted[position])
+    }
+
+    /// Returns the string a handle refers to.
+    ///
+    /// # Panics
+    /// Panics if `handle` was not returned by this interner.
+    pub fn resolve(&self, handle: Interned) -> &str {
+        let span = &self.spans[handle.0];
+        &self.buffer[span.start..span.end]
+    }
+
+    /// Returns the number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns whether no strings have been interned.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    fn search(&self, s: &str) -> Result<usize, usize> {
+        self.sorted
+            .binary_search_by(|&handle| self.resolve(handle).cmp(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubstringInterner;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_intern_range() {
+        let mut interner = SubstringInterner::new();
+        let handle = interner.intern_range("fõøbα®", 1..4);
+        assert_eq!(interner.resolve(handle), "õøb");
+    }
+
+    #[test]
+    fn test_equal_content_deduplicates() {
+        let mut interner = SubstringInterner::new();
+        let first = interner.intern_range("fõøbα®", 1..3);
+        let second = interner.intern_range("bαõø", 2..);
+        assert_eq!(first, second);
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn test_different_content() {
+        let mut interner = SubstringInterner::new();
+        let first = interner.intern_range("foobar", 0..3);
+        let second = interner.intern_range("foobar", 3..6);
+        assert_ne!(first, second);
+        assert_eq!(interner.resolve(first), "foo");
+        assert_eq!(interner.resolve(second), "bar");
+    }
+
+    #[test]
+    fn test_empty_range() {
+        let mut interner = SubstringInterner::new();
+        let (start, end) = (4, 2);
+        let empty = interner.intern_range("foobar", start..end);
+        assert_eq!(interner.resolve(empty), "");
+        assert_eq!(interner.intern_range("fõø", 10..), empty);
+    }
+
+    #[test]
+    fn test_many_insertions() {
+        let mut interner = SubstringInterner::new();
+        let strings = (0..500)
+            .map(|i| format!("{}õ{}", i % 250, i))
+            .collect::<Vec<_>>();
+        let handles = strings
+            .iter()
+            .map(|s| interner.intern_range(s, ..))
+            .collect::<Vec<_>>();
+        for (s, &handle) in strings.iter().zip(handles.iter()) {
+            assert_eq!(interner.resolve(handle), s);
+        }
+        // Re-interning a prefix of each string only stores each distinct prefix once.
+        for s in strings.iter() {
+            interner.intern_range(s, ..1);
+        }
+        assert_eq!(interner.len(), 510);
+    }
+
+    #[test]
+    fn test_get() {
+        let mut interner = SubstringInterner::new();
+        let handle = interner.intern_range("foobar", 3..);
+        assert_eq!(interner.get("bar"), Some(handle));
+        let owned = "bar".to_string();
+        assert_eq!(interner.get(&owned), Some(handle));
+        assert_eq!(interner.get("foo"), None);
+    }
+}
@@ -0,0 +1,395 @@
+//! Iterators over the characters of substrings.
+
+use core::ops::Range;
+use core::slice;
+use core::str::Chars;
+use nth_char_boundary;
+
+/// An iterator over the characters of a substring, from last to first.
+///
+/// This struct is created by the [`chars_in_rev()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`chars_in_rev()`]: trait.CharSubstring.html#tymethod.chars_in_rev
+#[derive(Clone, Debug)]
+pub struct CharsInRev<'a> {
+    chars: Chars<'a>,
+}
+
+impl<'a> CharsInRev<'a> {
+    pub(crate) fn new(s: &'a str) -> Self {
+        CharsInRev { chars: s.chars() }
+    }
+}
+
+impl<'a> Iterator for CharsInRev<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next_back()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for CharsInRev<'a> {
+    fn next_back(&mut self) -> Option<char> {
+        self.chars.next()
+    }
+}
+
+/// An iterator over the characters of a substring and their character indices within the
+/// original string, from last to first.
+///
+/// This struct is created by the [`char_indices_in_rev()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`char_indices_in_rev()`]: trait.CharSubstring.html#tymethod.char_indices_in_rev
+#[derive(Clone, Debug)]
+pub struct CharIndicesInRev<'a> {
+    chars: Chars<'a>,
+    /// The character index following the next character to be yielded.
+    end_index: usize,
+}
+
+impl<'a> CharIndicesInRev<'a> {
+    pub(crate) fn new(s: &'a str, end_index: usize) -> Self {
+        CharIndicesInRev {
+            chars: s.chars(),
+            end_index,
+        }
+    }
+}
+
+impl<'a> Iterator for CharIndicesInRev<'a> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.chars.next_back().map(|c| {
+            self.end_index -= 1;
+            (self.end_index, c)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+/// An iterator over the substrings of a string containing a fixed number of characters.
+///
+/// This struct is created by the [`char_chunks()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`char_chunks()`]: trait.CharSubstring.html#tymethod.char_chunks
+#[derive(Clone, Debug)]
+pub struct CharChunks<'a> {
+    remaining: &'a str,
+    chunk_size: usize,
+}
+
+impl<'a> CharChunks<'a> {
+    pub(crate) fn new(s: &'a str, chunk_size: usize) -> Self {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        CharChunks {
+            remaining: s,
+            chunk_size,
+        }
+    }
+}
+
+impl<'a> Iterator for CharChunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let end = nth_char_boundary(self.remaining, self.chunk_size);
+        // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `remaining`.
+        let (chunk, rest) = (
+            slice_unchecked!(self.remaining, 0, end),
+            slice_unchecked!(self.remaining, end, self.remaining.len()),
+        );
+        self.remaining = rest;
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.len();
+        // Each character occupies between one and four bytes.
+        let min_chars = (len + 3) / 4;
+        let lower = (min_chars + self.chunk_size - 1) / self.chunk_size;
+        let upper = len / self.chunk_size + if len % self.chunk_size == 0 { 0 } else { 1 };
+        (lower, Some(upper))
+    }
+}
+
+/// An iterator over the overlapping substrings of a string containing a fixed number of
+/// characters.
+///
+/// This struct is created by the [`char_windows()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`char_windows()`]: trait.CharSubstring.html#tymethod.char_windows
+#[derive(Clone, Debug)]
+pub struct CharWindows<'a> {
+    /// The string from the start of the next window.
+    remaining: &'a str,
+    /// The byte index within `remaining` of the end of the next window, if there is one.
+    end: Option<usize>,
+}
+
+impl<'a> CharWindows<'a> {
+    pub(crate) fn new(s: &'a str, size: usize) -> Self {
+        assert!(size != 0, "window size must be non-zero");
+        let end = s
+            .char_indices()
+            .map(|(index, c)| index + c.len_utf8())
+            .nth(size - 1);
+        CharWindows { remaining: s, end }
+    }
+}
+
+impl<'a> Iterator for CharWindows<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let end = self.end?;
+        // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `remaining`.
+        let (window, after) = (
+            slice_unchecked!(self.remaining, 0, end),
+            slice_unchecked!(self.remaining, end, self.remaining.len()),
+        );
+        match (window.chars().next(), after.chars().next()) {
+            (Some(first), Some(next)) => {
+                let first_len = first.len_utf8();
+                // SAFETY: `first_len` is the length of the first character of `remaining`.
+                self.remaining = slice_unchecked!(self.remaining, first_len, self.remaining.len());
+                self.end = Some(end - first_len + next.len_utf8());
+            }
+            _ => self.end = None,
+        }
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.end {
+            // Each following window extends one character, of one to four bytes, further.
+            Some(end) => {
+                let after = self.remaining.len() - end;
+                ((after + 3) / 4 + 1, Some(after + 1))
+            }
+            None => (0, Some(0)),
+        }
+    }
+}
+
+/// A character index and the byte index at which that character begins.
+#[derive(Clone, Copy, Debug)]
+struct Position {
+    char_idx: usize,
+    byte_idx: usize,
+}
+
+/// An iterator over the substrings of a string within each of a sequence of character ranges.
+///
+/// This struct is created by the [`substrings()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`substrings()`]: trait.CharSubstring.html#tymethod.substrings
+#[derive(Clone, Debug)]
+pub struct Substrings<'a, 'r> {
+    s: &'a str,
+    ranges: slice::Iter<'r, Range<usize>>,
+    /// The start of the previous substring.
+    start: Position,
+    /// The end of the previous substring.
+    end: Position,
+}
+
+impl<'a, 'r> Substrings<'a, 'r> {
+    pub(crate) fn new(s: &'a str, ranges: &'r [Range<usize>]) -> Self {
+        let origin = Position {
+            char_idx: 0,
+            byte_idx: 0,
+        };
+        Substrings {
+            s,
+            ranges: ranges.iter(),
+            start: origin,
+            end: origin,
+        }
+    }
+
+    /// Returns the position of the character index `char_idx`, walking from the furthest known
+    /// position before it.
+    ///
+    /// Indices past the end of the string are positioned at its end.
+    fn seek(&self, from: Position, char_idx: usize) -> Position {
+        let from = if self.end.char_idx <= char_idx && self.end.char_idx >= from.char_idx {
+            self.end
+        } else if self.start.char_idx <= char_idx && self.start.char_idx >= from.char_idx {
+            self.start
+        } else {
+            from
+        };
+        // SAFETY: `from.byte_idx` lies on a UTF-8 sequence boundary within the bounds of `s`.
+        let rest = slice_unchecked!(self.s, from.byte_idx, self.s.len());
+        Position {
+            char_idx,
+            byte_idx: from.byte_idx + nth_char_boundary(rest, char_idx - from.char_idx),
+        }
+    }
+}
+
+impl<'a, 'r> Iterator for Substrings<'a, 'r> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let range = self.ranges.next()?;
+        let origin = Position {
+            char_idx: 0,
+            byte_idx: 0,
+        };
+        let start = self.seek(origin, range.start);
+        let end = if range.end <= range.start {
+            start
+        } else {
+            self.seek(start, range.end)
+        };
+        self.start = start;
+        self.end = end;
+        // SAFETY: Both byte indices lie on UTF-8 sequence boundaries within the bounds of `s`.
+        Some(slice_unchecked!(self.s, start.byte_idx, end.byte_idx))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ranges.size_hint()
+    }
+}
+
+impl<'a, 'r> ExactSizeIterator for Substrings<'a, 'r> {}
+
+/// A lazy iterator over the characters of a substring.
+///
+/// This struct is created by the [`substring_chars()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`substring_chars()`]: trait.CharSubstring.html#tymethod.substring_chars
+#[derive(Clone, Debug)]
+pub struct SubstringChars<'a> {
+    chars: Chars<'a>,
+    /// The number of characters left to yield from the front, or `None` if `chars` ends where
+    /// the substring does.
+    remaining: Option<usize>,
+}
+
+impl<'a> SubstringChars<'a> {
+    pub(crate) fn new(s: &'a str, start: usize, end: Option<usize>) -> Self {
+        let start_byte = nth_char_boundary(s, start);
+        // SAFETY: `start_byte` lies on a UTF-8 sequence boundary within the bounds of `s`.
+        let rest = slice_unchecked!(s, start_byte, s.len());
+        SubstringChars {
+            chars: rest.chars(),
+            remaining: end.map(|end| end.saturating_sub(start)),
+        }
+    }
+
+    /// Returns the characters not yet yielded, finding the end of the substring if it is not yet
+    /// known.
+    fn as_str(&mut self) -> &'a str {
+        if let Some(remaining) = self.remaining.take() {
+            let rest = self.chars.as_str();
+            let end = nth_char_boundary(rest, remaining);
+            // SAFETY: `end` lies on a UTF-8 sequence boundary within the bounds of `rest`.
+            self.chars = slice_unchecked!(rest, 0, end).chars();
+        }
+        self.chars.as_str()
+    }
+}
+
+impl<'a> Iterator for SubstringChars<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self.remaining {
+            Some(0) => None,
+            Some(ref mut remaining) => {
+                let c = self.chars.next()?;
+                *remaining -= 1;
+                Some(c)
+            }
+            None => self.chars.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.chars.size_hint();
+        match self.remaining {
+            Some(remaining) => (
+                lower.min(remaining),
+                Some(upper.map_or(remaining, |upper| upper.min(remaining))),
+            ),
+            None => (lower, upper),
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for SubstringChars<'a> {
+    /// Yields the last character not yet yielded.
+    ///
+    /// The first call walks to the end of the substring, if the substring has an end bound.
+    fn next_back(&mut self) -> Option<char> {
+        self.as_str();
+        self.chars.next_back()
+    }
+}
+
+/// A lazy iterator over the characters of a substring and their byte indices.
+///
+/// Byte indices are relative to the start of the string, not of the substring. This struct is
+/// created by the [`substring_char_indices()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`substring_char_indices()`]: trait.CharSubstring.html#tymethod.substring_char_indices
+#[derive(Clone, Debug)]
+pub struct SubstringCharIndices<'a> {
+    chars: SubstringChars<'a>,
+    /// The byte index of the next character yielded from the front.
+    front: usize,
+}
+
+impl<'a> SubstringCharIndices<'a> {
+    pub(crate) fn new(s: &'a str, start: usize, end: Option<usize>) -> Self {
+        SubstringCharIndices {
+            chars: SubstringChars::new(s, start, end),
+            front: nth_char_boundary(s, start),
+        }
+    }
+}
+
+impl<'a> Iterator for SubstringCharIndices<'a> {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        let c = self.chars.next()?;
+        let index = self.front;
+        self.front += c.len_utf8();
+        Some((index, c))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for SubstringCharIndices<'a> {
+    fn next_back(&mut self) -> Option<(usize, char)> {
+        let c = self.chars.next_back()?;
+        // The characters not yet yielded lie between `front` and `c`.
+        Some((self.front + self.chars.as_str().len(), c))
+    }
+}
@@ -34,6 +34,36 @@
 //!
 //! The above example occurs because "ã" is technically made up of two UTF-8 scalar values.
 //!
+//! # Additional Methods
+//! On `rustc 1.28.0` and up, the [`CharSubstring`] trait provides further character-indexed
+//! methods, many of which accept any range type. On `rustc 1.59.0` and up, [`MicroIndexed`] allows
+//! short strings to be sliced repeatedly without walking them from the start. With the `alloc`
+//! feature, [`CharIndexedStr`] does the same for strings of any length, using a heap-allocated
+//! table.
+//!
+//! # Features
+//! - `alloc`: Enables methods returning owned strings. Requires `rustc 1.36.0` or later.
+//! - `std`: Enables implementations of `std` traits, and [`OsStrSubstring`]. Implies `alloc`.
+//! - `const_substring`: Enables [`const_substring()`], for slicing strings in constant expressions.
+//!   Requires `rustc 1.64.0` or later.
+//! - `forbid_unsafe`: Compiles the crate with `#![forbid(unsafe_code)]`. Slices are bounds-checked
+//!   rather than taken unchecked, which behaves identically. Combined with `stats`, requires
+//!   `rustc 1.70.0` or later.
+//! - `fuzzing`: Enables the [`fuzzing`] module, containing helpers for fuzz targets.
+//! - `stats`: Enables [`set_observer()`], for observing ranges clamped to the bounds of strings.
+//!   Implies `std`.
+//! - `testing`: Enables the [`fixtures`] module, containing generators of pathological strings for
+//!   tests and benchmarks. Implies `alloc`.
+//!
+//! [`CharIndexedStr`]: struct.CharIndexedStr.html
+//! [`CharSubstring`]: trait.CharSubstring.html
+//! [`const_substring()`]: fn.const_substring.html
+//! [`fixtures`]: fixtures/index.html
+//! [`MicroIndexed`]: struct.MicroIndexed.html
+//! [`OsStrSubstring`]: trait.OsStrSubstring.html
+//! [`range_in()`]: fn.range_in.html
+//! [`set_observer()`]: fn.set_observer.html
+//! [`fuzzing`]: fuzzing/index.html
 //! [`str`]: https://doc.rust-lang.org/std/primitive.str.html
 //! [`Substring`]: trait.Substring.html
 //! [`substring()`]: trait.Substring.html#tymethod.substring
@@ -45,9 +75,246 @@
 // not available in early versions.
 #![allow(deprecated)]
 #![cfg_attr(rustc_1_6, no_std)]
+#![cfg_attr(feature = "forbid_unsafe", forbid(unsafe_code))]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
 #[cfg(not(rustc_1_6))]
 extern crate std as core;
+#[cfg(all(rustc_1_6, any(test, feature = "std")))]
+#[macro_use]
+extern crate std;
+
+// Slices the string `$s` between the byte indices `$start` and `$end`, which must lie on UTF-8
+// sequence boundaries within its bounds, with `$start` not after `$end`. The bounds are only
+// checked when the `forbid_unsafe` feature is enabled.
+#[cfg(not(feature = "forbid_unsafe"))]
+macro_rules! slice_unchecked {
+    ($s:expr, $start:expr, $end:expr) => {{
+        let (s, start, end): (&str, usize, usize) = ($s, $start, $end);
+        unsafe { s.slice_unchecked(start, end) }
+    }};
+}
+#[cfg(feature = "forbid_unsafe")]
+macro_rules! slice_unchecked {
+    ($s:expr, $start:expr, $end:expr) => {{
+        let (s, start, end): (&str, usize, usize) = ($s, $start, $end);
+        &s[start..end]
+    }};
+}
+
+// Mutably slices the string `$s` within the byte range `$range`, under the same conditions as
+// `slice_unchecked!`.
+#[cfg(not(feature = "forbid_unsafe"))]
+macro_rules! slice_unchecked_mut {
+    ($s:expr, $range:expr) => {{
+        let (s, range): (&mut str, ::core::ops::Range<usize>) = ($s, $range);
+        unsafe { s.get_unchecked_mut(range) }
+    }};
+}
+#[cfg(feature = "forbid_unsafe")]
+macro_rules! slice_unchecked_mut {
+    ($s:expr, $range:expr) => {{
+        let (s, range): (&mut str, ::core::ops::Range<usize>) = ($s, $range);
+        &mut s[range]
+    }};
+}
+
+// Converts the bytes `$v`, which must be valid UTF-8, to a string slice. The bytes are only
+// validated when the `forbid_unsafe` feature is enabled.
+#[cfg(not(feature = "forbid_unsafe"))]
+macro_rules! from_utf8_unchecked {
+    ($v:expr) => {{
+        let v: &[u8] = $v;
+        unsafe { ::core::str::from_utf8_unchecked(v) }
+    }};
+}
+#[cfg(feature = "forbid_unsafe")]
+macro_rules! from_utf8_unchecked {
+    ($v:expr) => {
+        match ::core::str::from_utf8($v) {
+            Ok(s) => s,
+            Err(_) => unreachable!(),
+        }
+    };
+}
+
+#[cfg(rustc_1_28)]
+mod boundary;
+#[cfg(rustc_1_28)]
+mod bounds;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod byte_str;
+mod char_slice;
+#[cfg(rustc_1_28)]
+mod char_str;
+#[cfg(rustc_1_28)]
+mod char_substring;
+#[cfg(rustc_1_28)]
+mod checkpoint;
+#[cfg(rustc_1_28)]
+mod chunks;
+#[cfg(rustc_1_28)]
+mod compare;
+#[cfg(feature = "const_substring")]
+// The `const_substring` feature requires `rustc 1.64.0`, rather than the crate's minimum version.
+#[clippy::msrv = "1.64"]
+mod const_substring;
+#[cfg(rustc_1_28)]
+mod debug;
+#[cfg(rustc_1_28)]
+mod delimiter;
+#[cfg(rustc_1_28)]
+mod display;
+#[cfg(rustc_1_28)]
+mod error;
+#[cfg(all(rustc_1_28, feature = "testing"))]
+pub mod fixtures;
+#[cfg(rustc_1_28)]
+mod fold;
+#[cfg(all(rustc_1_28, feature = "fuzzing"))]
+pub mod fuzzing;
+#[cfg(rustc_1_28)]
+mod index;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod indexed;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod interner;
+#[cfg(rustc_1_28)]
+mod iter;
+#[cfg(rustc_1_28)]
+mod line;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod lossy;
+#[cfg(rustc_1_59)]
+mod micro;
+#[cfg(rustc_1_28)]
+mod offset;
+#[cfg(all(rustc_1_28, feature = "std"))]
+mod os_str;
+#[cfg(rustc_1_28)]
+mod pipeline;
+#[cfg(rustc_1_28)]
+mod render;
+#[cfg(rustc_1_28)]
+mod segment;
+#[cfg(rustc_1_28)]
+mod sentence;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod shared;
+#[cfg(rustc_1_28)]
+mod source_span;
+#[cfg(rustc_1_28)]
+mod span;
+#[cfg(all(rustc_1_28, feature = "stats"))]
+mod stats;
+#[cfg(rustc_1_28)]
+mod stream;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod string;
+#[cfg(rustc_1_28)]
+mod substring_mut;
+#[cfg(rustc_1_28)]
+mod text;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+mod truncate;
+#[cfg(rustc_1_28)]
+mod unit;
+#[cfg(rustc_1_28)]
+mod utf16;
+#[cfg(rustc_1_28)]
+mod wide;
+#[cfg(rustc_1_28)]
+mod width;
+#[cfg(rustc_1_28)]
+mod word;
+#[cfg(rustc_1_28)]
+mod wrap;
+
+#[cfg(rustc_1_28)]
+pub use boundary::{Direction, Snap};
+#[cfg(rustc_1_28)]
+pub use bounds::SizedRange;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use byte_str::ByteSubstring;
+pub use char_slice::CharSliceSubstring;
+#[cfg(rustc_1_28)]
+pub use char_str::CharStr;
+#[cfg(rustc_1_28)]
+pub use char_substring::CharSubstring;
+#[cfg(rustc_1_28)]
+pub use checkpoint::{CharIndicesFrom, Checkpoint};
+#[cfg(rustc_1_28)]
+pub use chunks::{IterSubstring, SubstringPieces};
+#[cfg(rustc_1_28)]
+pub use compare::{compare_ranges, ranges_equal};
+#[cfg(feature = "const_substring")]
+pub use const_substring::const_substring;
+#[cfg(rustc_1_28)]
+pub use delimiter::Delimiter;
+#[cfg(rustc_1_28)]
+pub use display::SubstringDisplay;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use error::FillError;
+#[cfg(rustc_1_28)]
+pub use error::{CharBoundaryError, IndexConversionError, RenderError, SubstringError};
+#[cfg(rustc_1_28)]
+pub use fold::FoldMatches;
+#[cfg(rustc_1_28)]
+pub use index::CharIndex;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use indexed::CharIndexedStr;
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use interner::{Interned, SubstringInterner};
+#[cfg(rustc_1_28)]
+pub use iter::{
+    CharChunks, CharIndicesInRev, CharWindows, CharsInRev, SubstringCharIndices, SubstringChars,
+    Substrings,
+};
+#[cfg(rustc_1_28)]
+pub use line::LineSubstring;
+#[cfg(rustc_1_59)]
+pub use micro::MicroIndexed;
+#[cfg(rustc_1_28)]
+pub use offset::{offset_in, range_in};
+#[cfg(all(rustc_1_28, feature = "std"))]
+pub use os_str::OsStrSubstring;
+#[cfg(rustc_1_28)]
+pub use pipeline::Pipeline;
+#[cfg(rustc_1_28)]
+pub use render::{render_fields, Align, Field};
+#[cfg(rustc_1_28)]
+pub use segment::{ByChars, Segmenter};
+#[cfg(rustc_1_28)]
+pub use sentence::{BySentences, SentenceSubstring};
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use shared::SharedSubstring;
+#[cfg(rustc_1_28)]
+pub use source_span::SourceSpan;
+#[cfg(rustc_1_28)]
+pub use span::{EditDelta, Span, ValidatedSpan};
+#[cfg(all(rustc_1_28, feature = "stats"))]
+pub use stats::{set_observer, ClampObserver, CountingObserver, SetObserverError};
+#[cfg(rustc_1_28)]
+pub use stream::{BoundaryState, ChunkBoundaries};
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use string::{fill_ranges, keep_char_range, replace_substring_tracked, StringSubstring};
+#[cfg(rustc_1_28)]
+pub use substring_mut::SubstringMut;
+#[cfg(rustc_1_28)]
+pub use text::{active_unit, TextSubstring};
+#[cfg(all(rustc_1_28, feature = "alloc"))]
+pub use truncate::{TruncatePosition, Truncator};
+#[cfg(rustc_1_28)]
+pub use unit::Unit;
+#[cfg(rustc_1_28)]
+pub use utf16::SurrogatePolicy;
+#[cfg(rustc_1_28)]
+pub use wide::WideSubstring;
+#[cfg(rustc_1_28)]
+pub use word::{ByWords, WordSubstring};
+#[cfg(rustc_1_28)]
+pub use wrap::{BreakOpportunities, WrapAtOpportunities};
 
 /// Provides a [`substring()`] method.
 ///
@@ -60,6 +327,7 @@ pub trait Substring {
     /// `start_index` and `end_index`.
     ///
     /// The range specified is a character range, not a byte range.
+    #[must_use]
     fn substring(&self, start_index: usize, end_index: usize) -> &str;
 }
 
@@ -77,39 +345,114 @@ impl Substring for str {
     /// As this is by character index, rather than byte index, the temporal complexity of finding a
     /// substring is *O(n)*, where *n* is the byte length of the string.
     ///
+    /// An empty result is always a slice of `self`, positioned where the range collapsed: at
+    /// `start_index`, or at the end of `self` if `start_index` is past its end. This holds even
+    /// when `end_index` is not after `start_index`.
+    ///
     /// Example:
     /// ```
     /// use substring::Substring;
     ///
     /// assert_eq!("foobar".substring(2,5), "oba");
     /// ```
-    #[must_use]
     fn substring(&self, start_index: usize, end_index: usize) -> &str {
-        if end_index <= start_index {
-            return "";
-        }
+        #[cfg(all(rustc_1_28, feature = "stats"))]
+        stats::observe_chars(self, start_index, Some(end_index));
 
-        let mut indices = self.char_indices();
-
-        let obtain_index = |(index, _char)| index;
-        let str_len = self.len();
-
-        unsafe {
-            // SAFETY: Since `indices` iterates over the `CharIndices` of `self`, we can guarantee
-            // that the indices obtained from it will always be within the bounds of `self` and they
-            // will always lie on UTF-8 sequence boundaries.
-            self.slice_unchecked(
-                indices.nth(start_index).map_or(str_len, &obtain_index),
-                indices
-                    .nth(end_index - start_index - 1)
-                    .map_or(str_len, &obtain_index),
-            )
+        let (start_byte, end_byte) = char_range_bytes(self, start_index, Some(end_index));
+        // SAFETY: `char_range_bytes()` always returns indices within the bounds of `self` which lie
+        // on UTF-8 sequence boundaries.
+        slice_unchecked!(self, start_byte, end_byte)
+    }
+}
+
+/// Returns the byte index at which the character with index `n` begins, or the length of `s` if
+/// `s` has no more than `n` characters.
+///
+/// Characters are not decoded. Instead, the bytes which begin characters, being those which are not
+/// UTF-8 continuation bytes, are counted eight at a time, and only the eight bytes containing the
+/// boundary are examined individually.
+///
+/// Example:
+/// ```
+/// use substring::nth_char_boundary;
+///
+/// assert_eq!(nth_char_boundary("fõøbα®", 3), 5);
+/// assert_eq!(nth_char_boundary("fõøbα®", 6), 10);
+/// assert_eq!(nth_char_boundary("fõøbα®", 100), 10);
+/// ```
+pub fn nth_char_boundary(s: &str, n: usize) -> usize {
+    let bytes = s.as_bytes();
+    // The number of character starts still to be passed.
+    let mut remaining = n;
+    let mut offset = 0;
+    for chunk in bytes.chunks(8) {
+        if chunk.len() == 8 {
+            let word = load_word(chunk);
+            // Continuation bytes are those with their highest bit set and the next bit unset.
+            let continuations = (word & !(word << 1) & 0x8080_8080_8080_8080).count_ones() as usize;
+            let starts = 8 - continuations;
+            if starts <= remaining {
+                remaining -= starts;
+                offset += 8;
+                continue;
+            }
+        }
+        for byte in chunk {
+            if (*byte as i8) >= -0x40 {
+                if remaining == 0 {
+                    return offset;
+                }
+                remaining -= 1;
+            }
+            offset += 1;
         }
     }
+    bytes.len()
+}
+
+/// Loads eight bytes as a single word, in an unspecified byte order.
+#[cfg(rustc_1_34)]
+#[clippy::msrv = "1.34"]
+#[inline]
+fn load_word(chunk: &[u8]) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(chunk);
+    u64::from_le_bytes(bytes)
+}
+
+/// Loads eight bytes as a single word, in an unspecified byte order.
+#[cfg(not(rustc_1_34))]
+#[inline]
+fn load_word(chunk: &[u8]) -> u64 {
+    chunk
+        .iter()
+        .fold(0u64, |word, byte| (word << 8) | *byte as u64)
+}
+
+/// Returns the byte indices of the start and end of the characters within `start_index..end_index`,
+/// clamped to the bounds of `s`.
+///
+/// An unbounded `end_index` extends to the end of `s`. If `end_index` is not after `start_index`,
+/// both byte indices are the start.
+fn char_range_bytes(s: &str, start_index: usize, end_index: Option<usize>) -> (usize, usize) {
+    let start_byte = nth_char_boundary(s, start_index);
+    let end_byte = match end_index {
+        Some(end_index) if end_index <= start_index => start_byte,
+        Some(end_index) => {
+            // SAFETY: `start_byte` was obtained from `nth_char_boundary()`, so it lies on a UTF-8
+            // sequence boundary within the bounds of `s`.
+            let rest = slice_unchecked!(s, start_byte, s.len());
+            start_byte + nth_char_boundary(rest, end_index - start_index)
+        }
+        None => s.len(),
+    };
+    (start_byte, end_byte)
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{char_range_bytes, nth_char_boundary};
     use Substring;
 
     #[test]
@@ -137,4 +480,155 @@ mod tests {
     fn test_multiple_byte_characters() {
         assert_eq!("fõøbα®".substring(2, 5), "øbα");
     }
+
+    /// Returns the byte offset of `sub` within `s`, asserting that `sub` lies within `s`.
+    fn offset_within(s: &str, sub: &str) -> usize {
+        let start = s.as_ptr() as usize;
+        let sub_start = sub.as_ptr() as usize;
+        assert!(sub_start >= start && sub_start + sub.len() <= start + s.len());
+        sub_start - start
+    }
+
+    #[test]
+    fn test_empty_result_position() {
+        let s = "fõøbα®";
+        // Equal indices collapse at the start.
+        assert_eq!(offset_within(s, s.substring(3, 3)), 5);
+        // Reversed indices collapse at the start.
+        assert_eq!(offset_within(s, s.substring(4, 2)), 6);
+        assert_eq!(offset_within(s, s.substring(1, 0)), 1);
+        // Starts past the end collapse at the end.
+        assert_eq!(offset_within(s, s.substring(6, 10)), 10);
+        assert_eq!(offset_within(s, s.substring(20, 10)), 10);
+        assert_eq!(offset_within(s, s.substring(0, 0)), 0);
+    }
+
+    #[test]
+    fn test_empty_result_within_parent() {
+        let s = "fõøbα®";
+        for start in 0..10 {
+            for end in 0..10 {
+                let sub = s.substring(start, end);
+                if sub.is_empty() {
+                    offset_within(s, sub);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_ascii_prefix() {
+        let s = "foobar_õø_baz";
+        assert_eq!(s.substring(0, 6), "foobar");
+        assert_eq!(s.substring(3, 8), "bar_õ");
+        assert_eq!(s.substring(7, 11), "õø_b");
+        assert_eq!(s.substring(8, 100), "ø_baz");
+        assert_eq!(s.substring(7, 7), "");
+    }
+
+    #[test]
+    fn test_ascii_prefix_offset_of_empty_result() {
+        let s = "foobar_õø_baz";
+        assert_eq!(offset_within(s, s.substring(3, 1)), 3);
+        assert_eq!(offset_within(s, s.substring(9, 2)), 11);
+        assert_eq!(offset_within(s, s.substring(40, 2)), 15);
+    }
+
+    #[test]
+    fn test_char_range_bytes_matches_walk() {
+        for s in ["", "foobar", "fõøbα®", "foo😀", "😀foo", "ab\u{301}cd"].iter() {
+            let walk = |index: usize| s.char_indices().nth(index).map_or(s.len(), |(i, _)| i);
+            for start in 0..10 {
+                for end in 0..10 {
+                    let start_byte = walk(start);
+                    let end_byte = if end <= start { start_byte } else { walk(end) };
+                    assert_eq!(
+                        char_range_bytes(s, start, Some(end)),
+                        (start_byte, end_byte)
+                    );
+                }
+                assert_eq!(char_range_bytes(s, start, None), (walk(start), s.len()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_nth_char_boundary() {
+        assert_eq!(nth_char_boundary("", 0), 0);
+        assert_eq!(nth_char_boundary("foobar", 0), 0);
+        assert_eq!(nth_char_boundary("foobar", 4), 4);
+        assert_eq!(nth_char_boundary("foobar", 6), 6);
+        assert_eq!(nth_char_boundary("fõøbα®", 2), 3);
+        assert_eq!(nth_char_boundary("😀a𝔘b", 3), 9);
+        assert_eq!(nth_char_boundary("😀a𝔘b", usize::max_value()), 10);
+    }
+
+    #[test]
+    fn test_nth_char_boundary_across_chunks() {
+        // "õ" straddles the first two eight-byte chunks.
+        let s = "abcdefgõhijklmnoøp";
+        for (index, (byte, _char)) in s.char_indices().enumerate() {
+            assert_eq!(nth_char_boundary(s, index), byte);
+        }
+        assert_eq!(nth_char_boundary(s, 18), s.len());
+    }
+
+    #[test]
+    fn test_nth_char_boundary_matches_walk() {
+        let alphabet = ['a', 'õ', 'α', '®', '😀', '𝔘', '\u{301}', ' '];
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..500 {
+            let len = (next() % 40) as usize;
+            let s: ::std::string::String = (0..len)
+                .map(|_| alphabet[(next() % alphabet.len() as u64) as usize])
+                .collect();
+            for n in 0..(len + 2) {
+                let expected = s.char_indices().nth(n).map_or(s.len(), |(i, _)| i);
+                assert_eq!(nth_char_boundary(&s, n), expected);
+            }
+        }
+    }
+
+    /// Compares the speed of `nth_char_boundary()` against walking `char_indices()`, for an index
+    /// near the end of a multi-megabyte string.
+    ///
+    /// Timings are only meaningful in an optimized build, so this is run with
+    /// `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    fn bench_nth_char_boundary() {
+        use std::time::Instant;
+
+        let s = "fõøbα®😀a𝔘b日本語".repeat(1 << 17);
+        let n = s.chars().count() - 7;
+        let expected = s.char_indices().nth(n).map_or(s.len(), |(i, _)| i);
+
+        let start = Instant::now();
+        for _ in 0..20 {
+            assert_eq!(
+                s.char_indices().nth(n).map_or(s.len(), |(i, _)| i),
+                expected
+            );
+        }
+        let walk = start.elapsed();
+        let start = Instant::now();
+        for _ in 0..20 {
+            assert_eq!(nth_char_boundary(&s, n), expected);
+        }
+        let count = start.elapsed();
+
+        println!(
+            "{} bytes: char_indices().nth() {:?}, nth_char_boundary() {:?}",
+            s.len(),
+            walk / 20,
+            count / 20
+        );
+        assert!(count < walk);
+    }
 }
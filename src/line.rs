@@ -0,0 +1,189 @@
+//! Substrings indexed by lines.
+//!
+//! Lines are counted as [`str::lines()`] counts them. A line ends at a `\n` or a `\r\n`, which is
+//! not part of the line, and the final line of a string need not end with either. A line ending
+//! at the end of the string does not begin another, empty, line.
+//!
+//! [`str::lines()`]: https://doc.rust-lang.org/std/primitive.str.html#method.lines
+
+use core::ops::{Range, RangeBounds};
+use word;
+
+/// An iterator over the byte ranges of the lines of a string, excluding their line endings.
+struct Lines<'a> {
+    s: &'a str,
+    /// The byte index at which the next line begins.
+    byte: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(s: &'a str) -> Self {
+        Lines { s, byte: 0 }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        let start = self.byte;
+        if start >= self.s.len() {
+            return None;
+        }
+        let bytes = self.s.as_bytes();
+        match bytes[start..].iter().position(|&byte| byte == b'\n') {
+            Some(offset) => {
+                let newline = start + offset;
+                self.byte = newline + 1;
+                if newline > start && bytes[newline - 1] == b'\r' {
+                    Some(start..(newline - 1))
+                } else {
+                    Some(start..newline)
+                }
+            }
+            None => {
+                self.byte = self.s.len();
+                Some(start..self.s.len())
+            }
+        }
+    }
+}
+
+/// Provides [`line_substring()`] and [`line_byte_range()`] methods indexed by lines.
+///
+/// See the [module documentation] for how lines are counted.
+///
+/// [module documentation]: index.html
+/// [`line_byte_range()`]: trait.LineSubstring.html#tymethod.line_byte_range
+/// [`line_substring()`]: trait.LineSubstring.html#tymethod.line_substring
+pub trait LineSubstring {
+    /// Obtains a string slice spanning the lines within `range`.
+    ///
+    /// The slice extends from the start of the first line to the end of the last line, including
+    /// the line endings between them, but not the line ending of the last line, just as the last
+    /// line yielded by [`str::lines()`] would not include it. The range is clamped to the number
+    /// of lines in the string, and a range whose end is not after its start results in an empty
+    /// string, positioned at the start of the line it starts at.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::LineSubstring;
+    ///
+    /// let s = "one\ntwo\r\nthree\nfour";
+    /// assert_eq!(s.line_substring(1..3), "two\r\nthree");
+    /// assert_eq!(s.line_substring(3..), "four");
+    /// ```
+    ///
+    /// [`str::lines()`]: https://doc.rust-lang.org/std/primitive.str.html#method.lines
+    #[must_use]
+    fn line_substring<R: RangeBounds<usize>>(&self, range: R) -> &str;
+
+    /// Returns the byte range of the slice [`line_substring()`] would return for `range`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::LineSubstring;
+    ///
+    /// assert_eq!("one\ntwo\r\nthree".line_byte_range(1..2), 4..7);
+    /// ```
+    ///
+    /// [`line_substring()`]: #tymethod.line_substring
+    #[must_use]
+    fn line_byte_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize>;
+}
+
+impl LineSubstring for str {
+    fn line_substring<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        word::segment_substring(self, Lines::new(self), &range)
+    }
+
+    fn line_byte_range<R: RangeBounds<usize>>(&self, range: R) -> Range<usize> {
+        word::segment_byte_range(self, Lines::new(self), &range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineSubstring, Lines};
+    use std::vec::Vec;
+
+    const CORPUS: [&str; 9] = [
+        "",
+        "\n",
+        "one",
+        "one\n",
+        "one\ntwo",
+        "one\r\ntwo\r\n",
+        "\n\none\n\n",
+        "fõø\r\nbα®\rx\n",
+        "a\r",
+    ];
+
+    fn lines(s: &str) -> Vec<&str> {
+        Lines::new(s).map(|range| &s[range]).collect()
+    }
+
+    #[test]
+    fn test_lines_match_str_lines() {
+        for s in CORPUS.iter() {
+            assert_eq!(lines(s), s.lines().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_line_substring() {
+        let s = "one\ntwo\r\nthree\nfour";
+        assert_eq!(s.line_substring(..), s);
+        assert_eq!(s.line_substring(0..1), "one");
+        assert_eq!(s.line_substring(1..3), "two\r\nthree");
+        assert_eq!(s.line_substring(..=1), "one\ntwo");
+        assert_eq!(s.line_substring(3..), "four");
+        assert_eq!(s.line_substring(2..10), "three\nfour");
+    }
+
+    #[test]
+    fn test_line_substring_trailing_newline() {
+        let s = "one\ntwo\n";
+        assert_eq!(s.line_substring(..), "one\ntwo");
+        assert_eq!(s.line_substring(1..), "two");
+        assert_eq!(s.line_substring(2..), "");
+        assert_eq!(s.line_substring(2..).as_ptr(), s[8..].as_ptr());
+    }
+
+    #[test]
+    fn test_line_substring_empty_lines() {
+        let s = "\n\none\n\n";
+        assert_eq!(s.line_substring(0..1), "");
+        assert_eq!(s.line_substring(1..2).as_ptr(), s[1..].as_ptr());
+        assert_eq!(s.line_substring(2..3), "one");
+        assert_eq!(s.line_substring(1..4), "\none\n");
+    }
+
+    #[test]
+    fn test_line_substring_empty() {
+        let s = "one\ntwo\nthree";
+        let (start, end) = (2, 1);
+        assert_eq!(s.line_substring(start..end), "");
+        assert_eq!(s.line_substring(start..end).as_ptr(), s[8..].as_ptr());
+        assert_eq!(s.line_substring(5..), "");
+        assert_eq!("".line_substring(..), "");
+    }
+
+    #[test]
+    fn test_line_byte_range() {
+        let s = "one\ntwo\r\nthree\nfour";
+        assert_eq!(s.line_byte_range(1..3), 4..14);
+        assert_eq!(s.line_byte_range(3..), 15..19);
+        assert_eq!(s.line_byte_range(10..), 19..19);
+        for s in CORPUS.iter() {
+            for start in 0..6 {
+                for end in 0..6 {
+                    assert_eq!(
+                        &s[s.line_byte_range(start..end)],
+                        s.line_substring(start..end)
+                    );
+                }
+            }
+        }
+    }
+}
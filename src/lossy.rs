@@ -0,0 +1,167 @@
+//! Character indexing of byte strings which may not be valid UTF-8.
+//!
+//! Each maximal invalid sequence of bytes counts as a single character, as in
+//! [`String::from_utf8_lossy()`], where it would be replaced by a single `U+FFFD`.
+//!
+//! [`String::from_utf8_lossy()`]: https://doc.rust-lang.org/std/string/struct.String.html#method.from_utf8_lossy
+
+#[cfg(test)]
+use core::cell::Cell;
+use core::ops::Range;
+use core::str::CharIndices;
+
+/// The number of bytes validated at a time.
+///
+/// As no UTF-8 sequence is longer than four bytes, a window of at least four bytes always contains
+/// the whole of the sequence it starts with.
+const WINDOW: usize = 64;
+
+#[cfg(test)]
+thread_local! {
+    /// The number of bytes validated by the current thread, for testing that only the bytes which
+    /// are needed are decoded.
+    pub(crate) static VALIDATED: Cell<usize> = Cell::new(0);
+}
+
+/// An iterator over the byte indices at which the characters of a byte string begin, counting
+/// each maximal invalid UTF-8 sequence as a single character.
+struct LossyCharStarts<'a> {
+    bytes: &'a [u8],
+    /// The byte index following the valid characters being yielded.
+    offset: usize,
+    /// The valid characters preceding `offset`, with the byte index of the first of them.
+    valid: (usize, CharIndices<'a>),
+}
+
+impl<'a> LossyCharStarts<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        LossyCharStarts {
+            bytes,
+            offset: 0,
+            valid: (0, "".char_indices()),
+        }
+    }
+}
+
+impl<'a> Iterator for LossyCharStarts<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if let Some((index, _char)) = self.valid.1.next() {
+                return Some(self.valid.0 + index);
+            }
+            if self.offset >= self.bytes.len() {
+                return None;
+            }
+            let rest = &self.bytes[self.offset..];
+            let window = &rest[..rest.len().min(WINDOW)];
+            #[cfg(test)]
+            VALIDATED.with(|validated| validated.set(validated.get() + window.len()));
+            let (valid_len, invalid_len) = match core::str::from_utf8(window) {
+                Ok(_) => (window.len(), 0),
+                Err(error) => match error.error_len() {
+                    Some(error_len) => (error.valid_up_to(), error_len),
+                    // The window ends within a sequence, which is only invalid if the bytes end
+                    // there too. Otherwise, the sequence is validated with the next window.
+                    None if window.len() < rest.len() => (error.valid_up_to(), 0),
+                    None => (error.valid_up_to(), window.len() - error.valid_up_to()),
+                },
+            };
+            if valid_len == 0 {
+                let invalid = self.offset;
+                self.offset += invalid_len;
+                return Some(invalid);
+            }
+            // SAFETY: The first `valid_len` bytes of `rest` were validated as UTF-8.
+            let valid = from_utf8_unchecked!(&rest[..valid_len]);
+            self.valid = (self.offset, valid.char_indices());
+            self.offset += valid_len;
+        }
+    }
+}
+
+/// Returns the byte range of the characters of `bytes` within `start..end`, clamped to the bounds
+/// of `bytes`.
+///
+/// An unbounded `end` extends to the end of `bytes`. If `end` is not after `start`, the returned
+/// range is empty and positioned at the start.
+pub(crate) fn char_range_to_byte_range(
+    bytes: &[u8],
+    start: usize,
+    end: Option<usize>,
+) -> Range<usize> {
+    let mut starts = LossyCharStarts::new(bytes);
+    let start_byte = starts.nth(start).unwrap_or(bytes.len());
+    let end_byte = match end {
+        Some(end) if end <= start => start_byte,
+        Some(end) => starts.nth(end - start - 1).unwrap_or(bytes.len()),
+        None => bytes.len(),
+    };
+    start_byte..end_byte
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{char_range_to_byte_range, LossyCharStarts, VALIDATED};
+    use core::cell::Cell;
+    use std::vec::Vec;
+
+    fn starts(bytes: &[u8]) -> Vec<usize> {
+        LossyCharStarts::new(bytes).collect()
+    }
+
+    #[test]
+    fn test_valid() {
+        assert_eq!(starts(b""), []);
+        assert_eq!(starts("fõø".as_bytes()), [0, 1, 3]);
+    }
+
+    #[test]
+    fn test_invalid_sequences() {
+        // "\xe2\x82" is a truncated sequence, and each lone continuation byte is its own sequence.
+        assert_eq!(starts(b"a\xe2\x82b\x80\x80\xff"), [0, 1, 3, 4, 5, 6]);
+        assert_eq!(starts(b"\xf0\x9f\x98"), [0]);
+    }
+
+    #[test]
+    fn test_matches_lossy_conversion() {
+        let bytes = b"\xf0\x9f\x98a\x80\xc3\xb8\xed\xa0\x80b\xff";
+        let lossy = ::std::string::String::from_utf8_lossy(bytes);
+        assert_eq!(starts(bytes).len(), lossy.chars().count());
+    }
+
+    #[test]
+    fn test_char_range_to_byte_range() {
+        let bytes = b"f\xffo\xe2\x82\xc3\xb8bar";
+        assert_eq!(char_range_to_byte_range(bytes, 1, Some(3)), 1..3);
+        assert_eq!(char_range_to_byte_range(bytes, 3, None), 3..10);
+        assert_eq!(char_range_to_byte_range(bytes, 5, Some(100)), 7..10);
+        assert_eq!(char_range_to_byte_range(bytes, 4, Some(2)), 5..5);
+        assert_eq!(char_range_to_byte_range(bytes, 20, Some(30)), 10..10);
+    }
+
+    #[test]
+    fn test_sequences_across_windows() {
+        // Each "ø" is two bytes, so the windows end within one.
+        let mut bytes = "a".as_bytes().to_vec();
+        for _ in 0..100 {
+            bytes.extend_from_slice("ø".as_bytes());
+        }
+        bytes.extend_from_slice(b"\xe2\x82");
+        let expected: Vec<usize> = (0..101)
+            .map(|i| if i == 0 { 0 } else { 2 * i - 1 })
+            .collect();
+        assert_eq!(starts(&bytes)[..101], expected[..]);
+        assert_eq!(starts(&bytes)[101..], [201]);
+    }
+
+    #[test]
+    fn test_decodes_only_needed_bytes() {
+        let mut bytes = "fõø".as_bytes().to_vec();
+        bytes.extend(::std::iter::repeat(b'a').take(1 << 20));
+        VALIDATED.with(|validated| validated.set(0));
+        assert_eq!(char_range_to_byte_range(&bytes, 0, Some(3)), 0..5);
+        assert!(VALIDATED.with(Cell::get) <= 64);
+    }
+}
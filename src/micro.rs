@@ -0,0 +1,225 @@
+//! Inline character indices for short strings.
+
+use core::fmt;
+use debug::TruncatedText;
+use Substring;
+
+/// A string slice with up to `N` inline checkpoints, bounding the walk needed to find a character
+/// index.
+///
+/// The checkpoints record the byte offsets of evenly spaced characters, and are found in a single
+/// pass on construction. No heap allocation is performed; the checkpoints are stored as `u16`
+/// values within the struct itself. Slicing by character index then only walks from the nearest
+/// preceding checkpoint, at most `char_len / N` characters.
+///
+/// Strings longer than `u16::MAX` bytes cannot be represented by the inline checkpoints. For such
+/// strings, no checkpoints are recorded, and slicing walks from the start of the string, exactly
+/// as [`str::substring()`] does.
+///
+/// Example:
+/// ```
+/// use substring::{MicroIndexed, Substring};
+///
+/// let indexed: MicroIndexed = MicroIndexed::new("fõøbα®");
+/// assert_eq!(indexed.char_len(), 6);
+/// assert_eq!(indexed.substring(2, 5), "øbα");
+/// ```
+///
+/// [`str::substring()`]: trait.Substring.html#tymethod.substring
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct MicroIndexed<'a, const N: usize = 16> {
+    s: &'a str,
+    /// The number of characters in `s`, if it is short enough to be indexed.
+    char_len: Option<u16>,
+    /// The number of characters between checkpoints.
+    stride: u16,
+    /// The byte offset of every `stride`th character.
+    checkpoints: [u16; N],
+}
+
+impl<'a, const N: usize> MicroIndexed<'a, N> {
+    /// Indexes `s` in a single pass.
+    pub fn new(s: &'a str) -> Self {
+        let mut indexed = MicroIndexed {
+            s,
+            char_len: None,
+            stride: 1,
+            checkpoints: [0; N],
+        };
+        if N == 0 || s.len() > usize::from(u16::max_value()) {
+            return indexed;
+        }
+        // A string of at most `u16::MAX` bytes has at most `u16::MAX` characters.
+        let char_len = s.chars().count();
+        let stride = (char_len + N - 1) / N;
+        let stride = if stride == 0 { 1 } else { stride };
+        for (char_index, (byte_index, _char)) in s.char_indices().enumerate().step_by(stride) {
+            indexed.checkpoints[char_index / stride] = byte_index as u16;
+        }
+        indexed.char_len = Some(char_len as u16);
+        indexed.stride = stride as u16;
+        indexed
+    }
+
+    /// Returns the underlying string slice.
+    pub fn as_str(&self) -> &'a str {
+        self.s
+    }
+
+    /// Returns the number of characters in the string.
+    ///
+    /// This takes constant time if the string is indexed, and is otherwise *O(n)*.
+    pub fn char_len(&self) -> usize {
+        match self.char_len {
+            Some(char_len) => usize::from(char_len),
+            None => self.s.chars().count(),
+        }
+    }
+
+    /// Returns whether the string was short enough for its checkpoints to be recorded.
+    pub fn is_indexed(&self) -> bool {
+        self.char_len.is_some()
+    }
+
+    /// Returns the byte index of the character at `char_index`, or the length of the string if
+    /// `char_index` is past its end.
+    fn byte_index(&self, char_index: usize) -> usize {
+        let char_len = match self.char_len {
+            Some(char_len) => usize::from(char_len),
+            None => {
+                return self
+                    .s
+                    .char_indices()
+                    .nth(char_index)
+                    .map_or(self.s.len(), |(index, _char)| index)
+            }
+        };
+        if char_index >= char_len {
+            return self.s.len();
+        }
+        let stride = usize::from(self.stride);
+        let checkpoint = usize::from(self.checkpoints[char_index / stride]);
+        // SAFETY: Each checkpoint was obtained from the `CharIndices` of the string.
+        slice_unchecked!(self.s, checkpoint, self.s.len())
+            .char_indices()
+            .nth(char_index % stride)
+            .map_or(self.s.len(), |(index, _char)| checkpoint + index)
+    }
+}
+
+/// Formats the number of characters, if indexed, along with the text, as in
+/// `MicroIndexed { char_len: Some(6), text: "fõøbα®" }`.
+///
+/// Text longer than 32 characters is truncated and followed by an ellipsis.
+impl<'a, const N: usize> fmt::Debug for MicroIndexed<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MicroIndexed")
+            .field("char_len", &self.char_len)
+            .field("text", &TruncatedText(self.s))
+            .finish()
+    }
+}
+
+impl<'a, const N: usize> Substring for MicroIndexed<'a, N> {
+    fn substring(&self, start_index: usize, end_index: usize) -> &str {
+        let start_byte = self.byte_index(start_index);
+        let end_byte = if end_index <= start_index {
+            start_byte
+        } else {
+            self.byte_index(end_index)
+        };
+        // SAFETY: Both byte indices were obtained from the `CharIndices` of the string, or are its
+        // length.
+        slice_unchecked!(self.s, start_byte, end_byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MicroIndexed;
+    use std::string::String;
+    use Substring;
+
+    fn assert_equivalent<const N: usize>(s: &str) {
+        let indexed = MicroIndexed::<N>::new(s);
+        let char_len = s.chars().count();
+        assert_eq!(indexed.char_len(), char_len);
+        for start in 0..(char_len + 2) {
+            for end in 0..(char_len + 2) {
+                assert_eq!(indexed.substring(start, end), s.substring(start, end));
+            }
+        }
+    }
+
+    #[test]
+    fn test_equivalent_to_str() {
+        for s in [
+            "",
+            "a",
+            "foobar",
+            "fõøbα®",
+            "😀a𝔘b",
+            "日本語😀 fõøbα® foobar 😀a𝔘b",
+        ]
+        .iter()
+        {
+            assert_equivalent::<0>(s);
+            assert_equivalent::<1>(s);
+            assert_equivalent::<3>(s);
+            assert_equivalent::<16>(s);
+            assert_equivalent::<100>(s);
+        }
+    }
+
+    #[test]
+    fn test_default_checkpoints() {
+        let indexed: MicroIndexed = MicroIndexed::new("fõøbα®");
+        assert_eq!(indexed.checkpoints.len(), 16);
+        assert!(indexed.is_indexed());
+    }
+
+    #[test]
+    fn test_longest_indexed() {
+        let mut s: String = (0..(usize::from(u16::max_value()) - 1))
+            .map(|_| 'a')
+            .collect();
+        s.push('õ');
+        assert_eq!(s.len(), usize::from(u16::max_value()) + 1);
+        s.pop();
+        s.push('b');
+        assert_eq!(s.len(), usize::from(u16::max_value()));
+
+        let indexed: MicroIndexed = MicroIndexed::new(&s);
+        assert!(indexed.is_indexed());
+        assert_eq!(indexed.char_len(), s.len());
+        assert_eq!(indexed.substring(65530, 65540), "aaaab");
+        assert_eq!(indexed.substring(4095, 4098), "aaa");
+    }
+
+    #[test]
+    fn test_too_long_to_index() {
+        let mut s: String = (0..(usize::from(u16::max_value()) - 1))
+            .map(|_| 'a')
+            .collect();
+        s.push('õ');
+        let indexed: MicroIndexed = MicroIndexed::new(&s);
+        assert!(!indexed.is_indexed());
+        assert_eq!(indexed.char_len(), s.chars().count());
+        assert_eq!(indexed.substring(65530, 65540), "aaaaõ");
+        assert_eq!(indexed.substring(70000, 70001), "");
+    }
+
+    #[test]
+    fn test_as_str() {
+        let s = "fõøbα®";
+        assert_eq!(MicroIndexed::<4>::new(s).as_str(), s);
+    }
+
+    #[test]
+    fn test_debug() {
+        assert_eq!(
+            format!("{:?}", MicroIndexed::<4>::new("fõøbα®")),
+            "MicroIndexed { char_len: Some(6), text: \"fõøbα®\" }"
+        );
+    }
+}
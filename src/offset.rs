@@ -0,0 +1,215 @@
+//! Recovery of the position of a slice within the string it was sliced from.
+
+use core::ops::Range;
+
+/// Returns the byte offset at which `sub` begins within `parent`, or `None` if `sub` is not a
+/// slice of `parent`.
+///
+/// Every string slice returned by this crate is a slice of the string it was obtained from,
+/// including empty results, which are positioned where their range collapsed. This allows the
+/// position of a result to be recovered without searching for it.
+///
+/// Slices are compared by address, so a slice with the same contents as part of `parent` but
+/// stored elsewhere is not a slice of it. An empty `sub` is a slice of `parent` if its address
+/// lies anywhere from the start to the end of `parent`, inclusive.
+///
+/// Example:
+/// ```
+/// use substring::{offset_in, Substring};
+///
+/// let s = "fõøbα®";
+/// assert_eq!(offset_in(s.substring(2, 5), s), Some(3));
+/// assert_eq!(offset_in("øbα", s), None);
+/// ```
+pub fn offset_in(sub: &str, parent: &str) -> Option<usize> {
+    let parent_start = parent.as_ptr() as usize;
+    let sub_start = sub.as_ptr() as usize;
+    let offset = sub_start.checked_sub(parent_start)?;
+    if offset <= parent.len() && sub.len() <= parent.len() - offset {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+/// Returns the byte range covered by `sub` within `parent`, or `None` if `sub` is not a slice of
+/// `parent`.
+///
+/// The returned range always lies on character boundaries of `parent`. See [`offset_in()`] for
+/// when a slice is considered to be a slice of `parent`.
+///
+/// Example:
+/// ```
+/// use substring::{range_in, CharSubstring};
+///
+/// let s = "fõøbα®";
+/// assert_eq!(range_in(s.truncate_at_word(3), s), Some(0..5));
+/// ```
+///
+/// [`offset_in()`]: fn.offset_in.html
+pub fn range_in(sub: &str, parent: &str) -> Option<Range<usize>> {
+    offset_in(sub, parent).map(|offset| offset..(offset + sub.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{offset_in, range_in};
+    use boundary::Snap;
+    use checkpoint::Checkpoint;
+    use core::ops::Range;
+    use pipeline::Pipeline;
+    use source_span::SourceSpan;
+    use span::Span;
+    use std::vec::Vec;
+    use text::TextSubstring;
+    use unit::{char_range_to_byte_range, to_byte_range, Unit};
+    use CharSubstring;
+    use Substring;
+
+    const CORPUS: [&str; 5] = ["", "foobar", "fõøbα®", "😀a𝔘b", " a\u{301} b-c "];
+
+    /// Returns every range with bounds up to two past the byte length of `s`.
+    fn ranges(s: &str) -> Vec<Range<usize>> {
+        let max = s.len() + 2;
+        let mut ranges = Vec::new();
+        for start in 0..max {
+            for end in 0..max {
+                ranges.push(start..end);
+            }
+        }
+        ranges
+    }
+
+    fn char_bytes(s: &str, range: &Range<usize>) -> Option<Range<usize>> {
+        Some(char_range_to_byte_range(s, range.start, Some(range.end)))
+    }
+
+    #[test]
+    fn test_offset_in() {
+        let s = "foobar";
+        assert_eq!(offset_in(s, s), Some(0));
+        assert_eq!(offset_in(&s[2..4], s), Some(2));
+        assert_eq!(offset_in(&s[6..], s), Some(6));
+    }
+
+    #[test]
+    fn test_not_a_subslice() {
+        let s = "foobar";
+        let other = ::std::string::String::from("foobar");
+        assert_eq!(offset_in(&other, s), None);
+        assert_eq!(offset_in(s, &s[1..]), None);
+        assert_eq!(offset_in(&s[2..], &s[..4]), None);
+    }
+
+    #[test]
+    fn test_range_in() {
+        let s = "fõøbα®";
+        assert_eq!(range_in(&s[3..6], s), Some(3..6));
+        assert_eq!(range_in(&s[10..], s), Some(10..10));
+        assert_eq!(range_in(s, &s[1..]), None);
+    }
+
+    #[test]
+    fn test_substring_contained() {
+        for s in CORPUS.iter() {
+            for range in ranges(s) {
+                assert_eq!(
+                    range_in(s.substring(range.start, range.end), s),
+                    char_bytes(s, &range)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_char_substring_methods_contained() {
+        for s in CORPUS.iter() {
+            for range in ranges(s) {
+                let expected = char_bytes(s, &range);
+                assert_eq!(range_in(s.text_substring(range.clone()), s), expected);
+                assert_eq!(
+                    range_in(
+                        s.substring_from_checkpoint(Checkpoint::default(), range.clone())
+                            .unwrap(),
+                        s
+                    ),
+                    expected
+                );
+                assert_eq!(
+                    range_in(Pipeline::new(s).chars(range.clone()).as_str(), s),
+                    expected
+                );
+                if let Ok(span) = Span::new(range.start, range.end) {
+                    assert_eq!(range_in(SourceSpan::new(s, span).text(), s), expected);
+                    if let Ok(validated) = span.validate(s) {
+                        assert_eq!(range_in(validated.as_str(), s), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_methods_contained() {
+        for s in CORPUS.iter() {
+            for range in ranges(s) {
+                let utf16 = to_byte_range(s, Unit::Utf16, range.start, Some(range.end));
+                assert_eq!(range_in(s.utf16_substring(range.clone()), s), Some(utf16));
+                let bytes = to_byte_range(s, Unit::Bytes, range.start, Some(range.end));
+                assert_eq!(
+                    range_in(s.byte_substring_snapped(range.clone(), Snap::Inward), s),
+                    Some(bytes)
+                );
+                for &snap in [Snap::Outward, Snap::Backward, Snap::Forward].iter() {
+                    assert!(range_in(s.byte_substring_snapped(range.clone(), snap), s).is_some());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_runs_and_prefixes_contained() {
+        for s in CORPUS.iter() {
+            for start in 0..(s.len() + 2) {
+                let run = s.char_range_while(start, char::is_alphabetic);
+                let expected = char_bytes(s, &run);
+                assert_eq!(
+                    range_in(s.span_while(start, char::is_alphabetic), s),
+                    expected
+                );
+                let run = s.char_range_while(start, |c| !c.is_whitespace());
+                assert_eq!(
+                    range_in(s.span_until(start, char::is_whitespace), s),
+                    char_bytes(s, &run)
+                );
+                let prefix = s.truncate_at_word(start);
+                assert_eq!(range_in(prefix, s).map(|range| range.start), Some(0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterators_contained() {
+        for s in CORPUS.iter() {
+            for line in s.wrap_at_opportunities(3) {
+                assert!(range_in(line, s).is_some());
+            }
+        }
+    }
+
+    #[cfg(rustc_1_59)]
+    #[test]
+    fn test_micro_indexed_contained() {
+        use micro::MicroIndexed;
+
+        for s in CORPUS.iter() {
+            let indexed = MicroIndexed::<4>::new(s);
+            for range in ranges(s) {
+                assert_eq!(
+                    range_in(indexed.substring(range.start, range.end), s),
+                    char_bytes(s, &range)
+                );
+            }
+        }
+    }
+}
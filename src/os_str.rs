@@ -0,0 +1,164 @@
+//! Character-indexed substrings of platform strings.
+
+use alloc::borrow::Cow;
+#[cfg(unix)]
+use lossy;
+use std::ffi::OsStr;
+#[cfg(not(any(unix, windows)))]
+use std::ffi::OsString;
+#[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(windows)]
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+#[cfg(windows)]
+use std::vec::Vec;
+#[cfg(not(any(unix, windows)))]
+use unit::char_range_to_byte_range;
+use Substring;
+
+/// Provides a [`substring()`] method for [`OsStr`].
+///
+/// Platform strings which are valid UTF-8 are sliced exactly as by [`Substring::substring()`],
+/// and the result is borrowed. Otherwise, the characters of the string depend on the platform:
+///
+/// - On Unix, the string is a sequence of bytes. Each maximal invalid UTF-8 sequence counts as
+///   a single character, just as it is replaced by a single `U+FFFD` in
+///   [`OsStr::to_string_lossy()`]. The result is always borrowed.
+/// - On Windows, the string is a sequence of potentially ill-formed UTF-16. Each unpaired
+///   surrogate counts as a single character. The result is owned if the string is not valid
+///   UTF-8.
+/// - Elsewhere, the string is sliced as its [`OsStr::to_string_lossy()`] conversion, so the
+///   invalid sequences within the result are replaced by `U+FFFD`.
+///
+/// [`OsStr`]: https://doc.rust-lang.org/std/ffi/struct.OsStr.html
+/// [`OsStr::to_string_lossy()`]: https://doc.rust-lang.org/std/ffi/struct.OsStr.html#method.to_string_lossy
+/// [`substring()`]: trait.OsStrSubstring.html#tymethod.substring
+/// [`Substring::substring()`]: trait.Substring.html#tymethod.substring
+pub trait OsStrSubstring {
+    /// Obtains the characters within the range specified by `start_index` and `end_index`.
+    ///
+    /// See the [trait documentation] for what is considered a character of a platform string.
+    /// The range is clamped to the bounds of the string, as with [`Substring::substring()`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::ffi::OsStr;
+    /// use substring::OsStrSubstring;
+    ///
+    /// assert_eq!(OsStr::new("fõøbα®.txt").substring(0, 6), OsStr::new("fõøbα®"));
+    /// ```
+    ///
+    /// [trait documentation]: trait.OsStrSubstring.html
+    /// [`Substring::substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    fn substring(&self, start_index: usize, end_index: usize) -> Cow<'_, OsStr>;
+}
+
+impl OsStrSubstring for OsStr {
+    fn substring(&self, start_index: usize, end_index: usize) -> Cow<'_, OsStr> {
+        if let Some(s) = self.to_str() {
+            return Cow::Borrowed(OsStr::new(s.substring(start_index, end_index)));
+        }
+        substring_ill_formed(self, start_index, end_index)
+    }
+}
+
+#[cfg(unix)]
+fn substring_ill_formed(s: &OsStr, start_index: usize, end_index: usize) -> Cow<'_, OsStr> {
+    let bytes = s.as_bytes();
+    let range = lossy::char_range_to_byte_range(bytes, start_index, Some(end_index));
+    Cow::Borrowed(OsStr::from_bytes(&bytes[range]))
+}
+
+#[cfg(windows)]
+fn substring_ill_formed(s: &OsStr, start_index: usize, end_index: usize) -> Cow<'_, OsStr> {
+    let wide: Vec<u16> = s.encode_wide().collect();
+    let mut starts = core::char::decode_utf16(wide.iter().cloned()).scan(0, |offset, c| {
+        let start = *offset;
+        *offset += match c {
+            Ok(c) => c.len_utf16(),
+            Err(_) => 1,
+        };
+        Some(start)
+    });
+    let start_unit = starts.nth(start_index).unwrap_or(wide.len());
+    let end_unit = if end_index <= start_index {
+        start_unit
+    } else {
+        starts
+            .nth(end_index - start_index - 1)
+            .unwrap_or(wide.len())
+    };
+    Cow::Owned(std::ffi::OsString::from_wide(&wide[start_unit..end_unit]))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn substring_ill_formed(s: &OsStr, start_index: usize, end_index: usize) -> Cow<'_, OsStr> {
+    let lossy = s.to_string_lossy();
+    let bytes = char_range_to_byte_range(&lossy, start_index, Some(end_index));
+    Cow::Owned(OsString::from(&lossy[bytes]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OsStrSubstring;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn test_valid_utf8() {
+        let s = OsStr::new("fõøbα®");
+        assert_eq!(s.substring(1, 4), OsStr::new("õøb"));
+        assert_eq!(s.substring(3, 100), OsStr::new("bα®"));
+        assert_eq!(s.substring(4, 2), OsStr::new(""));
+        assert!(match s.substring(1, 4) {
+            ::alloc::borrow::Cow::Borrowed(_) => true,
+            ::alloc::borrow::Cow::Owned(_) => false,
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_unix() {
+        use std::os::unix::ffi::OsStrExt;
+
+        // "\xff" and the truncated sequence "\xe2\x82" each count as one character.
+        let s = OsStr::from_bytes(b"f\xffo\xe2\x82\xc3\xb8bar");
+        assert_eq!(s.substring(0, 3).as_bytes(), b"f\xffo");
+        assert_eq!(s.substring(1, 2).as_bytes(), b"\xff");
+        assert_eq!(s.substring(3, 5).as_bytes(), b"\xe2\x82\xc3\xb8");
+        assert_eq!(s.substring(5, 100).as_bytes(), b"bar");
+        assert_eq!(s.substring(8, 9).as_bytes(), b"");
+        assert_eq!(s.substring(4, 2).as_bytes(), b"");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_unix_lossy_char_count() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let s = OsStr::from_bytes(b"\xf0\x9f\x98a\x80\x80b\xff");
+        let count = s.to_string_lossy().chars().count();
+        assert_eq!(s.substring(0, count).as_bytes(), s.as_bytes());
+        for index in 0..count {
+            let lossy = s.substring(index, index + 1).to_string_lossy().into_owned();
+            assert_eq!(lossy.chars().count(), 1);
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_unpaired_surrogates_windows() {
+        use std::ffi::OsString;
+        use std::os::windows::ffi::{OsStrExt, OsStringExt};
+        use std::vec::Vec;
+
+        // "f", an unpaired high surrogate, "õ", "😀", and an unpaired low surrogate.
+        let s = OsString::from_wide(&[0x66, 0xd800, 0xf5, 0xd83d, 0xde00, 0xdc00]);
+        let wide = |s: &OsStr| s.encode_wide().collect::<Vec<u16>>();
+        assert_eq!(wide(&s.substring(0, 2)), [0x66, 0xd800]);
+        assert_eq!(wide(&s.substring(1, 2)), [0xd800]);
+        assert_eq!(wide(&s.substring(2, 4)), [0xf5, 0xd83d, 0xde00]);
+        assert_eq!(wide(&s.substring(4, 100)), [0xdc00]);
+        assert_eq!(wide(&s.substring(3, 1)), Vec::<u16>::new());
+    }
+}
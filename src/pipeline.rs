@@ -0,0 +1,256 @@
+//! Lazily composed chains of slicing operations.
+
+use bounds;
+use core::fmt;
+use core::ops::RangeBounds;
+use unit::char_range_to_byte_range;
+
+/// The number of operations a [`Pipeline`] records before applying them.
+///
+/// [`Pipeline`]: struct.Pipeline.html
+const MAX_OPS: usize = 8;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Op {
+    Trim,
+    TrimStart,
+    TrimEnd,
+    /// A range of characters, as resolved start and end indices.
+    Chars(usize, Option<usize>),
+}
+
+impl Op {
+    fn apply(self, s: &str) -> &str {
+        match self {
+            Op::Trim => s.trim(),
+            Op::TrimStart => s.trim_left(),
+            Op::TrimEnd => s.trim_right(),
+            Op::Chars(start, end) => {
+                let bytes = char_range_to_byte_range(s, start, end);
+                // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `s`.
+                slice_unchecked!(s, bytes.start, bytes.end)
+            }
+        }
+    }
+}
+
+/// Returns the character range selecting `inner` from the characters selected by `outer`.
+fn compose_chars(outer: (usize, Option<usize>), inner: (usize, Option<usize>)) -> Op {
+    let (outer_start, outer_end) = outer;
+    let (inner_start, inner_end) = inner;
+    // A reversed outer range selects nothing, positioned at its start.
+    let outer_end = outer_end.map(|end| end.max(outer_start));
+
+    let start = outer_start.saturating_add(inner_start);
+    let end = inner_end.map(|end| outer_start.saturating_add(end));
+    match outer_end {
+        Some(outer_end) => Op::Chars(
+            start.min(outer_end),
+            Some(end.map_or(outer_end, |end| end.min(outer_end))),
+        ),
+        None => Op::Chars(start, end),
+    }
+}
+
+/// A chain of slicing operations on a string, applied only when the result is requested.
+///
+/// Operations are recorded rather than performed as they are chained, and consecutive character
+/// ranges are fused into a single range, so that a chain such as taking a range and then a range
+/// within it walks the string once. Every operation selects a contiguous part of its input, so the
+/// result is always a slice of the original string.
+///
+/// Example:
+/// ```
+/// use substring::Pipeline;
+///
+/// let s = "  fõøbα® and more  ";
+/// let pipeline = Pipeline::new(s).trim().chars(..10).chars(2..);
+/// assert_eq!(pipeline.as_str(), "øbα® and");
+/// ```
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Pipeline<'a> {
+    s: &'a str,
+    ops: [Op; MAX_OPS],
+    len: usize,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Creates a pipeline with no operations on `s`.
+    pub fn new(s: &'a str) -> Self {
+        Pipeline {
+            s,
+            ops: [Op::Trim; MAX_OPS],
+            len: 0,
+        }
+    }
+
+    /// Removes leading and trailing whitespace, as [`str::trim()`] does.
+    ///
+    /// [`str::trim()`]: https://doc.rust-lang.org/std/primitive.str.html#method.trim
+    pub fn trim(self) -> Self {
+        self.push(Op::Trim)
+    }
+
+    /// Removes leading whitespace.
+    pub fn trim_start(self) -> Self {
+        self.push(Op::TrimStart)
+    }
+
+    /// Removes trailing whitespace.
+    pub fn trim_end(self) -> Self {
+        self.push(Op::TrimEnd)
+    }
+
+    /// Keeps the characters within `range`, clamped to the end of the string.
+    pub fn chars<R: RangeBounds<usize>>(self, range: R) -> Self {
+        let resolved = bounds::resolve(&range);
+        self.push(Op::Chars(resolved.start, resolved.end))
+    }
+
+    /// Applies the recorded operations, returning the resulting slice of the original string.
+    #[must_use]
+    pub fn as_str(&self) -> &'a str {
+        self.ops[..self.len]
+            .iter()
+            .fold(self.s, |s, &op| op.apply(s))
+    }
+
+    /// Writes the result of the recorded operations to `w`.
+    pub fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        w.write_str(self.as_str())
+    }
+
+    fn push(mut self, op: Op) -> Self {
+        if let Some(last) = self.len.checked_sub(1) {
+            match (self.ops[last], op) {
+                (Op::Chars(start, end), Op::Chars(inner_start, inner_end)) => {
+                    self.ops[last] = compose_chars((start, end), (inner_start, inner_end));
+                    return self;
+                }
+                (last_op, _) if last_op == op => return self,
+                _ => {}
+            }
+        }
+        if self.len == MAX_OPS {
+            // Rather than allocating, apply the operations recorded so far and start afresh.
+            self = Pipeline::new(self.as_str());
+        }
+        self.ops[self.len] = op;
+        self.len += 1;
+        self
+    }
+}
+
+impl<'a> fmt::Display for Pipeline<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pipeline, MAX_OPS};
+    use Substring;
+
+    const CORPUS: [&str; 6] = [
+        "",
+        "foobar",
+        "  fõøbα®  ",
+        "\t日本語のテキスト\n",
+        " a\u{301}e\u{301} 😀😀 ",
+        "   ",
+    ];
+
+    const RANGES: [(usize, usize); 7] = [(0, 0), (0, 3), (1, 4), (2, 100), (3, 1), (5, 6), (9, 20)];
+
+    /// Returns the byte offset of `sub` within `s`, asserting that `sub` lies within `s`.
+    fn offset_within(s: &str, sub: &str) -> usize {
+        let start = s.as_ptr() as usize;
+        let sub_start = sub.as_ptr() as usize;
+        assert!(sub_start >= start && sub_start + sub.len() <= start + s.len());
+        sub_start - start
+    }
+
+    #[test]
+    fn test_no_ops() {
+        assert_eq!(Pipeline::new("  foo ").as_str(), "  foo ");
+    }
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(Pipeline::new("  foo ").trim().as_str(), "foo");
+        assert_eq!(Pipeline::new("  foo ").trim_start().as_str(), "foo ");
+        assert_eq!(Pipeline::new("  foo ").trim_end().as_str(), "  foo");
+    }
+
+    #[test]
+    fn test_chars_then_trim_matches_sequential() {
+        for s in CORPUS.iter() {
+            for &(start, end) in RANGES.iter() {
+                let pipeline = Pipeline::new(s).chars(start..end).trim();
+                assert_eq!(pipeline.as_str(), s.substring(start, end).trim());
+            }
+        }
+    }
+
+    #[test]
+    fn test_fused_chars_match_sequential() {
+        for s in CORPUS.iter() {
+            for &(outer_start, outer_end) in RANGES.iter() {
+                for &(inner_start, inner_end) in RANGES.iter() {
+                    let pipeline = Pipeline::new(s)
+                        .trim()
+                        .chars(outer_start..outer_end)
+                        .chars(inner_start..inner_end);
+                    let sequential = s
+                        .trim()
+                        .substring(outer_start, outer_end)
+                        .substring(inner_start, inner_end);
+                    assert_eq!(pipeline.as_str(), sequential);
+                    assert_eq!(
+                        offset_within(s, pipeline.as_str()),
+                        offset_within(s, sequential)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fused_unbounded_chars() {
+        let s = "fõøbα®";
+        assert_eq!(Pipeline::new(s).chars(1..).chars(..2).as_str(), "õø");
+        assert_eq!(Pipeline::new(s).chars(..4).chars(2..).as_str(), "øb");
+        assert_eq!(Pipeline::new(s).chars(2..).chars(1..).as_str(), "bα®");
+    }
+
+    #[test]
+    fn test_as_str_borrows_original() {
+        let s = "  fõøbα®  ";
+        let result = Pipeline::new(s).trim().chars(1..4).as_str();
+        assert_eq!(result, "õøb");
+        assert_eq!(offset_within(s, result), 3);
+    }
+
+    #[test]
+    fn test_many_ops() {
+        let s = " a b c d e f g h i j k l m n o p ";
+        let mut pipeline = Pipeline::new(s);
+        let mut sequential = s;
+        for _ in 0..(MAX_OPS * 2) {
+            pipeline = pipeline.trim().chars(1..);
+            sequential = sequential.trim().substring(1, usize::max_value());
+        }
+        assert_eq!(pipeline.as_str(), sequential);
+    }
+
+    #[test]
+    fn test_display_and_write_to() {
+        let pipeline = Pipeline::new(" fõøbα® ").trim().chars(1..3);
+        assert_eq!(format!("{}", pipeline), "õø");
+
+        let mut written = ::std::string::String::new();
+        pipeline.write_to(&mut written).unwrap();
+        assert_eq!(written, "õø");
+    }
+}
@@ -0,0 +1,405 @@
+//! Rendering of values into fixed-layout records.
+
+use error::RenderError;
+use span::Span;
+use unit::{Measure, Unit};
+use width;
+
+/// The alignment of a value within its slot.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Align {
+    /// Place the value at the start of the slot, padding after it.
+    Left,
+    /// Place the value at the end of the slot, padding before it.
+    Right,
+    /// Place the value in the middle of the slot, padding on both sides.
+    ///
+    /// If the padding cannot be split evenly, the extra fill character goes after the value.
+    Center,
+}
+
+/// The slot of a value within a record rendered by [`render_fields()`].
+///
+/// A field's [`Span`] gives the position of its slot within the record, measured in the field's
+/// [`Unit`]. By default, values are aligned to the left and padded with spaces.
+///
+/// Example:
+/// ```
+/// use substring::{Align, Field, Span, Unit};
+///
+/// let field = Field::new(Span::new(0, 8).unwrap(), Unit::Bytes)
+///     .align(Align::Right)
+///     .fill('0');
+/// ```
+///
+/// [`render_fields()`]: fn.render_fields.html
+/// [`Span`]: struct.Span.html
+/// [`Unit`]: enum.Unit.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Field {
+    span: Span,
+    unit: Unit,
+    align: Align,
+    fill: char,
+}
+
+impl Field {
+    /// Creates a field whose slot is `span`, measured in `unit`.
+    pub fn new(span: Span, unit: Unit) -> Self {
+        Field {
+            span,
+            unit,
+            align: Align::Left,
+            fill: ' ',
+        }
+    }
+
+    /// Aligns values within the slot according to `align`.
+    pub fn align(self, align: Align) -> Self {
+        Field { align, ..self }
+    }
+
+    /// Pads values shorter than the slot with `fill`.
+    ///
+    /// If `fill` is encoded in more than one unit and the padding is not a multiple of its
+    /// length, the remainder is padded with spaces. A `fill` of no length, such as a zero width
+    /// character measured in [`Unit::Width`], is replaced with spaces entirely.
+    ///
+    /// [`Unit::Width`]: enum.Unit.html#variant.Width
+    pub fn fill(self, fill: char) -> Self {
+        Field { fill, ..self }
+    }
+}
+
+/// The position reached within a record, in every unit.
+#[derive(Clone, Copy, Default)]
+struct Cursor {
+    bytes: usize,
+    chars: usize,
+    utf16: usize,
+    columns: usize,
+}
+
+impl Cursor {
+    /// Returns the position in `unit`, or `None` if positions are not tracked in `unit`.
+    fn get(&self, unit: Unit) -> Option<usize> {
+        match unit {
+            Unit::Bytes => Some(self.bytes),
+            Unit::Chars => Some(self.chars),
+            Unit::Utf16 => Some(self.utf16),
+            Unit::Width => Some(self.columns),
+            Unit::Words => None,
+        }
+    }
+}
+
+/// Writes encoded characters into a buffer, tracking the position reached.
+struct Writer<'o> {
+    out: &'o mut [u8],
+    cursor: Cursor,
+    field: usize,
+}
+
+impl<'o> Writer<'o> {
+    fn push(&mut self, c: char) -> Result<(), RenderError> {
+        let start = self.cursor.bytes;
+        let end = start + c.len_utf8();
+        if end > self.out.len() {
+            return Err(RenderError::BufferTooSmall {
+                field: self.field,
+                buffer_len: self.out.len(),
+            });
+        }
+        c.encode_utf8(&mut self.out[start..end]);
+        self.cursor.bytes = end;
+        self.cursor.chars += 1;
+        self.cursor.utf16 += c.len_utf16();
+        self.cursor.columns += width::char_width(c);
+        Ok(())
+    }
+
+    fn push_repeated(&mut self, c: char, count: usize) -> Result<(), RenderError> {
+        for _ in 0..count {
+            self.push(c)?;
+        }
+        Ok(())
+    }
+
+    fn push_str(&mut self, s: &str) -> Result<(), RenderError> {
+        for c in s.chars() {
+            self.push(c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders each of `values` into the slot of the corresponding field within `out`, returning the
+/// rendered record.
+///
+/// Fields must be given in the order their slots appear in the record. Any gap before a slot is
+/// padded with spaces. A value longer than its slot is truncated, and one shorter than its slot is
+/// padded according to the field's alignment and fill character. Characters are never split, so
+/// that the record is always valid UTF-8. The record ends at the end of the last slot; any bytes of
+/// `out` after it are left unchanged.
+///
+/// Returns an error if the number of values differs from the number of fields, if a field is
+/// measured in [`Unit::Words`], if a slot starts before the end of the preceding slot, or if the
+/// record does not fit within `out`.
+///
+/// Example:
+/// ```
+/// use substring::{render_fields, Align, Field, Span, Unit};
+///
+/// let fields = [
+///     Field::new(Span::new(0, 6).unwrap(), Unit::Chars),
+///     Field::new(Span::new(7, 11).unwrap(), Unit::Chars)
+///         .align(Align::Right)
+///         .fill('0'),
+/// ];
+/// let mut out = [0; 16];
+/// assert_eq!(
+///     render_fields(&fields, &["Jürgen", "42"], &mut out),
+///     Ok("Jürgen 0042")
+/// );
+/// ```
+///
+/// [`Unit::Words`]: enum.Unit.html#variant.Words
+pub fn render_fields<'o>(
+    fields: &[Field],
+    values: &[&str],
+    out: &'o mut [u8],
+) -> Result<&'o str, RenderError> {
+    if fields.len() != values.len() {
+        return Err(RenderError::ValueCount {
+            fields: fields.len(),
+            values: values.len(),
+        });
+    }
+
+    let mut writer = Writer {
+        out,
+        cursor: Cursor::default(),
+        field: 0,
+    };
+    for (index, (field, value)) in fields.iter().zip(values).enumerate() {
+        writer.field = index;
+        let unit = field.unit;
+        let (position, len_of) = match (writer.cursor.get(unit), unit.measure()) {
+            (Some(position), Measure::PerChar(len_of)) => (position, len_of),
+            _ => return Err(RenderError::UnsupportedUnit { field: index }),
+        };
+        if field.span.start() < position {
+            return Err(RenderError::Overlapping {
+                first: index - 1,
+                second: index,
+            });
+        }
+        writer.push_repeated(' ', field.span.start() - position)?;
+
+        // Truncate the value to the longest prefix fitting within the slot.
+        let width = field.span.len();
+        let mut value_width = 0;
+        let mut value_end = value.len();
+        for (byte_idx, c) in value.char_indices() {
+            if value_width + len_of(c) > width {
+                value_end = byte_idx;
+                break;
+            }
+            value_width += len_of(c);
+        }
+        let value = &value[..value_end];
+
+        let padding = width - value_width;
+        let (fills, spaces) = match len_of(field.fill) {
+            0 => (0, padding),
+            fill_width => (padding / fill_width, padding % fill_width),
+        };
+        match field.align {
+            Align::Left => {
+                writer.push_str(value)?;
+                writer.push_repeated(field.fill, fills)?;
+                writer.push_repeated(' ', spaces)?;
+            }
+            Align::Right => {
+                writer.push_repeated(' ', spaces)?;
+                writer.push_repeated(field.fill, fills)?;
+                writer.push_str(value)?;
+            }
+            Align::Center => {
+                writer.push_repeated(field.fill, fills / 2)?;
+                writer.push_str(value)?;
+                writer.push_repeated(field.fill, fills - fills / 2)?;
+                writer.push_repeated(' ', spaces)?;
+            }
+        }
+    }
+
+    let len = writer.cursor.bytes;
+    let out = writer.out;
+    // SAFETY: Only the UTF-8 encodings of whole characters were written to `out[..len]`.
+    Ok(from_utf8_unchecked!(&out[..len]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_fields, Align, Field};
+    use error::RenderError;
+    use span::Span;
+    use unit::Unit;
+
+    fn field(start: usize, end: usize, unit: Unit) -> Field {
+        Field::new(Span::new(start, end).unwrap(), unit)
+    }
+
+    #[test]
+    fn test_adjacent_fields() {
+        let fields = [
+            field(0, 3, Unit::Chars),
+            field(3, 6, Unit::Chars).align(Align::Right),
+            field(6, 10, Unit::Chars).align(Align::Center).fill('*'),
+        ];
+        let mut out = [0; 32];
+        assert_eq!(
+            render_fields(&fields, &["ab", "c", "d"], &mut out),
+            Ok("ab   c*d**")
+        );
+    }
+
+    #[test]
+    fn test_gap_between_fields() {
+        let fields = [field(0, 2, Unit::Bytes), field(4, 6, Unit::Bytes)];
+        let mut out = [0; 8];
+        assert_eq!(
+            render_fields(&fields, &["ab", "cd"], &mut out),
+            Ok("ab  cd")
+        );
+    }
+
+    #[test]
+    fn test_truncated_at_slot_edge() {
+        // "ø" is two bytes, so only "fõ" fits within four bytes and the slot is padded.
+        let fields = [field(0, 4, Unit::Bytes).fill('.'), field(4, 5, Unit::Bytes)];
+        let mut out = [0; 8];
+        assert_eq!(
+            render_fields(&fields, &["fõøbα®", "x"], &mut out),
+            Ok("fõ.x")
+        );
+    }
+
+    #[test]
+    fn test_truncated_in_chars() {
+        let fields = [field(0, 3, Unit::Chars)];
+        let mut out = [0; 8];
+        assert_eq!(render_fields(&fields, &["fõøbα®"], &mut out), Ok("fõø"));
+    }
+
+    #[test]
+    fn test_utf16_surrogate_pair_not_split() {
+        let fields = [field(0, 3, Unit::Utf16)];
+        let mut out = [0; 16];
+        assert_eq!(render_fields(&fields, &["a😀😀"], &mut out), Ok("a😀"));
+    }
+
+    #[test]
+    fn test_truncated_in_columns() {
+        // "日" occupies two columns, so only "a" fits before it, along with its combining mark.
+        let fields = [field(0, 2, Unit::Width).fill('.'), field(2, 3, Unit::Width)];
+        let mut out = [0; 16];
+        assert_eq!(
+            render_fields(&fields, &["a\u{301}日", "本x"], &mut out),
+            Ok("a\u{301}. ")
+        );
+    }
+
+    #[test]
+    fn test_wide_fill_in_columns() {
+        let fields = [field(0, 6, Unit::Width).align(Align::Right).fill('日')];
+        let mut out = [0; 16];
+        assert_eq!(
+            render_fields(&fields, &["a\u{301}"], &mut out),
+            Ok(" 日日a\u{301}")
+        );
+    }
+
+    #[test]
+    fn test_zero_width_fill() {
+        let fields = [field(0, 3, Unit::Width).fill('\u{200b}')];
+        let mut out = [0; 8];
+        assert_eq!(render_fields(&fields, &["a"], &mut out), Ok("a  "));
+    }
+
+    #[test]
+    fn test_wide_fill_remainder() {
+        let fields = [field(0, 5, Unit::Bytes).fill('õ')];
+        let mut out = [0; 8];
+        assert_eq!(render_fields(&fields, &["ab"], &mut out), Ok("abõ "));
+    }
+
+    #[test]
+    fn test_words_unsupported() {
+        let fields = [field(0, 2, Unit::Chars), field(3, 4, Unit::Words)];
+        let mut out = [0; 16];
+        assert_eq!(
+            render_fields(&fields, &["ab", "cd"], &mut out),
+            Err(RenderError::UnsupportedUnit { field: 1 })
+        );
+    }
+
+    #[test]
+    fn test_overlapping_fields() {
+        let fields = [field(0, 4, Unit::Chars), field(3, 6, Unit::Chars)];
+        let mut out = [0; 16];
+        assert_eq!(
+            render_fields(&fields, &["a", "b"], &mut out),
+            Err(RenderError::Overlapping {
+                first: 0,
+                second: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_buffer_exactly_right_size() {
+        let fields = [field(0, 3, Unit::Chars), field(3, 5, Unit::Chars)];
+        // "fõø" is five bytes and "ab" is two.
+        let mut out = [0; 7];
+        assert_eq!(
+            render_fields(&fields, &["fõø", "ab"], &mut out),
+            Ok("fõøab")
+        );
+    }
+
+    #[test]
+    fn test_buffer_too_small() {
+        let fields = [field(0, 3, Unit::Chars), field(3, 5, Unit::Chars)];
+        let mut out = [0; 6];
+        assert_eq!(
+            render_fields(&fields, &["fõø", "ab"], &mut out),
+            Err(RenderError::BufferTooSmall {
+                field: 1,
+                buffer_len: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_value_count() {
+        let fields = [field(0, 3, Unit::Chars)];
+        let mut out = [0; 8];
+        assert_eq!(
+            render_fields(&fields, &[], &mut out),
+            Err(RenderError::ValueCount {
+                fields: 1,
+                values: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_leaves_rest_of_buffer() {
+        let fields = [field(0, 2, Unit::Chars)];
+        let mut out = [b'#'; 4];
+        assert_eq!(render_fields(&fields, &["a"], &mut out), Ok("a "));
+        assert_eq!(&out[2..], b"##");
+    }
+}
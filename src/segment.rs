@@ -0,0 +1,163 @@
+//! Substrings indexed by user-defined segments.
+
+use core::ops::Range;
+
+/// A way of dividing a string into segments, such as characters, words, or tokens.
+///
+/// A segmenter finds segments one at a time, from a byte index onward. Segments are non-empty,
+/// need not be adjacent, and are found in order, so any text between two segments belongs to
+/// neither. Implementing this trait gives access to [`substring_by()`], which handles the
+/// resolving and clamping of ranges of segment indices.
+///
+/// The trait is implemented for the [`ByChars`], [`ByWords`], and [`BySentences`] segmenters, and
+/// for closures of the same signature as [`next_segment()`].
+///
+/// Example:
+/// ```
+/// use std::ops::Range;
+/// use substring::{CharSubstring, Segmenter};
+///
+/// /// Divides a string into the parts separated by '-'.
+/// struct Dashes;
+///
+/// impl Segmenter for Dashes {
+///     fn next_segment(&self, s: &str, from: usize) -> Option<Range<usize>> {
+///         let rest = s.get(from..)?;
+///         let start = from + rest.find(|c| c != '-')?;
+///         let end = s[start..].find('-').map_or(s.len(), |offset| start + offset);
+///         Some(start..end)
+///     }
+/// }
+///
+/// assert_eq!("ab-cd--ef-g".substring_by(Dashes, 1..3), "cd--ef");
+/// assert_eq!("ab-cd--ef-g".substring_by(Dashes, 3..), "g");
+/// ```
+///
+/// [`ByChars`]: struct.ByChars.html
+/// [`BySentences`]: struct.BySentences.html
+/// [`ByWords`]: struct.ByWords.html
+/// [`next_segment()`]: #tymethod.next_segment
+/// [`substring_by()`]: trait.CharSubstring.html#tymethod.substring_by
+pub trait Segmenter {
+    /// Returns the byte range of the first segment of `s` starting at or after the byte index
+    /// `from`, or `None` if there is none.
+    ///
+    /// The returned range must be non-empty, must start no earlier than `from`, and must lie on
+    /// character boundaries within `s`. If `from` is not a character boundary of `s`,
+    /// implementations within this crate return `None`.
+    fn next_segment(&self, s: &str, from: usize) -> Option<Range<usize>>;
+}
+
+impl<F: Fn(&str, usize) -> Option<Range<usize>>> Segmenter for F {
+    fn next_segment(&self, s: &str, from: usize) -> Option<Range<usize>> {
+        self(s, from)
+    }
+}
+
+/// A [`Segmenter`] dividing a string into its characters.
+///
+/// [`Segmenter`]: trait.Segmenter.html
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ByChars;
+
+impl Segmenter for ByChars {
+    fn next_segment(&self, s: &str, from: usize) -> Option<Range<usize>> {
+        let c = s.get(from..)?.chars().next()?;
+        Some(from..(from + c.len_utf8()))
+    }
+}
+
+/// An iterator over the segments found by a [`Segmenter`].
+///
+/// # Panics
+/// Panics if the segmenter returns a segment which does not satisfy the requirements of
+/// [`Segmenter::next_segment()`].
+///
+/// [`Segmenter`]: trait.Segmenter.html
+/// [`Segmenter::next_segment()`]: trait.Segmenter.html#tymethod.next_segment
+pub(crate) struct Segments<'a, S> {
+    s: &'a str,
+    segmenter: S,
+    /// The byte index from which to search for the next segment.
+    byte: usize,
+}
+
+impl<'a, S: Segmenter> Segments<'a, S> {
+    pub(crate) fn new(s: &'a str, segmenter: S) -> Self {
+        Segments {
+            s,
+            segmenter,
+            byte: 0,
+        }
+    }
+}
+
+impl<'a, S: Segmenter> Iterator for Segments<'a, S> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        let segment = self.segmenter.next_segment(self.s, self.byte)?;
+        assert!(
+            self.byte <= segment.start
+                && segment.start < segment.end
+                && self.s.is_char_boundary(segment.start)
+                && self.s.is_char_boundary(segment.end),
+            "segment {:?} is not a non-empty range of character boundaries from byte {}",
+            segment,
+            self.byte
+        );
+        self.byte = segment.end;
+        Some(segment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ByChars, Segmenter, Segments};
+    use core::ops::Range;
+    use std::vec::Vec;
+
+    fn segments<S: Segmenter>(s: &str, segmenter: S) -> Vec<&str> {
+        Segments::new(s, segmenter).map(|range| &s[range]).collect()
+    }
+
+    #[test]
+    fn test_by_chars() {
+        assert_eq!(segments("fõø", ByChars), ["f", "õ", "ø"]);
+        assert_eq!(segments("", ByChars), Vec::<&str>::new());
+        assert_eq!(ByChars.next_segment("fõø", 2), None);
+        assert_eq!(ByChars.next_segment("fõø", 10), None);
+    }
+
+    #[test]
+    fn test_closure() {
+        let pairs = |s: &str, from: usize| -> Option<Range<usize>> {
+            if from < s.len() {
+                Some(from..(from + 2).min(s.len()))
+            } else {
+                None
+            }
+        };
+        assert_eq!(segments("abcde", pairs), ["ab", "cd", "e"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "segment 0..0 is not a non-empty range of character boundaries")]
+    fn test_empty_segment() {
+        let _ = segments("abc", |_: &str, from: usize| Some(from..from));
+    }
+
+    #[test]
+    #[should_panic(expected = "segment 0..2 is not a non-empty range of character boundaries")]
+    fn test_segment_inside_char() {
+        let _ = segments("õ", |_: &str, _: usize| Some(0..2));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "segment 0..1 is not a non-empty range of character boundaries from byte 1"
+    )]
+    fn test_segment_before_from() {
+        let _ = segments("abc", |_: &str, _: usize| Some(0..1));
+    }
+}
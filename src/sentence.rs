@@ -0,0 +1,241 @@
+//! Substrings indexed by sentences.
+//!
+//! Sentences follow a simplified subset of [UAX #29]. A sentence ends after a run of `.`, `!`, or
+//! `?`, along with any closing quotation marks and brackets, when it is followed by whitespace or
+//! the end of the string. A full stop followed by whitespace and then a lowercase letter does not
+//! end a sentence, since it usually ends an abbreviation, as in "e.g. this". A line break also ends
+//! a sentence. Whitespace between sentences belongs to neither of them.
+//!
+//! As with UAX #29, an abbreviation followed by a capitalized word, such as "Dr. Smith", is
+//! indistinguishable from the end of a sentence, and is treated as one.
+//!
+//! [UAX #29]: https://www.unicode.org/reports/tr29/
+
+use core::ops::{Range, RangeBounds};
+use segment::Segmenter;
+use word;
+
+fn is_terminator(c: char) -> bool {
+    c == '.' || c == '!' || c == '?'
+}
+
+fn is_close(c: char) -> bool {
+    match c {
+        '"' | '\'' | ')' | ']' | '}' | '’' | '”' | '»' => true,
+        _ => false,
+    }
+}
+
+fn is_line_break(c: char) -> bool {
+    c == '\n' || c == '\r' || c == '\u{85}' || c == '\u{2028}' || c == '\u{2029}'
+}
+
+/// An iterator over the byte ranges of the sentences of a string.
+struct Sentences<'a> {
+    s: &'a str,
+    /// The byte index from which to search for the next sentence.
+    byte: usize,
+}
+
+impl<'a> Sentences<'a> {
+    fn new(s: &'a str) -> Self {
+        Sentences { s, byte: 0 }
+    }
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        // SAFETY: `self.byte` always lies on a UTF-8 sequence boundary within `self.s`.
+        let rest = slice_unchecked!(self.s, self.byte, self.s.len());
+        let (offset, _) = rest.char_indices().find(|&(_, c)| !c.is_whitespace())?;
+        let start = self.byte + offset;
+
+        // The end of the sentence so far, excluding any trailing whitespace.
+        let mut end = start;
+        // The last terminator in the run of terminators and closers ending at `end`, if any.
+        let mut terminator = None;
+        // Whether whitespace has been passed since `end`.
+        let mut spaced = false;
+        // SAFETY: `start` lies on a UTF-8 sequence boundary within `self.s`.
+        let chars = slice_unchecked!(self.s, start, self.s.len()).char_indices();
+        for (index, c) in chars {
+            if is_line_break(c) {
+                break;
+            }
+            if c.is_whitespace() {
+                spaced = true;
+                continue;
+            }
+            match terminator {
+                Some('.') if spaced && c.is_lowercase() => terminator = None,
+                Some(_) if spaced => break,
+                Some(_) if is_terminator(c) || is_close(c) => {}
+                _ => terminator = None,
+            }
+            if is_terminator(c) {
+                terminator = Some(c);
+            }
+            spaced = false;
+            end = start + index + c.len_utf8();
+        }
+
+        self.byte = end;
+        Some(start..end)
+    }
+}
+
+/// Provides a [`sentence_substring()`] method indexed by sentences.
+///
+/// See the [module documentation] for how sentences are identified.
+///
+/// [module documentation]: index.html
+/// [`sentence_substring()`]: trait.SentenceSubstring.html#tymethod.sentence_substring
+pub trait SentenceSubstring {
+    /// Obtains a string slice spanning the sentences within `range`.
+    ///
+    /// The slice extends from the start of the first sentence to the end of the last sentence,
+    /// including any whitespace between them, but no whitespace before the first sentence or after
+    /// the last. The range is clamped to the number of sentences in the string, and a range whose
+    /// end is not after its start results in an empty string, positioned at the start of the
+    /// sentence it starts at.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::SentenceSubstring;
+    ///
+    /// let s = "One sentence. Then another! And a third?";
+    /// assert_eq!(s.sentence_substring(..2), "One sentence. Then another!");
+    /// assert_eq!(s.sentence_substring(2..), "And a third?");
+    /// ```
+    #[must_use]
+    fn sentence_substring<R: RangeBounds<usize>>(&self, range: R) -> &str;
+}
+
+impl SentenceSubstring for str {
+    fn sentence_substring<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        word::segment_substring(self, Sentences::new(self), &range)
+    }
+}
+
+/// A [`Segmenter`] dividing a string into its sentences.
+///
+/// See the [module documentation] for how sentences are identified.
+///
+/// Example:
+/// ```
+/// use substring::{BySentences, CharSubstring};
+///
+/// assert_eq!("One. Two! Three?".substring_by(BySentences, 1..), "Two! Three?");
+/// ```
+///
+/// [module documentation]: index.html
+/// [`Segmenter`]: trait.Segmenter.html
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BySentences;
+
+impl Segmenter for BySentences {
+    fn next_segment(&self, s: &str, from: usize) -> Option<Range<usize>> {
+        if !s.is_char_boundary(from) {
+            return None;
+        }
+        Sentences { s, byte: from }.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BySentences, Segmenter, SentenceSubstring, Sentences};
+    use std::vec::Vec;
+
+    fn sentences(s: &str) -> Vec<&str> {
+        Sentences::new(s).map(|range| &s[range]).collect()
+    }
+
+    #[test]
+    fn test_sentences() {
+        assert_eq!(
+            sentences("One. Two!  Three?\tFour"),
+            ["One.", "Two!", "Three?", "Four"]
+        );
+        assert_eq!(sentences(""), Vec::<&str>::new());
+        assert_eq!(sentences(" \t "), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_sentences_abbreviations() {
+        assert_eq!(
+            sentences("Dr. Smith went home. Then he left."),
+            ["Dr.", "Smith went home.", "Then he left."]
+        );
+        assert_eq!(
+            sentences("Bring fruit, e.g. apples. Or not."),
+            ["Bring fruit, e.g. apples.", "Or not."]
+        );
+    }
+
+    #[test]
+    fn test_sentences_terminators_within_words() {
+        assert_eq!(sentences("Pi is 3.14. It is."), ["Pi is 3.14.", "It is."]);
+        assert_eq!(sentences("Really?! Yes..."), ["Really?!", "Yes..."]);
+    }
+
+    #[test]
+    fn test_sentences_closers() {
+        assert_eq!(
+            sentences("He said \"stop.\" (It worked.) Good."),
+            ["He said \"stop.\"", "(It worked.)", "Good."]
+        );
+    }
+
+    #[test]
+    fn test_sentences_line_breaks() {
+        assert_eq!(
+            sentences("A heading\nSome text. More\r\n"),
+            ["A heading", "Some text.", "More"]
+        );
+    }
+
+    #[test]
+    fn test_sentence_substring() {
+        let s = "Dr. Smith went home. Then he left.";
+        assert_eq!(s.sentence_substring(1..), "Smith went home. Then he left.");
+        assert_eq!(s.sentence_substring(..2), "Dr. Smith went home.");
+        assert_eq!(s.sentence_substring(2..=2), "Then he left.");
+        assert_eq!(
+            s.sentence_substring(1..10),
+            "Smith went home. Then he left."
+        );
+    }
+
+    #[test]
+    fn test_sentence_substring_no_terminal_punctuation() {
+        let s = "  no punctuation here  ";
+        assert_eq!(s.sentence_substring(..), "no punctuation here");
+        assert_eq!(s.sentence_substring(..1), "no punctuation here");
+        assert_eq!(s.sentence_substring(1..), "");
+        assert_eq!("First. second".sentence_substring(..), "First. second");
+        assert_eq!("First. Second".sentence_substring(1..), "Second");
+    }
+
+    #[test]
+    fn test_sentence_substring_empty() {
+        let s = "One. Two. Three.";
+        let (start, end) = (2, 1);
+        assert_eq!(s.sentence_substring(start..end), "");
+        assert_eq!(s.sentence_substring(start..end).as_ptr(), s[10..].as_ptr());
+        assert_eq!(s.sentence_substring(3..), "");
+        assert_eq!(s.sentence_substring(3..).as_ptr(), s[16..].as_ptr());
+        assert_eq!("".sentence_substring(..), "");
+    }
+
+    #[test]
+    fn test_by_sentences() {
+        let s = "Fõø. Bαr!";
+        assert_eq!(BySentences.next_segment(s, 0), Some(0..6));
+        assert_eq!(BySentences.next_segment(s, 6), Some(7..12));
+        assert_eq!(BySentences.next_segment(s, 12), None);
+        assert_eq!(BySentences.next_segment(s, 2), None);
+    }
+}
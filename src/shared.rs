@@ -0,0 +1,201 @@
+//! Substrings sharing ownership of their source string.
+
+use alloc::sync::Arc;
+use bounds;
+use core::fmt;
+use core::ops::{Deref, Range, RangeBounds};
+use unit::char_range_to_byte_range;
+
+/// A substring holding shared ownership of the string it was sliced from.
+///
+/// The source is held by a reference-counted pointer, [`Arc<str>`] by default or
+/// [`Rc<str>`] when thread safety is not needed, so a `SharedSubstring` has no lifetime and
+/// cloning it only increments the reference count. It dereferences to the characters within its
+/// range.
+///
+/// Example:
+/// ```
+/// use std::sync::Arc;
+/// use substring::SharedSubstring;
+///
+/// let source: Arc<str> = Arc::from("fõøbα®");
+/// let shared = SharedSubstring::new(source, 1..5);
+/// assert_eq!(shared, "õøbα");
+///
+/// let sliced = shared.slice(2..);
+/// assert_eq!(sliced, "bα");
+/// assert_eq!(sliced.byte_range(), 5..8);
+/// ```
+///
+/// [`Arc<str>`]: https://doc.rust-lang.org/std/sync/struct.Arc.html
+/// [`Rc<str>`]: https://doc.rust-lang.org/std/rc/struct.Rc.html
+pub struct SharedSubstring<P = Arc<str>> {
+    source: P,
+    bytes: Range<usize>,
+}
+
+impl<P: Deref<Target = str>> SharedSubstring<P> {
+    /// Creates a substring of the characters of `source` within `range`.
+    ///
+    /// The range is clamped to the bounds of `source` in the same way as [`substring()`].
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    pub fn new<R: RangeBounds<usize>>(source: P, range: R) -> Self {
+        let resolved = bounds::resolve(&range);
+        let bytes = char_range_to_byte_range(&source, resolved.start, resolved.end);
+        SharedSubstring { source, bytes }
+    }
+
+    /// Returns the characters within the substring.
+    pub fn as_str(&self) -> &str {
+        &self.source[self.bytes.clone()]
+    }
+
+    /// Returns the string the substring was sliced from.
+    pub fn source(&self) -> &P {
+        &self.source
+    }
+
+    /// Returns the byte range of the substring within its source.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.bytes.clone()
+    }
+}
+
+impl<P: Clone + Deref<Target = str>> SharedSubstring<P> {
+    /// Returns a substring of the characters within `range` of this substring, sharing the same
+    /// source.
+    ///
+    /// The range is relative to this substring, and is clamped to its bounds in the same way as
+    /// [`substring()`].
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let resolved = bounds::resolve(&range);
+        let bytes = char_range_to_byte_range(self.as_str(), resolved.start, resolved.end);
+        SharedSubstring {
+            source: self.source.clone(),
+            bytes: (self.bytes.start + bytes.start)..(self.bytes.start + bytes.end),
+        }
+    }
+}
+
+impl<P: Clone> Clone for SharedSubstring<P> {
+    fn clone(&self) -> Self {
+        SharedSubstring {
+            source: self.source.clone(),
+            bytes: self.bytes.clone(),
+        }
+    }
+}
+
+impl<P: Deref<Target = str>> Deref for SharedSubstring<P> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<P: Deref<Target = str>> AsRef<str> for SharedSubstring<P> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<P: Deref<Target = str>> fmt::Debug for SharedSubstring<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<P: Deref<Target = str>> fmt::Display for SharedSubstring<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<P: Deref<Target = str>, Q: Deref<Target = str>> PartialEq<SharedSubstring<Q>>
+    for SharedSubstring<P>
+{
+    fn eq(&self, other: &SharedSubstring<Q>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<P: Deref<Target = str>> Eq for SharedSubstring<P> {}
+
+impl<P: Deref<Target = str>> PartialEq<str> for SharedSubstring<P> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a, P: Deref<Target = str>> PartialEq<&'a str> for SharedSubstring<P> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedSubstring;
+    use alloc::rc::Rc;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn test_new() {
+        let source: Arc<str> = Arc::from("fõøbα®");
+        assert_eq!(SharedSubstring::new(source.clone(), 2..5), "øbα");
+        assert_eq!(SharedSubstring::new(source.clone(), ..), *source);
+        assert_eq!(SharedSubstring::new(source.clone(), 4..100), "α®");
+        let (start, end) = (4, 2);
+        let empty = SharedSubstring::new(source, start..end);
+        assert_eq!(empty, "");
+        assert_eq!(empty.byte_range(), 6..6);
+    }
+
+    #[test]
+    fn test_clone_shares_source() {
+        let source: Arc<str> = Arc::from("foobar");
+        let shared = SharedSubstring::new(source.clone(), 1..4);
+        let cloned = shared.clone();
+        assert_eq!(Arc::strong_count(&source), 3);
+        assert_eq!(cloned.as_ptr(), shared.as_ptr());
+    }
+
+    #[test]
+    fn test_slice() {
+        let source: Arc<str> = Arc::from("😀a𝔘bõ");
+        let shared = SharedSubstring::new(source.clone(), 1..);
+        let sliced = shared.slice(1..3);
+        assert_eq!(sliced, "𝔘b");
+        assert_eq!(sliced.byte_range(), 5..10);
+        assert_eq!(sliced.as_ptr(), source[5..].as_ptr());
+        assert_eq!(shared.slice(2..100), "bõ");
+        assert_eq!(shared.slice(4..), "");
+        assert_eq!(shared.slice(4..).byte_range(), 12..12);
+        assert!(Arc::ptr_eq(sliced.source(), &source));
+    }
+
+    #[test]
+    fn test_rc() {
+        let source: Rc<str> = Rc::from("fõøbα®");
+        let shared = SharedSubstring::new(source.clone(), 1..3);
+        assert_eq!(shared, "õø");
+        assert_eq!(shared.slice(1..), "ø");
+        assert_eq!(Rc::strong_count(&source), 2);
+    }
+
+    #[test]
+    fn test_traits() {
+        let shared = SharedSubstring::new(Arc::<str>::from("fõøbα®"), 1..3);
+        assert_eq!(shared.len(), 4);
+        assert_eq!(AsRef::<str>::as_ref(&shared), "õø");
+        assert_eq!(format!("{}", shared), "õø");
+        assert_eq!(format!("{:?}", shared), "\"õø\"");
+        assert_eq!(shared, SharedSubstring::new(Rc::<str>::from("õø!"), ..2));
+        assert!(shared != SharedSubstring::new(Arc::<str>::from("õø!"), ..));
+    }
+}
@@ -0,0 +1,291 @@
+//! Rendering of spans within source text.
+
+use core::fmt;
+use debug::TruncatedText;
+use span::Span;
+use unit::char_range_to_byte_range;
+
+/// A [`Span`] within a source text, with an optional label.
+///
+/// The `Display` implementation renders the lines covered by the span, underlining the span with
+/// carets and following the underline with the label:
+///
+/// ```
+/// use substring::{SourceSpan, Span};
+///
+/// let source = "let x = fõo;\nlet y = x;";
+/// let span = SourceSpan::new(source, Span::new(8, 11).unwrap()).with_label("not found");
+///
+/// assert_eq!(span.text(), "fõo");
+/// assert_eq!(span.line_col(), ((1, 9), (1, 12)));
+/// assert_eq!(
+///     span.to_string(),
+///     "1 | let x = fõo;\n  |         ^^^ not found"
+/// );
+/// ```
+///
+/// Each line covered by a multi-line span is rendered with its own underline, and the label
+/// follows the underline of the last line. An empty span is underlined with a single caret at its
+/// position. Spans extending past the end of the source are clamped to it.
+///
+/// [`Span`]: struct.Span.html
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct SourceSpan<'a> {
+    source: &'a str,
+    span: Span,
+    label: Option<&'a str>,
+}
+
+/// A line and column position, both counted from `0`.
+#[derive(Clone, Copy)]
+struct Position {
+    line: usize,
+    column: usize,
+}
+
+impl<'a> SourceSpan<'a> {
+    /// Creates a new unlabeled span within `source`.
+    pub fn new(source: &'a str, span: Span) -> Self {
+        SourceSpan {
+            source,
+            span,
+            label: None,
+        }
+    }
+
+    /// Labels the span with `label`.
+    pub fn with_label(self, label: &'a str) -> Self {
+        SourceSpan {
+            label: Some(label),
+            ..self
+        }
+    }
+
+    /// Returns the source text.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    /// Returns the character span.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the label, if there is one.
+    pub fn label(&self) -> Option<&'a str> {
+        self.label
+    }
+
+    /// Returns the characters within the span.
+    pub fn text(&self) -> &'a str {
+        let bytes = char_range_to_byte_range(self.source, self.span.start(), Some(self.span.end()));
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `source`.
+        slice_unchecked!(self.source, bytes.start, bytes.end)
+    }
+
+    /// Returns the line and column of the start and end of the span.
+    ///
+    /// Lines and columns are both counted from `1`, and columns count characters. The end
+    /// position is that of the character following the span.
+    pub fn line_col(&self) -> ((usize, usize), (usize, usize)) {
+        let bytes = char_range_to_byte_range(self.source, self.span.start(), Some(self.span.end()));
+        let start = self.position(bytes.start);
+        let end = self.position(bytes.end);
+        (
+            (start.line + 1, start.column + 1),
+            (end.line + 1, end.column + 1),
+        )
+    }
+
+    /// Returns the position of the character at byte index `byte_index`.
+    fn position(&self, byte_index: usize) -> Position {
+        // SAFETY: `byte_index` is always on a UTF-8 sequence boundary of `source`.
+        let prefix = slice_unchecked!(self.source, 0, byte_index);
+        let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+        Position {
+            line: prefix.matches('\n').count(),
+            // SAFETY: `line_start` is either `0` or follows a newline.
+            column: slice_unchecked!(prefix, line_start, prefix.len())
+                .chars()
+                .count(),
+        }
+    }
+}
+
+/// Formats the span's character range along with its text and label, as in
+/// `SourceSpan { chars: 8..11, text: "fõo", label: Some("not found") }`.
+///
+/// Text longer than 32 characters is truncated and followed by an ellipsis.
+impl<'a> fmt::Debug for SourceSpan<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SourceSpan")
+            .field("chars", &(self.span.start()..self.span.end()))
+            .field("text", &TruncatedText(self.text()))
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+impl<'a> fmt::Display for SourceSpan<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = char_range_to_byte_range(self.source, self.span.start(), Some(self.span.end()));
+        let first = self.position(bytes.start);
+        // The position of the last character within the span.
+        let last = if bytes.end > bytes.start {
+            let mut last_start = bytes.end - 1;
+            while !self.source.is_char_boundary(last_start) {
+                last_start -= 1;
+            }
+            self.position(last_start)
+        } else {
+            first
+        };
+
+        let mut width = 1;
+        let mut max_line_number = last.line + 1;
+        while max_line_number >= 10 {
+            width += 1;
+            max_line_number /= 10;
+        }
+
+        let lines = self
+            .source
+            .split('\n')
+            .enumerate()
+            .skip(first.line)
+            .take(last.line - first.line + 1);
+        for (line, text) in lines {
+            if line != first.line {
+                f.write_str("\n")?;
+            }
+            let text = if text.ends_with('\r') {
+                &text[..(text.len() - 1)]
+            } else {
+                text
+            };
+            write!(f, "{:>width$} |", line + 1, width = width)?;
+            if !text.is_empty() {
+                write!(f, " {}", text)?;
+            }
+
+            let from = if line == first.line { first.column } else { 0 };
+            let to = if line == last.line {
+                last.column
+            } else {
+                from.max(text.chars().count().saturating_sub(1))
+            };
+            write!(f, "\n{:width$} | ", "", width = width)?;
+            for _ in 0..from {
+                f.write_str(" ")?;
+            }
+            for _ in from..(to + 1) {
+                f.write_str("^")?;
+            }
+            if line == last.line {
+                if let Some(label) = self.label {
+                    write!(f, " {}", label)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceSpan;
+    use span::Span;
+
+    fn source_span(source: &str, start: usize, end: usize) -> SourceSpan<'_> {
+        SourceSpan::new(source, Span::new(start, end).unwrap())
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(source_span("fõøbα®", 1, 4).text(), "õøb");
+        assert_eq!(source_span("fõøbα®", 4, 10).text(), "α®");
+    }
+
+    #[test]
+    fn test_line_col() {
+        let source = "foo\nbαr\r\nbaz";
+        assert_eq!(source_span(source, 0, 3).line_col(), ((1, 1), (1, 4)));
+        assert_eq!(source_span(source, 5, 11).line_col(), ((2, 2), (3, 3)));
+        assert_eq!(source_span(source, 13, 13).line_col(), ((3, 4), (3, 4)));
+    }
+
+    #[test]
+    fn test_display_single_line() {
+        assert_eq!(
+            format!("{}", source_span("let x = fõo;", 8, 11).with_label("here")),
+            "1 | let x = fõo;\n  |         ^^^ here"
+        );
+    }
+
+    #[test]
+    fn test_display_without_label() {
+        assert_eq!(
+            format!("{}", source_span("let x = fõo;", 4, 5)),
+            "1 | let x = fõo;\n  |     ^"
+        );
+    }
+
+    #[test]
+    fn test_display_multi_line() {
+        let source = "fn main() {\n    let x = 1;\r\n    x\n}";
+        assert_eq!(
+            format!("{}", source_span(source, 3, 33).with_label("block")),
+            "1 | fn main() {\n\
+             \x20 |    ^^^^^^^^\n\
+             2 |     let x = 1;\n\
+             \x20 | ^^^^^^^^^^^^^^\n\
+             3 |     x\n\
+             \x20 | ^^^^^ block"
+        );
+    }
+
+    #[test]
+    fn test_display_wide_line_numbers() {
+        let source = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk";
+        assert_eq!(
+            format!("{}", source_span(source, 16, 19)),
+            " 9 | i\n   | ^\n10 | j\n   | ^"
+        );
+    }
+
+    #[test]
+    fn test_display_end_of_file() {
+        assert_eq!(
+            format!("{}", source_span("foo", 3, 3).with_label("expected `;`")),
+            "1 | foo\n  |    ^ expected `;`"
+        );
+        assert_eq!(
+            format!("{}", source_span("foo\n", 4, 4).with_label("expected `;`")),
+            "2 |\n  | ^ expected `;`"
+        );
+        assert_eq!(
+            format!("{}", source_span("foo", 10, 12)),
+            "1 | foo\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let span =
+            SourceSpan::new("let x = fõo;", Span::new(8, 11).unwrap()).with_label("not found");
+        assert_eq!(
+            format!("{:?}", span),
+            "SourceSpan { chars: 8..11, text: \"fõo\", label: Some(\"not found\") }"
+        );
+    }
+
+    #[test]
+    fn test_debug_truncated() {
+        let source = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        let span = SourceSpan::new(source, Span::new(0, 36).unwrap());
+        assert_eq!(
+            format!("{:?}", span),
+            "SourceSpan { chars: 0..36, text: \"0123456789ABCDEFGHIJKLMNOPQRSTUV\"…, label: None }"
+        );
+    }
+}
@@ -0,0 +1,647 @@
+//! Character spans and spans validated against a specific string.
+
+#[cfg(rustc_1_34)]
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Range;
+use debug::TruncatedText;
+use error::SubstringError;
+use unit::char_range_to_byte_range;
+
+/// A range of character indices whose start is not after its end.
+///
+/// A `Span` is not tied to any particular string. To slice a string by a span repeatedly without
+/// re-walking it, [`validate()`] the span against the string once.
+///
+/// Example:
+/// ```
+/// use substring::Span;
+///
+/// let span = Span::new(2, 5).unwrap();
+/// assert_eq!(span.validate("fõøbα®").unwrap().as_str(), "øbα");
+/// ```
+///
+/// [`validate()`]: #method.validate
+#[derive(Clone, Copy, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Span {
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    /// Creates a span of the characters within `start..end`.
+    ///
+    /// Returns an error if `start` is after `end`.
+    pub fn new(start: usize, end: usize) -> Result<Self, SubstringError> {
+        if start > end {
+            Err(SubstringError::StartAfterEnd { start, end })
+        } else {
+            Ok(Span { start, end })
+        }
+    }
+
+    /// Returns the character index at which the span starts.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the character index at which the span ends, exclusive.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Returns the number of characters within the span.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns whether the span contains no characters.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Returns whether every character within `other` is also within the span.
+    ///
+    /// An empty `other` is contained if it is positioned within the span or at either of its ends.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::Span;
+    ///
+    /// let span = Span::new(2, 5).unwrap();
+    /// assert!(span.contains_span(Span::new(3, 5).unwrap()));
+    /// assert!(span.contains_span(Span::new(5, 5).unwrap()));
+    /// assert!(!span.contains_span(Span::new(4, 6).unwrap()));
+    /// ```
+    pub fn contains_span(&self, other: Span) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Returns whether the span and `other` have at least one character in common.
+    ///
+    /// Empty spans overlap no other span.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::Span;
+    ///
+    /// let span = Span::new(2, 5).unwrap();
+    /// assert!(span.overlaps(Span::new(4, 6).unwrap()));
+    /// assert!(!span.overlaps(Span::new(5, 6).unwrap()));
+    /// ```
+    pub fn overlaps(&self, other: Span) -> bool {
+        !self.is_empty() && !other.is_empty() && self.start < other.end && other.start < self.end
+    }
+
+    /// Converts the span from being relative to the start of `outer` to being absolute.
+    ///
+    /// The resulting span is clamped to the end of `outer`, so it is always contained within
+    /// `outer`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::Span;
+    ///
+    /// let outer = Span::new(10, 20).unwrap();
+    /// assert_eq!(
+    ///     Span::new(2, 5).unwrap().rebase_into(outer),
+    ///     Span::new(12, 15).unwrap()
+    /// );
+    /// assert_eq!(
+    ///     Span::new(8, 15).unwrap().rebase_into(outer),
+    ///     Span::new(18, 20).unwrap()
+    /// );
+    /// ```
+    pub fn rebase_into(&self, outer: Span) -> Span {
+        let rebase = |index: usize| {
+            let index = outer.start.saturating_add(index);
+            if index > outer.end {
+                outer.end
+            } else {
+                index
+            }
+        };
+        Span {
+            start: rebase(self.start),
+            end: rebase(self.end),
+        }
+    }
+
+    /// Converts the span from being absolute to being relative to the start of `outer`.
+    ///
+    /// Returns `None` if the span is not [contained] within `outer`.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::Span;
+    ///
+    /// let outer = Span::new(10, 20).unwrap();
+    /// assert_eq!(
+    ///     Span::new(12, 15).unwrap().rebase_out_of(outer),
+    ///     Some(Span::new(2, 5).unwrap())
+    /// );
+    /// assert_eq!(Span::new(8, 15).unwrap().rebase_out_of(outer), None);
+    /// ```
+    ///
+    /// [contained]: #method.contains_span
+    pub fn rebase_out_of(&self, outer: Span) -> Option<Span> {
+        if outer.contains_span(*self) {
+            Some(Span {
+                start: self.start - outer.start,
+                end: self.end - outer.start,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Validates the span against `s`, resolving it to a byte range.
+    ///
+    /// Returns an error if the span extends past the end of `s`. The returned [`ValidatedSpan`]
+    /// borrows `s`, and can be used to slice it in constant time.
+    ///
+    /// [`ValidatedSpan`]: struct.ValidatedSpan.html
+    pub fn validate(self, s: &str) -> Result<ValidatedSpan<'_>, SubstringError> {
+        let bytes = char_range_to_byte_range(s, self.start, Some(self.end));
+        if bytes.end == s.len() {
+            // The span may have been clamped to the end of `s`.
+            let char_len = s.chars().count();
+            if self.start > char_len {
+                return Err(SubstringError::StartOutOfBounds {
+                    index: self.start,
+                    char_len,
+                });
+            }
+            if self.end > char_len {
+                return Err(SubstringError::EndOutOfBounds {
+                    index: self.end,
+                    char_len,
+                });
+            }
+        }
+        Ok(ValidatedSpan {
+            span: self,
+            source: s,
+            start_byte: bytes.start,
+            end_byte: bytes.end,
+        })
+    }
+
+    /// Adjusts the span to account for the edit described by `delta`, made to the string the span
+    /// refers to.
+    ///
+    /// Where the edit replaced the characters within `old..` with those within `new..`, both
+    /// starting at the edit point:
+    ///
+    /// - A span ending at or before the edit point is unchanged.
+    /// - A span starting at or after the end of the replaced characters is shifted by the edit's
+    ///   change in length.
+    /// - A span within the replaced characters is collapsed to an empty span at the edit point.
+    /// - A span covering all of the replaced characters is resized to cover their replacement.
+    /// - A span covering only the start or the end of the replaced characters is clipped to the
+    ///   characters outside of them.
+    ///
+    /// An empty span at the edit point is therefore unchanged by an insertion there, while a
+    /// non-empty span starting at the edit point is shifted past the inserted characters.
+    ///
+    /// Returns `None` if an adjusted index would overflow.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{EditDelta, Span};
+    ///
+    /// // Replacing "õø" with "oooo" in "fõøbα®".
+    /// let delta = EditDelta::new(1, 2, 4, 0);
+    /// assert_eq!(
+    ///     Span::new(4, 6).unwrap().apply_delta(&delta),
+    ///     Some(Span::new(6, 8).unwrap())
+    /// );
+    /// ```
+    pub fn apply_delta(&self, delta: &EditDelta) -> Option<Span> {
+        let edit_start = delta.old_char_range.start;
+        let old_end = delta.old_char_range.end;
+        let new_end = delta.new_char_range.end;
+        // Maps an index at or after the end of the replaced characters to its new position.
+        let shift = |index: usize| (index - old_end).checked_add(new_end);
+
+        let (start, end) = if self.end <= edit_start {
+            (self.start, self.end)
+        } else if self.start >= old_end {
+            (shift(self.start)?, shift(self.end)?)
+        } else if self.start >= edit_start && self.end <= old_end {
+            (edit_start, edit_start)
+        } else if self.start <= edit_start && self.end >= old_end {
+            (self.start, shift(self.end)?)
+        } else if self.start < edit_start {
+            (self.start, edit_start)
+        } else {
+            (new_end, shift(self.end)?)
+        };
+        Some(Span { start, end })
+    }
+}
+
+/// The change made to a string by replacing a range of its characters.
+///
+/// This struct is returned by [`replace_substring_tracked()`], and can be applied to spans within
+/// the edited string using [`Span::apply_delta()`].
+///
+/// [`replace_substring_tracked()`]: fn.replace_substring_tracked.html
+/// [`Span::apply_delta()`]: struct.Span.html#method.apply_delta
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct EditDelta {
+    old_char_range: Range<usize>,
+    new_char_range: Range<usize>,
+    byte_delta: isize,
+}
+
+impl EditDelta {
+    /// Creates a delta describing the replacement of `old_len` characters at the character index
+    /// `start` with `new_len` characters, changing the byte length of the string by `byte_delta`.
+    ///
+    /// This allows spans to be adjusted for edits not made by [`replace_substring_tracked()`].
+    ///
+    /// [`replace_substring_tracked()`]: fn.replace_substring_tracked.html
+    pub fn new(start: usize, old_len: usize, new_len: usize, byte_delta: isize) -> Self {
+        EditDelta {
+            old_char_range: start..(start + old_len),
+            new_char_range: start..(start + new_len),
+            byte_delta,
+        }
+    }
+
+    /// Returns the character range of the replaced characters, before the edit.
+    pub fn old_char_range(&self) -> Range<usize> {
+        self.old_char_range.clone()
+    }
+
+    /// Returns the character range of the replacement characters, after the edit.
+    pub fn new_char_range(&self) -> Range<usize> {
+        self.new_char_range.clone()
+    }
+
+    /// Returns the change in the number of characters of the string.
+    pub fn char_delta(&self) -> isize {
+        self.new_char_range.len() as isize - self.old_char_range.len() as isize
+    }
+
+    /// Returns the change in the byte length of the string.
+    pub fn byte_delta(&self) -> isize {
+        self.byte_delta
+    }
+}
+
+#[cfg(rustc_1_34)]
+impl TryFrom<Range<usize>> for Span {
+    type Error = SubstringError;
+
+    fn try_from(range: Range<usize>) -> Result<Self, SubstringError> {
+        Span::new(range.start, range.end)
+    }
+}
+
+/// Formats the span's character range, as in `Span { chars: 2..5 }`.
+impl fmt::Debug for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Span")
+            .field("chars", &(self.start..self.end))
+            .finish()
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+/// A [`Span`] validated against a specific string.
+///
+/// The span's byte range is resolved once, by [`Span::validate()`], so slicing the string by it
+/// takes constant time. A `ValidatedSpan` borrows the string it was validated against, and cannot
+/// outlive it:
+///
+/// ```compile_fail
+/// use substring::Span;
+///
+/// let validated;
+/// {
+///     let s = String::from("foobar");
+///     validated = Span::new(1, 3).unwrap().validate(&s).unwrap();
+/// }
+/// validated.as_str();
+/// ```
+///
+/// [`Span`]: struct.Span.html
+/// [`Span::validate()`]: struct.Span.html#method.validate
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+pub struct ValidatedSpan<'s> {
+    span: Span,
+    source: &'s str,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+impl<'s> ValidatedSpan<'s> {
+    /// Returns the character span.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the byte range of the span within the string it was validated against.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.start_byte..self.end_byte
+    }
+
+    /// Returns the string the span was validated against.
+    pub fn source(&self) -> &'s str {
+        self.source
+    }
+
+    /// Returns the characters within the span.
+    pub fn as_str(&self) -> &'s str {
+        // SAFETY: The byte range was resolved from the `CharIndices` of `source` during
+        // validation, so it lies on UTF-8 sequence boundaries within `source`.
+        slice_unchecked!(self.source, self.start_byte, self.end_byte)
+    }
+
+    /// Returns whether the span was validated against `s`.
+    ///
+    /// Strings are compared by identity, not by content.
+    pub(crate) fn is_validated_against(&self, s: &str) -> bool {
+        self.source.as_ptr() == s.as_ptr() && self.source.len() == s.len()
+    }
+}
+
+/// Formats the span's character and byte ranges along with its text, as in
+/// `ValidatedSpan { chars: 2..5, bytes: 3..8, text: "øbα" }`.
+///
+/// Text longer than 32 characters is truncated and followed by an ellipsis.
+impl<'s> fmt::Debug for ValidatedSpan<'s> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ValidatedSpan")
+            .field("chars", &(self.span.start..self.span.end))
+            .field("bytes", &(self.start_byte..self.end_byte))
+            .field("text", &TruncatedText(self.as_str()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EditDelta, Span};
+    use error::SubstringError;
+    use unit::WALKS;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            Span::new(2, 5).map(|span| (span.start(), span.end())),
+            Ok((2, 5))
+        );
+        assert_eq!(
+            Span::new(5, 2),
+            Err(SubstringError::StartAfterEnd { start: 5, end: 2 })
+        );
+    }
+
+    #[test]
+    fn test_len() {
+        assert_eq!(Span::new(2, 5).unwrap().len(), 3);
+        assert!(Span::new(3, 3).unwrap().is_empty());
+    }
+
+    #[cfg(rustc_1_34)]
+    #[test]
+    fn test_try_from_range() {
+        use core::convert::TryFrom;
+
+        assert_eq!(Span::try_from(2..5), Span::new(2, 5));
+        let (start, end) = (5, 2);
+        assert_eq!(
+            Span::try_from(start..end),
+            Err(SubstringError::StartAfterEnd { start: 5, end: 2 })
+        );
+    }
+
+    #[test]
+    fn test_into_range() {
+        let range: ::core::ops::Range<usize> = Span::new(2, 5).unwrap().into();
+        assert_eq!(range, 2..5);
+    }
+
+    #[test]
+    fn test_validate() {
+        let validated = Span::new(2, 5).unwrap().validate("fõøbα®").unwrap();
+        assert_eq!(validated.as_str(), "øbα");
+        assert_eq!(validated.byte_range(), 3..8);
+        assert_eq!(validated.span(), Span::new(2, 5).unwrap());
+    }
+
+    #[test]
+    fn test_validate_to_end() {
+        assert_eq!(
+            Span::new(4, 6)
+                .unwrap()
+                .validate("fõøbα®")
+                .unwrap()
+                .as_str(),
+            "α®"
+        );
+        assert_eq!(
+            Span::new(6, 6)
+                .unwrap()
+                .validate("fõøbα®")
+                .unwrap()
+                .as_str(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_validate_out_of_bounds() {
+        assert_eq!(
+            Span::new(4, 7).unwrap().validate("fõøbα®"),
+            Err(SubstringError::EndOutOfBounds {
+                index: 7,
+                char_len: 6
+            })
+        );
+        assert_eq!(
+            Span::new(7, 8).unwrap().validate("fõøbα®"),
+            Err(SubstringError::StartOutOfBounds {
+                index: 7,
+                char_len: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_validated_reuse_does_not_walk() {
+        let s = "fõøbα®";
+        let validated = Span::new(1, 4).unwrap().validate(s).unwrap();
+        let walks = WALKS.with(|walks| walks.get());
+        for _ in 0..10 {
+            assert_eq!(validated.as_str(), "õøb");
+        }
+        assert_eq!(WALKS.with(|walks| walks.get()), walks);
+    }
+
+    fn span(start: usize, end: usize) -> Span {
+        Span::new(start, end).unwrap()
+    }
+
+    #[test]
+    fn test_edit_delta() {
+        let delta = EditDelta::new(2, 3, 1, -4);
+        assert_eq!(delta.old_char_range(), 2..5);
+        assert_eq!(delta.new_char_range(), 2..3);
+        assert_eq!(delta.char_delta(), -2);
+        assert_eq!(delta.byte_delta(), -4);
+    }
+
+    #[test]
+    fn test_apply_delta_before() {
+        let delta = EditDelta::new(4, 2, 5, 3);
+        assert_eq!(span(0, 3).apply_delta(&delta), Some(span(0, 3)));
+        assert_eq!(span(1, 4).apply_delta(&delta), Some(span(1, 4)));
+    }
+
+    #[test]
+    fn test_apply_delta_after() {
+        let delta = EditDelta::new(4, 2, 5, 3);
+        assert_eq!(span(6, 8).apply_delta(&delta), Some(span(9, 11)));
+        let delta = EditDelta::new(4, 3, 1, -2);
+        assert_eq!(span(7, 8).apply_delta(&delta), Some(span(5, 6)));
+    }
+
+    #[test]
+    fn test_apply_delta_inside() {
+        let delta = EditDelta::new(2, 4, 1, -3);
+        assert_eq!(span(3, 5).apply_delta(&delta), Some(span(2, 2)));
+        assert_eq!(span(2, 6).apply_delta(&delta), Some(span(2, 2)));
+    }
+
+    #[test]
+    fn test_apply_delta_covering() {
+        let delta = EditDelta::new(2, 2, 5, 3);
+        assert_eq!(span(1, 6).apply_delta(&delta), Some(span(1, 9)));
+        assert_eq!(span(2, 5).apply_delta(&delta), Some(span(2, 8)));
+        assert_eq!(span(1, 4).apply_delta(&delta), Some(span(1, 7)));
+    }
+
+    #[test]
+    fn test_apply_delta_straddling() {
+        let delta = EditDelta::new(2, 3, 1, -2);
+        assert_eq!(span(0, 3).apply_delta(&delta), Some(span(0, 2)));
+        assert_eq!(span(4, 7).apply_delta(&delta), Some(span(3, 5)));
+    }
+
+    #[test]
+    fn test_apply_delta_insertion() {
+        let delta = EditDelta::new(3, 0, 2, 2);
+        assert_eq!(span(3, 3).apply_delta(&delta), Some(span(3, 3)));
+        assert_eq!(span(3, 5).apply_delta(&delta), Some(span(5, 7)));
+        assert_eq!(span(1, 3).apply_delta(&delta), Some(span(1, 3)));
+        assert_eq!(span(1, 4).apply_delta(&delta), Some(span(1, 6)));
+    }
+
+    #[test]
+    fn test_apply_delta_overflow() {
+        let delta = EditDelta::new(0, 0, 2, 2);
+        assert_eq!(span(1, usize::max_value()).apply_delta(&delta), None);
+    }
+
+    #[test]
+    fn test_contains_span() {
+        let outer = span(2, 5);
+        assert!(outer.contains_span(span(2, 5)));
+        assert!(outer.contains_span(span(2, 2)));
+        assert!(outer.contains_span(span(5, 5)));
+        assert!(!outer.contains_span(span(1, 1)));
+        assert!(!outer.contains_span(span(6, 6)));
+        assert!(!outer.contains_span(span(1, 3)));
+        assert!(!outer.contains_span(span(0, 10)));
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let outer = span(2, 5);
+        assert!(outer.overlaps(span(4, 10)));
+        assert!(outer.overlaps(span(0, 3)));
+        assert!(outer.overlaps(span(0, 10)));
+        assert!(!outer.overlaps(span(0, 2)));
+        assert!(!outer.overlaps(span(5, 10)));
+        assert!(!outer.overlaps(span(3, 3)));
+        assert!(!span(3, 3).overlaps(span(3, 3)));
+    }
+
+    #[test]
+    fn test_rebase_into() {
+        let outer = span(10, 20);
+        assert_eq!(span(0, 10).rebase_into(outer), span(10, 20));
+        assert_eq!(span(0, 0).rebase_into(outer), span(10, 10));
+        assert_eq!(span(10, 10).rebase_into(outer), span(20, 20));
+        assert_eq!(span(5, 30).rebase_into(outer), span(15, 20));
+        assert_eq!(span(30, 40).rebase_into(outer), span(20, 20));
+    }
+
+    #[test]
+    fn test_rebase_into_overflow() {
+        let max = usize::max_value();
+        let outer = span(max - 2, max);
+        assert_eq!(span(1, 2).rebase_into(outer), span(max - 1, max));
+        assert_eq!(span(1, max).rebase_into(outer), span(max - 1, max));
+        assert_eq!(span(max, max).rebase_into(outer), span(max, max));
+    }
+
+    #[test]
+    fn test_rebase_out_of() {
+        let outer = span(10, 20);
+        assert_eq!(span(10, 20).rebase_out_of(outer), Some(span(0, 10)));
+        assert_eq!(span(10, 10).rebase_out_of(outer), Some(span(0, 0)));
+        assert_eq!(span(20, 20).rebase_out_of(outer), Some(span(10, 10)));
+        assert_eq!(span(9, 12).rebase_out_of(outer), None);
+        assert_eq!(span(18, 21).rebase_out_of(outer), None);
+        assert_eq!(span(21, 21).rebase_out_of(outer), None);
+    }
+
+    #[test]
+    fn test_rebase_round_trip() {
+        let max = usize::max_value();
+        for &outer in [span(0, 0), span(3, 8), span(max - 4, max)].iter() {
+            for start in 0..6 {
+                for end in start..6 {
+                    let inner = span(start, end);
+                    let absolute = inner.rebase_into(outer);
+                    assert!(outer.contains_span(absolute));
+                    if start <= outer.len() && end <= outer.len() {
+                        assert_eq!(absolute.rebase_out_of(outer), Some(inner));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_span_debug() {
+        assert_eq!(format!("{:?}", span(2, 5)), "Span { chars: 2..5 }");
+    }
+
+    #[test]
+    fn test_validated_span_debug() {
+        assert_eq!(
+            format!("{:?}", span(2, 5).validate("fõøbα®").unwrap()),
+            "ValidatedSpan { chars: 2..5, bytes: 3..8, text: \"øbα\" }"
+        );
+    }
+
+    #[test]
+    fn test_validated_span_debug_truncated() {
+        let s = "Þetta er langur texti sem verður styttur í villuleitarúttakinu.";
+        assert_eq!(
+            format!("{:?}", span(1, 40).validate(s).unwrap()),
+            "ValidatedSpan { chars: 1..40, bytes: 2..42, text: \"etta er langur texti sem verður \"… }"
+        );
+    }
+}
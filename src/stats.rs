@@ -0,0 +1,298 @@
+//! Observation of ranges clamped to the bounds of strings.
+
+use core::fmt;
+use core::ops::{Bound, Range};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::error::Error;
+#[cfg(feature = "forbid_unsafe")]
+use std::sync::OnceLock;
+use unit::{self, Unit};
+
+/// A hook notified whenever a range is clamped to the bounds of a string.
+///
+/// Clamping usually indicates that a range was computed incorrectly upstream. Installing an
+/// observer with [`set_observer()`] allows such ranges to be detected without switching to
+/// fallible methods.
+///
+/// [`set_observer()`]: fn.set_observer.html
+pub trait ClampObserver: Sync {
+    /// Called with the `requested` range and the `effective` range it was clamped to, both in the
+    /// unit the range was requested in.
+    ///
+    /// The requested range is given with its bounds resolved: the start is always included, and
+    /// the end is either excluded or unbounded.
+    fn on_clamp(&self, requested: (Bound<usize>, Bound<usize>), effective: Range<usize>);
+}
+
+/// A [`ClampObserver`] counting the ranges clamped.
+///
+/// Example:
+/// ```
+/// use substring::{set_observer, ClampObserver, CountingObserver, Substring};
+///
+/// static OBSERVER: CountingObserver = CountingObserver::new();
+///
+/// set_observer(&OBSERVER).unwrap();
+/// assert_eq!("foobar".substring(2, 10), "obar");
+/// assert_eq!(OBSERVER.clamped(), 1);
+/// ```
+///
+/// [`ClampObserver`]: trait.ClampObserver.html
+#[derive(Debug, Default)]
+pub struct CountingObserver {
+    clamped: AtomicUsize,
+}
+
+impl CountingObserver {
+    /// Creates an observer that has not counted any ranges.
+    pub const fn new() -> Self {
+        CountingObserver {
+            clamped: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of ranges clamped since the observer was created or last reset.
+    pub fn clamped(&self) -> usize {
+        self.clamped.load(Ordering::Relaxed)
+    }
+
+    /// Resets the count of ranges clamped to zero.
+    pub fn reset(&self) {
+        self.clamped.store(0, Ordering::Relaxed);
+    }
+}
+
+impl ClampObserver for CountingObserver {
+    fn on_clamp(&self, _requested: (Bound<usize>, Bound<usize>), _effective: Range<usize>) {
+        self.clamped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// An error indicating that an observer has already been set.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SetObserverError(());
+
+impl fmt::Display for SetObserverError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a clamp observer has already been set")
+    }
+}
+
+impl Error for SetObserverError {}
+
+#[cfg(not(feature = "forbid_unsafe"))]
+const UNSET: usize = 0;
+#[cfg(not(feature = "forbid_unsafe"))]
+const SETTING: usize = 1;
+#[cfg(not(feature = "forbid_unsafe"))]
+const SET: usize = 2;
+
+#[cfg(not(feature = "forbid_unsafe"))]
+static STATE: AtomicUsize = AtomicUsize::new(UNSET);
+#[cfg(not(feature = "forbid_unsafe"))]
+static mut OBSERVER: Option<&'static dyn ClampObserver> = None;
+// Without `unsafe` code, the observer is stored in a `OnceLock`, which requires `rustc 1.70.0`.
+#[cfg(feature = "forbid_unsafe")]
+#[clippy::msrv = "1.70"]
+static OBSERVER: OnceLock<&'static dyn ClampObserver> = OnceLock::new();
+
+/// Installs `observer` to be notified of every range clamped for the remainder of the program.
+///
+/// An observer can only be set once; later calls return an error. Until an observer is set,
+/// clamping costs a single atomic load.
+#[cfg(not(feature = "forbid_unsafe"))]
+pub fn set_observer(observer: &'static dyn ClampObserver) -> Result<(), SetObserverError> {
+    if STATE
+        .compare_exchange(UNSET, SETTING, Ordering::Acquire, Ordering::Relaxed)
+        .is_err()
+    {
+        return Err(SetObserverError(()));
+    }
+    // SAFETY: `OBSERVER` is only written here, which the state transition above guarantees
+    // happens at most once, and only read after the state is `SET`.
+    unsafe {
+        OBSERVER = Some(observer);
+    }
+    STATE.store(SET, Ordering::Release);
+    Ok(())
+}
+
+/// Installs `observer` to be notified of every range clamped for the remainder of the program.
+///
+/// An observer can only be set once; later calls return an error. Until an observer is set,
+/// clamping costs a single atomic load.
+#[cfg(feature = "forbid_unsafe")]
+#[clippy::msrv = "1.70"]
+pub fn set_observer(observer: &'static dyn ClampObserver) -> Result<(), SetObserverError> {
+    OBSERVER.set(observer).map_err(|_| SetObserverError(()))
+}
+
+#[cfg(not(feature = "forbid_unsafe"))]
+fn observer() -> Option<&'static dyn ClampObserver> {
+    if STATE.load(Ordering::Acquire) == SET {
+        // SAFETY: `OBSERVER` is never written again once the state is `SET`.
+        unsafe { OBSERVER }
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "forbid_unsafe")]
+#[clippy::msrv = "1.70"]
+fn observer() -> Option<&'static dyn ClampObserver> {
+    OBSERVER.get().copied()
+}
+
+/// Notifies the installed observer if the `unit` range `start..end` was clamped to `bytes` within
+/// `s`.
+#[inline]
+pub(crate) fn observe(s: &str, unit: Unit, start: usize, end: Option<usize>, bytes: &Range<usize>) {
+    if let Some(observer) = observer() {
+        notify(observer, s, unit, start, end, bytes);
+    }
+}
+
+/// Notifies the installed observer if the character range `start..end` would be clamped within
+/// `s`.
+///
+/// The range is only resolved if an observer is installed.
+#[inline]
+pub(crate) fn observe_chars(s: &str, start: usize, end: Option<usize>) {
+    if let Some(observer) = observer() {
+        let bytes = unit::char_range_to_byte_range(s, start, end);
+        notify(observer, s, Unit::Chars, start, end, &bytes);
+    }
+}
+
+fn notify(
+    observer: &dyn ClampObserver,
+    s: &str,
+    unit: Unit,
+    start: usize,
+    end: Option<usize>,
+    bytes: &Range<usize>,
+) {
+    let effective = unit::from_byte_range(s, unit, bytes.start, Some(bytes.end));
+    if effective.start != start || end.map_or(false, |end| effective.end != end) {
+        observer.on_clamp(
+            (
+                Bound::Included(start),
+                end.map_or(Bound::Unbounded, Bound::Excluded),
+            ),
+            effective,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_observer, ClampObserver, CountingObserver};
+    use core::cell::RefCell;
+    use core::ops::{Bound, Range};
+    use std::sync::Once;
+    use std::vec::Vec;
+    use unit::Unit;
+    use {CharSubstring, Substring};
+
+    /// An observer recording the clamps performed on the current thread, so that tests running in
+    /// parallel do not observe each other.
+    struct TestObserver;
+
+    type Clamp = ((Bound<usize>, Bound<usize>), Range<usize>);
+
+    thread_local! {
+        static CLAMPS: RefCell<Vec<Clamp>> = RefCell::new(Vec::new());
+    }
+
+    impl ClampObserver for TestObserver {
+        fn on_clamp(&self, requested: (Bound<usize>, Bound<usize>), effective: Range<usize>) {
+            CLAMPS.with(|clamps| clamps.borrow_mut().push((requested, effective)));
+        }
+    }
+
+    static OBSERVER: TestObserver = TestObserver;
+    static INSTALL: Once = Once::new();
+
+    /// Installs the test observer and returns the clamps observed while running `f`.
+    fn clamps<F: FnOnce()>(f: F) -> Vec<Clamp> {
+        INSTALL.call_once(|| set_observer(&OBSERVER).unwrap());
+        CLAMPS.with(|clamps| clamps.borrow_mut().clear());
+        f();
+        CLAMPS.with(|clamps| clamps.replace(Vec::new()))
+    }
+
+    #[test]
+    fn test_in_bounds_not_observed() {
+        let observed = clamps(|| {
+            assert_eq!("fõøbα®".substring(1, 4), "õøb");
+            assert_eq!("fõøbα®".substring(0, 6), "fõøbα®");
+            assert_eq!("fõøbα®".to_byte_range(Unit::Chars, 2..), 3..10);
+            assert_eq!("fõøbα®".to_byte_range(Unit::Bytes, 1..3), 1..3);
+            assert_eq!("😀a".to_byte_range(Unit::Utf16, 2..3), 4..5);
+        });
+        assert!(observed.is_empty());
+    }
+
+    #[test]
+    fn test_clamped_observed() {
+        let observed = clamps(|| {
+            assert_eq!("fõøbα®".substring(4, 10), "α®");
+        });
+        assert_eq!(
+            observed,
+            [((Bound::Included(4), Bound::Excluded(10)), 4..6)]
+        );
+    }
+
+    #[test]
+    fn test_reversed_observed() {
+        let (start, end) = (4, 2);
+        let observed = clamps(|| {
+            assert_eq!("fõøbα®".to_byte_range(Unit::Chars, start..=end), 6..6);
+        });
+        assert_eq!(observed, [((Bound::Included(4), Bound::Excluded(3)), 4..4)]);
+    }
+
+    #[test]
+    fn test_snapped_observed() {
+        let observed = clamps(|| {
+            assert_eq!("fõøbα®".to_byte_range(Unit::Bytes, 2..), 3..10);
+            assert_eq!("😀a".to_byte_range(Unit::Utf16, 1..), 4..5);
+        });
+        assert_eq!(
+            observed,
+            [
+                ((Bound::Included(2), Bound::Unbounded), 3..10),
+                ((Bound::Included(1), Bound::Unbounded), 2..3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_observed_once_per_call() {
+        let observed = clamps(|| {
+            for _ in 0..3 {
+                let _ = "foo".substring(5, 7);
+            }
+        });
+        assert_eq!(observed.len(), 3);
+    }
+
+    #[test]
+    fn test_set_observer_twice() {
+        static OTHER: CountingObserver = CountingObserver::new();
+        INSTALL.call_once(|| set_observer(&OBSERVER).unwrap());
+        assert!(set_observer(&OTHER).is_err());
+    }
+
+    #[test]
+    fn test_counting_observer() {
+        let observer = CountingObserver::new();
+        assert_eq!(observer.clamped(), 0);
+        observer.on_clamp((Bound::Included(0), Bound::Excluded(5)), 0..3);
+        observer.on_clamp((Bound::Included(4), Bound::Unbounded), 3..3);
+        assert_eq!(observer.clamped(), 2);
+        observer.reset();
+        assert_eq!(observer.clamped(), 0);
+    }
+}
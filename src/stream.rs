@@ -0,0 +1,300 @@
+//! Boundary detection over text received in chunks.
+
+use unit::{Measure, Unit};
+use word::WordState;
+
+/// Resumable detection of unit boundaries over text received in chunks.
+///
+/// Each chunk passed to [`feed()`] continues the text from where the previous chunk ended.
+/// Boundaries are reported as byte offsets from the start of the whole text. Together with the
+/// offset returned by [`finish()`], the reported offsets are the same as those found by
+/// processing the whole text at once, regardless of how the text was split into chunks.
+///
+/// A boundary is reported at the start of every unit, so an empty text only has the boundary
+/// returned by [`finish()`]. The boundary at the end of each chunk is held back until the next
+/// chunk shows whether it is merged into the unit before it, as a combining mark at the start of a
+/// chunk is when measured in [`Unit::Width`]. At most one boundary is pending at a time, and it is
+/// reported by the next call to [`feed()`] or returned by [`finish()`].
+///
+/// Example:
+/// ```
+/// use substring::{BoundaryState, Unit};
+///
+/// let mut state = BoundaryState::new(Unit::Chars);
+/// assert_eq!(state.feed("fõ").collect::<Vec<_>>(), [0, 1]);
+/// assert_eq!(state.feed("øb").collect::<Vec<_>>(), [3, 5]);
+/// assert_eq!(state.finish(), 6);
+/// ```
+///
+/// [`feed()`]: #method.feed
+/// [`finish()`]: #method.finish
+/// [`Unit::Width`]: enum.Unit.html#variant.Width
+#[derive(Clone, Debug)]
+pub struct BoundaryState {
+    unit: Unit,
+    offset: usize,
+    /// The boundary held back at `offset`, the end of the text fed so far, or `None` if no text
+    /// has been fed.
+    pending: Option<usize>,
+    /// The progress of finding words at `offset`, for [`Unit::Words`].
+    ///
+    /// [`Unit::Words`]: enum.Unit.html#variant.Words
+    words: WordState,
+}
+
+impl BoundaryState {
+    /// Creates a new state for detecting the boundaries of `unit`s, starting at byte offset `0`.
+    ///
+    /// As every UTF-16 code unit boundary within valid text is also a character boundary,
+    /// [`Unit::Utf16`] reports the same boundaries as [`Unit::Chars`]. [`Unit::Width`] reports a
+    /// boundary at the start of the text and at the start of every character occupying at least
+    /// one column, as zero width characters are kept with the character they follow. [`Unit::Words`] reports a boundary at
+    /// the start of every word, taking into account the characters of earlier chunks.
+    ///
+    /// [`Unit::Chars`]: enum.Unit.html#variant.Chars
+    /// [`Unit::Utf16`]: enum.Unit.html#variant.Utf16
+    /// [`Unit::Width`]: enum.Unit.html#variant.Width
+    /// [`Unit::Words`]: enum.Unit.html#variant.Words
+    pub fn new(unit: Unit) -> Self {
+        BoundaryState {
+            unit,
+            offset: 0,
+            pending: None,
+            words: WordState::Outside,
+        }
+    }
+
+    /// Feeds the next chunk of text, returning an iterator over the boundaries found within it.
+    ///
+    /// The boundary pending from the previous chunk is reported first, unless the start of this
+    /// chunk merges it away. The boundary at the end of this chunk is held back in turn, as it is
+    /// also the start of the next chunk.
+    pub fn feed<'a>(&mut self, chunk: &'a str) -> ChunkBoundaries<'a> {
+        let boundaries = ChunkBoundaries {
+            chunk,
+            unit: self.unit,
+            base: self.offset,
+            index: 0,
+            pending: self.pending,
+            words: self.words,
+        };
+        if !chunk.is_empty() {
+            self.offset += chunk.len();
+            self.pending = Some(self.offset);
+            if let Unit::Words = self.unit {
+                self.words = chunk.chars().fold(self.words, |state, c| state.next(c).0);
+            }
+        }
+        boundaries
+    }
+
+    /// Finishes the text, returning the boundary held back at its end.
+    ///
+    /// No later character can merge this boundary away, so it is always the final boundary. For an
+    /// empty text, it is `0`.
+    pub fn finish(self) -> usize {
+        self.pending.unwrap_or(self.offset)
+    }
+}
+
+/// An iterator over the boundaries within a chunk fed to a [`BoundaryState`].
+///
+/// This struct is created by the [`feed()`] method on [`BoundaryState`].
+///
+/// [`BoundaryState`]: struct.BoundaryState.html
+/// [`feed()`]: struct.BoundaryState.html#method.feed
+#[derive(Clone, Debug)]
+pub struct ChunkBoundaries<'a> {
+    chunk: &'a str,
+    unit: Unit,
+    base: usize,
+    index: usize,
+    /// The boundary before the character at `index`, held back until that character shows
+    /// whether it is merged away, or `None` at the start of the text.
+    pending: Option<usize>,
+    words: WordState,
+}
+
+impl<'a> Iterator for ChunkBoundaries<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.index < self.chunk.len() {
+            let boundary = self.base + self.index;
+            if let Unit::Bytes = self.unit {
+                self.index += 1;
+                self.pending = Some(self.base + self.index);
+                return Some(boundary);
+            }
+            // SAFETY: `index` always lies on a UTF-8 sequence boundary of `chunk`.
+            let c = slice_unchecked!(self.chunk, self.index, self.chunk.len())
+                .chars()
+                .next()?;
+            self.index += c.len_utf8();
+            // The pending boundary before `c` is reported unless `c` merges it away.
+            let merged = match self.unit.measure() {
+                Measure::PerChar(len_of) => self.pending.is_some() && len_of(c) == 0,
+                Measure::Words => {
+                    let (words, starts_word) = self.words.next(c);
+                    self.words = words;
+                    !starts_word
+                }
+            };
+            self.pending = Some(self.base + self.index);
+            if !merged {
+                return Some(boundary);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundaryState;
+    use std::vec::Vec;
+    use unit::Unit;
+    use width::char_width;
+
+    fn boundaries<'a, I: IntoIterator<Item = &'a str>>(unit: Unit, chunks: I) -> Vec<usize> {
+        let mut state = BoundaryState::new(unit);
+        let mut boundaries = Vec::new();
+        for chunk in chunks {
+            boundaries.extend(state.feed(chunk));
+        }
+        boundaries.push(state.finish());
+        boundaries
+    }
+
+    fn whole(s: &str) -> Vec<usize> {
+        boundaries(Unit::Chars, Some(s))
+    }
+
+    #[test]
+    fn test_chars() {
+        assert_eq!(whole("fõøbα®"), [0, 1, 3, 5, 6, 8, 10]);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(whole(""), [0]);
+        assert_eq!(boundaries(Unit::Chars, vec!["", "a", "", ""]), [0, 1]);
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(boundaries(Unit::Bytes, vec!["fõ", "ø"]), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_utf16() {
+        assert_eq!(boundaries(Unit::Utf16, vec!["😀", "a"]), whole("😀a"));
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(
+            boundaries(Unit::Width, vec!["日e", "\u{301}", "\u{200b}x"]),
+            [0, 3, 9, 10]
+        );
+        assert_eq!(boundaries(Unit::Width, Some("\u{301}a")), [0, 2, 3]);
+        assert_eq!(
+            boundaries(Unit::Width, vec!["", "\u{301}", "", "a"]),
+            [0, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_width_mark_at_chunk_start() {
+        for s in [
+            "e\u{301}",
+            "a\u{301}\u{302}b",
+            "日\u{200d}本\u{fe0f}",
+            "\u{301}\u{301}x\u{301}",
+        ]
+        .iter()
+        {
+            let whole = boundaries(Unit::Width, Some(*s));
+            for (index, c) in s.char_indices() {
+                let split = boundaries(Unit::Width, vec![&s[..index], &s[index..]]);
+                assert_eq!(split, whole, "{:?} split at {}", s, index);
+                if index > 0 && char_width(c) == 0 {
+                    assert!(!split.contains(&index), "{:?} split at {}", s, index);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_width_one_char_per_chunk() {
+        let s = "a🇺🇸b👨\u{200d}👩e\u{301}\u{301}日";
+        let chunks = s
+            .char_indices()
+            .map(|(index, c)| &s[index..(index + c.len_utf8())])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            boundaries(Unit::Width, chunks),
+            boundaries(Unit::Width, Some(s))
+        );
+    }
+
+    #[test]
+    fn test_pending_boundary() {
+        let mut state = BoundaryState::new(Unit::Width);
+        assert_eq!(state.feed("ab").collect::<Vec<_>>(), [0, 1]);
+        // The boundary at 2 is pending, and is merged away by the combining mark.
+        assert_eq!(
+            state.feed("\u{301}").collect::<Vec<_>>(),
+            Vec::<usize>::new()
+        );
+        assert_eq!(state.clone().finish(), 4);
+        assert_eq!(state.feed("c").collect::<Vec<_>>(), [4]);
+        assert_eq!(state.finish(), 5);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            boundaries(Unit::Words, Some(" The quick, brown fox ")),
+            [1, 5, 12, 18, 22]
+        );
+        assert_eq!(
+            boundaries(Unit::Words, vec!["don", "'", "t 日", "\u{301}本"]),
+            [0, 6, 11, 14]
+        );
+    }
+
+    #[test]
+    fn test_words_every_split_point() {
+        let s = "don't 3.14. a'日\u{301}b";
+        let whole = boundaries(Unit::Words, Some(s));
+        for (index, _) in s.char_indices() {
+            assert_eq!(
+                boundaries(Unit::Words, vec![&s[..index], &s[index..]]),
+                whole
+            );
+        }
+    }
+
+    #[test]
+    fn test_one_char_per_chunk() {
+        // Regional indicator pairs and a ZWJ sequence.
+        let s = "a🇺🇸🇫🇷b👨\u{200d}👩\u{200d}👧e\u{301}";
+        let chunks = s
+            .char_indices()
+            .map(|(index, c)| &s[index..(index + c.len_utf8())])
+            .collect::<Vec<_>>();
+        assert_eq!(boundaries(Unit::Chars, chunks), whole(s));
+    }
+
+    #[test]
+    fn test_every_split_point() {
+        let s = "🇺🇸👨\u{200d}👩fõø";
+        for (index, _) in s.char_indices() {
+            assert_eq!(
+                boundaries(Unit::Chars, vec![&s[..index], &s[index..]]),
+                whole(s)
+            );
+        }
+    }
+}
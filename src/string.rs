@@ -0,0 +1,831 @@
+//! Character-indexed editing of owned strings.
+
+use alloc::string::{Drain, String};
+use bounds;
+use core::iter;
+use core::ops::{Range, RangeBounds};
+use error::{FillError, SubstringError};
+use nth_char_boundary;
+use span::EditDelta;
+#[cfg(feature = "stats")]
+use stats;
+use unit::char_range_to_byte_range;
+#[cfg(feature = "stats")]
+use unit::Unit;
+
+/// Retains only the characters of `s` within `range`, in place.
+///
+/// The range is clamped to the bounds of `s` in the same way as [`substring()`], so afterward `s`
+/// contains exactly what `s.substring()` would have returned. A range resolving to no characters,
+/// including a range whose end is not after its start, leaves `s` empty.
+///
+/// No allocation is performed and the capacity of `s` is preserved. The retained bytes are moved
+/// to the front of the buffer, touching each of them once; no bytes are moved when `range` starts
+/// at the beginning of `s`.
+///
+/// Example:
+/// ```
+/// use substring::keep_char_range;
+///
+/// let mut s = String::from("fõøbα®");
+/// keep_char_range(&mut s, 2..5);
+/// assert_eq!(s, "øbα");
+/// ```
+///
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+pub fn keep_char_range<R: RangeBounds<usize>>(s: &mut String, range: R) {
+    let resolved = bounds::resolve(&range);
+    let start = resolved.start;
+    let end = resolved.end;
+    let bytes = char_range_to_byte_range(s, start, end);
+    #[cfg(feature = "stats")]
+    stats::observe(s, Unit::Chars, start, end, &bytes);
+    s.truncate(bytes.end);
+    if bytes.start > 0 {
+        s.drain(..bytes.start);
+    }
+}
+
+/// Returns a copy of `template` with each character range in `fields` replaced by its value.
+///
+/// A value with fewer characters than its range is padded at the end with `fill`, so the result
+/// has exactly as many characters as `template`. Fields may be given in any order.
+///
+/// Returns an error if a range is reversed or extends past the end of `template`, if a value has
+/// more characters than its range, or if the ranges of two fields overlap. Empty ranges do not
+/// overlap any other range.
+///
+/// The result is allocated once.
+///
+/// Example:
+/// ```
+/// use substring::fill_ranges;
+///
+/// let template = "NAME:______ ID:____";
+/// assert_eq!(
+///     fill_ranges(template, &[(15..19, "42"), (5..11, "Jürgen")], '0'),
+///     Ok(String::from("NAME:Jürgen ID:4200"))
+/// );
+/// ```
+pub fn fill_ranges(
+    template: &str,
+    fields: &[(Range<usize>, &str)],
+    fill: char,
+) -> Result<String, FillError> {
+    let char_len = template.chars().count();
+    let mut capacity = template.len();
+    for (field, (range, value)) in fields.iter().enumerate() {
+        if range.start > range.end {
+            return Err(FillError::StartAfterEnd { field });
+        }
+        if range.end > char_len {
+            return Err(FillError::OutOfBounds { field, char_len });
+        }
+        let value_len = value.chars().count();
+        let range_len = range.end - range.start;
+        if value_len > range_len {
+            return Err(FillError::ValueTooLong {
+                field,
+                value_len,
+                range_len,
+            });
+        }
+        capacity += value.len() + (range_len - value_len) * fill.len_utf8();
+    }
+    for (first, (a, _)) in fields.iter().enumerate() {
+        for (offset, (b, _)) in fields[first + 1..].iter().enumerate() {
+            if a.start < a.end && b.start < b.end && a.start < b.end && b.start < a.end {
+                return Err(FillError::Overlapping {
+                    first,
+                    second: first + 1 + offset,
+                });
+            }
+        }
+    }
+
+    let mut result = String::with_capacity(capacity);
+    let mut indices = template.char_indices();
+    let mut char_pos = 0;
+    let mut byte_pos = 0;
+    // Advances the walk to the character index `target`, returning its byte index.
+    let mut advance = |target: usize, char_pos: &mut usize| {
+        if target > *char_pos {
+            byte_pos = indices
+                .nth(target - *char_pos - 1)
+                .map_or(template.len(), |(index, c)| index + c.len_utf8());
+            *char_pos = target;
+        }
+        byte_pos
+    };
+    // Empty ranges hold no characters, so they are skipped. The remaining ranges do not overlap,
+    // so each has a distinct start and they are visited in order of it.
+    let mut copied = 0;
+    let mut last_start = None;
+    loop {
+        let next = fields
+            .iter()
+            .filter(|(range, _)| {
+                range.start < range.end && last_start.map_or(true, |last| range.start > last)
+            })
+            .min_by_key(|(range, _)| range.start);
+        let (range, value) = match next {
+            Some(field) => field,
+            None => break,
+        };
+        let start = advance(range.start, &mut char_pos);
+        result.push_str(&template[copied..start]);
+        result.push_str(value);
+        for _ in value.chars().count()..range.end - range.start {
+            result.push(fill);
+        }
+        copied = advance(range.end, &mut char_pos);
+        last_start = Some(range.start);
+    }
+    result.push_str(&template[copied..]);
+    Ok(result)
+}
+
+/// Replaces the characters of `s` within `range` with `replacement`, returning a description of
+/// the edit.
+///
+/// The range is clamped to the bounds of `s` in the same way as [`substring()`]. The returned
+/// [`EditDelta`] can be used to adjust other spans within `s` with [`Span::apply_delta()`].
+///
+/// Example:
+/// ```
+/// use substring::replace_substring_tracked;
+///
+/// let mut s = String::from("fõøbα®");
+/// let delta = replace_substring_tracked(&mut s, 1..3, "o");
+/// assert_eq!(s, "fobα®");
+/// assert_eq!(delta.new_char_range(), 1..2);
+/// assert_eq!(delta.char_delta(), -1);
+/// assert_eq!(delta.byte_delta(), -3);
+/// ```
+///
+/// [`EditDelta`]: struct.EditDelta.html
+/// [`Span::apply_delta()`]: struct.Span.html#method.apply_delta
+/// [`substring()`]: trait.Substring.html#tymethod.substring
+pub fn replace_substring_tracked<R: RangeBounds<usize>>(
+    s: &mut String,
+    range: R,
+    replacement: &str,
+) -> EditDelta {
+    let resolved = bounds::resolve(&range);
+    let start = resolved.start;
+    let end = resolved.end;
+    let bytes = char_range_to_byte_range(s, start, end);
+    #[cfg(feature = "stats")]
+    stats::observe(s, Unit::Chars, start, end, &bytes);
+
+    let start_char = s[..bytes.start].chars().count();
+    let old_len = s[bytes.clone()].chars().count();
+    let byte_delta = replacement.len() as isize - (bytes.end - bytes.start) as isize;
+    s.replace_range(bytes, replacement);
+    EditDelta::new(start_char, old_len, replacement.chars().count(), byte_delta)
+}
+
+/// Provides character-indexed editing methods for [`String`].
+///
+/// [`String`]: https://doc.rust-lang.org/std/string/struct.String.html
+pub trait StringSubstring {
+    /// Shortens the string to its first `n` characters.
+    ///
+    /// If the string has no more than `n` characters, it is left unchanged. The capacity of the
+    /// string is preserved.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::StringSubstring;
+    ///
+    /// let mut s = String::from("fõøbα®");
+    /// s.truncate_chars(3);
+    /// assert_eq!(s, "fõø");
+    /// ```
+    fn truncate_chars(&mut self, n: usize);
+
+    /// Removes the characters within `range` from the string, returning them as an iterator.
+    ///
+    /// The range is clamped to the bounds of the string in the same way as [`substring()`], so
+    /// this never panics. A range whose end is not after its start removes nothing. As with
+    /// [`String::drain()`], the characters are removed even if the iterator is not consumed.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::StringSubstring;
+    ///
+    /// let mut s = String::from("fõøbα®");
+    /// assert_eq!(s.drain_chars(1..3).collect::<String>(), "õø");
+    /// assert_eq!(s, "fbα®");
+    /// ```
+    ///
+    /// [`String::drain()`]: https://doc.rust-lang.org/std/string/struct.String.html#method.drain
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    fn drain_chars<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_>;
+
+    /// Replaces the characters within `range` with `replacement`.
+    ///
+    /// The range is clamped to the bounds of the string in the same way as [`substring()`]. A
+    /// range whose end is not after its start replaces nothing, so `replacement` is inserted at
+    /// the start of the range, or at the end of the string if the start is past its end. To also
+    /// find how the edit shifted the rest of the string, use [`replace_substring_tracked()`].
+    ///
+    /// Example:
+    /// ```
+    /// use substring::StringSubstring;
+    ///
+    /// let mut s = String::from("fõøbα®");
+    /// s.replace_char_range(2..5, "xyz");
+    /// assert_eq!(s, "fõxyz®");
+    /// ```
+    ///
+    /// [`replace_substring_tracked()`]: fn.replace_substring_tracked.html
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    fn replace_char_range<R: RangeBounds<usize>>(&mut self, range: R, replacement: &str);
+
+    /// Inserts `s` before the character at `char_idx`.
+    ///
+    /// An index past the end of the string appends `s` to it.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::StringSubstring;
+    ///
+    /// let mut s = String::from("fõøbα®");
+    /// s.insert_str_at_char(3, "oo");
+    /// assert_eq!(s, "fõøoobα®");
+    /// ```
+    fn insert_str_at_char(&mut self, char_idx: usize, s: &str);
+
+    /// Inserts `c` before the character at `char_idx`.
+    ///
+    /// An index past the end of the string appends `c` to it.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::StringSubstring;
+    ///
+    /// let mut s = String::from("fõøbα®");
+    /// s.insert_char_at_char(1, '-');
+    /// assert_eq!(s, "f-õøbα®");
+    /// ```
+    fn insert_char_at_char(&mut self, char_idx: usize, c: char);
+
+    /// Inserts `s` before the character at `char_idx`, or returns an error if `char_idx` is past
+    /// the end of the string.
+    ///
+    /// An index equal to the number of characters is not past the end, and appends `s`. Otherwise,
+    /// the error is a [`SubstringError::StartOutOfBounds`], and the string is left unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{StringSubstring, SubstringError};
+    ///
+    /// let mut s = String::from("fõø");
+    /// assert_eq!(s.try_insert_str_at_char(3, "bar"), Ok(()));
+    /// assert_eq!(
+    ///     s.try_insert_str_at_char(7, "!"),
+    ///     Err(SubstringError::StartOutOfBounds {
+    ///         index: 7,
+    ///         char_len: 6
+    ///     })
+    /// );
+    /// assert_eq!(s, "fõøbar");
+    /// ```
+    ///
+    /// [`SubstringError::StartOutOfBounds`]: enum.SubstringError.html#variant.StartOutOfBounds
+    fn try_insert_str_at_char(&mut self, char_idx: usize, s: &str) -> Result<(), SubstringError>;
+
+    /// Converts the string into the characters within `range`, reusing its allocation.
+    ///
+    /// This is [`keep_char_range()`] for an owned string, and gives the same result as
+    /// `s.substring(start, end).to_string()` without allocating a second buffer. The capacity of
+    /// the string is preserved; call [`String::shrink_to_fit()`] on the result to release it.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::StringSubstring;
+    ///
+    /// let s = String::from("fõøbα®");
+    /// assert_eq!(s.into_substring(1..4), "õøb");
+    /// ```
+    ///
+    /// [`String::shrink_to_fit()`]: https://doc.rust-lang.org/std/string/struct.String.html#method.shrink_to_fit
+    /// [`keep_char_range()`]: fn.keep_char_range.html
+    #[must_use]
+    fn into_substring<R: RangeBounds<usize>>(self, range: R) -> String;
+}
+
+impl StringSubstring for String {
+    fn truncate_chars(&mut self, n: usize) {
+        let bytes = char_range_to_byte_range(self, 0, Some(n));
+        #[cfg(feature = "stats")]
+        stats::observe(self, Unit::Chars, 0, Some(n), &bytes);
+        self.truncate(bytes.end);
+    }
+
+    fn drain_chars<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_> {
+        let resolved = bounds::resolve(&range);
+        let start = resolved.start;
+        let end = resolved.end;
+        let bytes = char_range_to_byte_range(self, start, end);
+        #[cfg(feature = "stats")]
+        stats::observe(self, Unit::Chars, start, end, &bytes);
+        self.drain(bytes)
+    }
+
+    fn replace_char_range<R: RangeBounds<usize>>(&mut self, range: R, replacement: &str) {
+        let resolved = bounds::resolve(&range);
+        let start = resolved.start;
+        let end = resolved.end;
+        let bytes = char_range_to_byte_range(self, start, end);
+        #[cfg(feature = "stats")]
+        stats::observe(self, Unit::Chars, start, end, &bytes);
+        self.replace_range(bytes, replacement);
+    }
+
+    fn insert_str_at_char(&mut self, char_idx: usize, s: &str) {
+        let byte = nth_char_boundary(self, char_idx);
+        self.insert_str(byte, s);
+    }
+
+    fn insert_char_at_char(&mut self, char_idx: usize, c: char) {
+        let byte = nth_char_boundary(self, char_idx);
+        self.insert(byte, c);
+    }
+
+    fn try_insert_str_at_char(&mut self, char_idx: usize, s: &str) -> Result<(), SubstringError> {
+        let byte = self
+            .char_indices()
+            .map(|(index, _char)| index)
+            .chain(iter::once(self.len()))
+            .nth(char_idx);
+        match byte {
+            Some(byte) => {
+                self.insert_str(byte, s);
+                Ok(())
+            }
+            None => Err(SubstringError::StartOutOfBounds {
+                index: char_idx,
+                char_len: self.chars().count(),
+            }),
+        }
+    }
+
+    fn into_substring<R: RangeBounds<usize>>(mut self, range: R) -> String {
+        keep_char_range(&mut self, range);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_ranges, keep_char_range, replace_substring_tracked, StringSubstring};
+    use alloc::string::String;
+    use error::{FillError, SubstringError};
+    use span::Span;
+    use std::vec::Vec;
+    use Substring;
+
+    #[test]
+    fn test_keep_char_range() {
+        let mut s = String::from("fõøbα®");
+        keep_char_range(&mut s, 1..4);
+        assert_eq!(s, "õøb");
+    }
+
+    #[test]
+    fn test_keep_char_range_matches_substring() {
+        let original = "fõøbα®";
+        for start in 0..8 {
+            for end in 0..8 {
+                let mut s = String::from(original);
+                keep_char_range(&mut s, start..end);
+                assert_eq!(s, original.substring(start, end));
+            }
+        }
+    }
+
+    #[test]
+    fn test_keep_char_range_preserves_capacity() {
+        let mut s = String::with_capacity(64);
+        s.push_str("fõøbα®");
+        let ptr = s.as_ptr();
+        keep_char_range(&mut s, 3..);
+        assert_eq!(s, "bα®");
+        assert_eq!(s.capacity(), 64);
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_keep_char_range_at_start() {
+        let mut s = String::from("fõøbα®");
+        let capacity = s.capacity();
+        keep_char_range(&mut s, ..2);
+        assert_eq!(s, "fõ");
+        assert_eq!(s.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_keep_char_range_whole_string() {
+        let mut s = String::from("fõøbα®");
+        keep_char_range(&mut s, ..);
+        assert_eq!(s, "fõøbα®");
+        keep_char_range(&mut s, 0..100);
+        assert_eq!(s, "fõøbα®");
+    }
+
+    #[test]
+    fn test_keep_char_range_empty() {
+        let mut s = String::from("fõøbα®");
+        let capacity = s.capacity();
+        let (start, end) = (4, 2);
+        keep_char_range(&mut s, start..end);
+        assert_eq!(s, "");
+        assert_eq!(s.capacity(), capacity);
+
+        let mut s = String::from("fõøbα®");
+        keep_char_range(&mut s, 10..);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_fill_ranges() {
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(1..3, "xy")], ' '),
+            Ok(String::from("fxybα®"))
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_pads() {
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(1..5, "ü")], '·'),
+            Ok(String::from("fü···®"))
+        );
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(1..3, "")], ' '),
+            Ok(String::from("f  bα®"))
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_adjacent() {
+        assert_eq!(
+            fill_ranges("[____|____]", &[(6..10, "βγ"), (1..5, "αβγδ")], '.'),
+            Ok(String::from("[αβγδ|βγ..]"))
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_multibyte_template() {
+        assert_eq!(
+            fill_ranges(
+                "õ___ø___α",
+                &[(0..1, "o"), (1..4, "ab"), (5..8, "cdé"), (8..9, "a")],
+                '-'
+            ),
+            Ok(String::from("oab-øcdéa"))
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_no_fields() {
+        assert_eq!(fill_ranges("fõøbα®", &[], ' '), Ok(String::from("fõøbα®")));
+    }
+
+    #[test]
+    fn test_fill_ranges_empty_ranges() {
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(2..2, ""), (1..3, "xy"), (2..2, "")], ' '),
+            Ok(String::from("fxybα®"))
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_value_too_long() {
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(0..1, "a"), (1..3, "xyz")], ' '),
+            Err(FillError::ValueTooLong {
+                field: 1,
+                value_len: 3,
+                range_len: 2
+            })
+        );
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(3..3, "a")], ' '),
+            Err(FillError::ValueTooLong {
+                field: 0,
+                value_len: 1,
+                range_len: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_overlapping() {
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(0..1, "a"), (3..5, "b"), (4..6, "c")], ' '),
+            Err(FillError::Overlapping {
+                first: 1,
+                second: 2
+            })
+        );
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(2..3, "a"), (0..6, "b")], ' '),
+            Err(FillError::Overlapping {
+                first: 0,
+                second: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_out_of_bounds() {
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(4..7, "a")], ' '),
+            Err(FillError::OutOfBounds {
+                field: 0,
+                char_len: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_start_after_end() {
+        let (start, end) = (3, 2);
+        assert_eq!(
+            fill_ranges("fõøbα®", &[(start..end, "")], ' '),
+            Err(FillError::StartAfterEnd { field: 0 })
+        );
+    }
+
+    #[test]
+    fn test_fill_ranges_single_allocation() {
+        let result = fill_ranges("fõøbα®", &[(1..5, "a")], '®').unwrap();
+        assert_eq!(result, "fa®®®®");
+        assert_eq!(result.capacity(), "fõøbα®".len() + 1 + 3 * 2);
+    }
+
+    #[test]
+    fn test_replace_substring_tracked() {
+        let mut s = String::from("fõøbα®");
+        let delta = replace_substring_tracked(&mut s, 1..3, "ü");
+        assert_eq!(s, "fübα®");
+        assert_eq!(delta.old_char_range(), 1..3);
+        assert_eq!(delta.new_char_range(), 1..2);
+        assert_eq!(delta.char_delta(), -1);
+        assert_eq!(delta.byte_delta(), -2);
+    }
+
+    #[test]
+    fn test_replace_substring_tracked_deltas_differ() {
+        let mut s = String::from("foobar");
+        let delta = replace_substring_tracked(&mut s, 3..4, "😀😀");
+        assert_eq!(s, "foo😀😀ar");
+        assert_eq!(delta.char_delta(), 1);
+        assert_eq!(delta.byte_delta(), 7);
+
+        let mut s = String::from("日本語");
+        let delta = replace_substring_tracked(&mut s, 1..2, "ab");
+        assert_eq!(s, "日ab語");
+        assert_eq!(delta.char_delta(), 1);
+        assert_eq!(delta.byte_delta(), -1);
+    }
+
+    #[test]
+    fn test_replace_substring_tracked_clamped() {
+        let mut s = String::from("fõøbα®");
+        let delta = replace_substring_tracked(&mut s, 4..10, "x");
+        assert_eq!(s, "fõøbx");
+        assert_eq!(delta.old_char_range(), 4..6);
+
+        let mut s = String::from("fõøbα®");
+        let delta = replace_substring_tracked(&mut s, 10.., "x");
+        assert_eq!(s, "fõøbα®x");
+        assert_eq!(delta.old_char_range(), 6..6);
+        assert_eq!(delta.new_char_range(), 6..7);
+    }
+
+    #[test]
+    fn test_replace_substring_tracked_spans() {
+        let original = "fõø bα® ǆ";
+        let spans = [
+            Span::new(0, 2).unwrap(),
+            Span::new(1, 5).unwrap(),
+            Span::new(4, 6).unwrap(),
+            Span::new(5, 9).unwrap(),
+            Span::new(8, 9).unwrap(),
+        ];
+        let mut s = String::from(original);
+        let delta = replace_substring_tracked(&mut s, 3..7, "-😀-");
+        assert_eq!(s, "fõø-😀- ǆ");
+        let texts: Vec<_> = spans
+            .iter()
+            .map(|span| {
+                let span = span.apply_delta(&delta).unwrap();
+                String::from(&s[span.validate(&s).unwrap().byte_range()])
+            })
+            .collect();
+        assert_eq!(texts, ["fõ", "õø", "", " ǆ", "ǆ"]);
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        let mut s = String::from("fõøbα®");
+        let capacity = s.capacity();
+        s.truncate_chars(4);
+        assert_eq!(s, "fõøb");
+        assert_eq!(s.capacity(), capacity);
+        s.truncate_chars(100);
+        assert_eq!(s, "fõøb");
+        s.truncate_chars(0);
+        assert_eq!(s, "");
+    }
+
+    #[test]
+    fn test_truncate_chars_astral() {
+        let mut s = String::from("😀a𝔘b");
+        s.truncate_chars(3);
+        assert_eq!(s, "😀a𝔘");
+    }
+
+    #[test]
+    fn test_drain_chars() {
+        let mut s = String::from("😀a𝔘bõ");
+        assert_eq!(s.drain_chars(2..4).collect::<String>(), "𝔘b");
+        assert_eq!(s, "😀aõ");
+        assert_eq!(s.drain_chars(..1).collect::<String>(), "😀");
+        assert_eq!(s, "aõ");
+    }
+
+    #[test]
+    fn test_drain_chars_past_end() {
+        let mut s = String::from("fõøbα®");
+        assert_eq!(s.drain_chars(4..100).collect::<String>(), "α®");
+        assert_eq!(s, "fõøb");
+        assert_eq!(s.drain_chars(10..).count(), 0);
+        assert_eq!(s, "fõøb");
+    }
+
+    #[test]
+    fn test_drain_chars_inverted() {
+        let mut s = String::from("fõøbα®");
+        let (start, end) = (4, 2);
+        assert_eq!(s.drain_chars(start..end).count(), 0);
+        assert_eq!(s, "fõøbα®");
+    }
+
+    #[test]
+    fn test_drain_chars_not_consumed() {
+        let mut s = String::from("fõøbα®");
+        drop(s.drain_chars(1..=2));
+        assert_eq!(s, "fbα®");
+    }
+
+    #[test]
+    fn test_drain_chars_matches_substring() {
+        let original = "fõøbα®";
+        for start in 0..8 {
+            for end in 0..8 {
+                let mut s = String::from(original);
+                let drained: String = s.drain_chars(start..end).collect();
+                assert_eq!(drained, original.substring(start, end));
+                assert_eq!(s.len() + drained.len(), original.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_replace_char_range() {
+        let mut s = String::from("fõøbα®");
+        s.replace_char_range(1..3, "oo");
+        assert_eq!(s, "foobα®");
+        s.replace_char_range(3..5, "😀😀😀");
+        assert_eq!(s, "foo😀😀😀®");
+    }
+
+    #[test]
+    fn test_replace_char_range_deletion() {
+        let mut s = String::from("fõøbα®");
+        s.replace_char_range(1..=2, "");
+        assert_eq!(s, "fbα®");
+    }
+
+    #[test]
+    fn test_replace_char_range_clamped() {
+        let mut s = String::from("fõøbα®");
+        s.replace_char_range(4..100, "ar");
+        assert_eq!(s, "fõøbar");
+        s.replace_char_range(10..20, "!");
+        assert_eq!(s, "fõøbar!");
+    }
+
+    #[test]
+    fn test_replace_char_range_inverted_inserts_at_start() {
+        let mut s = String::from("fõøbα®");
+        let (start, end) = (3, 1);
+        s.replace_char_range(start..end, "-");
+        assert_eq!(s, "fõø-bα®");
+        s.replace_char_range(2..2, "+");
+        assert_eq!(s, "fõ+ø-bα®");
+    }
+
+    #[test]
+    fn test_replace_char_range_matches_tracked() {
+        let original = "fõøbα®";
+        for start in 0..8 {
+            for end in 0..8 {
+                let mut s = String::from(original);
+                let mut tracked = String::from(original);
+                s.replace_char_range(start..end, "xü");
+                replace_substring_tracked(&mut tracked, start..end, "xü");
+                assert_eq!(s, tracked);
+            }
+        }
+    }
+
+    #[test]
+    fn test_insert_str_at_char() {
+        let mut s = String::from("õø");
+        s.insert_str_at_char(0, "f");
+        assert_eq!(s, "fõø");
+        s.insert_str_at_char(2, "😀");
+        assert_eq!(s, "fõ😀ø");
+        s.insert_str_at_char(4, "b");
+        assert_eq!(s, "fõ😀øb");
+        s.insert_str_at_char(100, "ar");
+        assert_eq!(s, "fõ😀øbar");
+    }
+
+    #[test]
+    fn test_insert_char_at_char() {
+        let mut s = String::from("😀𝔘");
+        s.insert_char_at_char(1, 'a');
+        assert_eq!(s, "😀a𝔘");
+        s.insert_char_at_char(3, 'b');
+        assert_eq!(s, "😀a𝔘b");
+        s.insert_char_at_char(10, 'õ');
+        assert_eq!(s, "😀a𝔘bõ");
+        s.insert_char_at_char(0, 'α');
+        assert_eq!(s, "α😀a𝔘bõ");
+    }
+
+    #[test]
+    fn test_try_insert_str_at_char() {
+        let mut s = String::from("õø");
+        assert_eq!(s.try_insert_str_at_char(1, "-"), Ok(()));
+        assert_eq!(s, "õ-ø");
+        assert_eq!(s.try_insert_str_at_char(3, "!"), Ok(()));
+        assert_eq!(s, "õ-ø!");
+        assert_eq!(
+            s.try_insert_str_at_char(5, "?"),
+            Err(SubstringError::StartOutOfBounds {
+                index: 5,
+                char_len: 4
+            })
+        );
+        assert_eq!(s, "õ-ø!");
+    }
+
+    #[test]
+    fn test_into_substring() {
+        assert_eq!(String::from("fõøbα®").into_substring(1..4), "õøb");
+        assert_eq!(String::from("fõøbα®").into_substring(3..), "bα®");
+        assert_eq!(String::from("fõøbα®").into_substring(..=1), "fõ");
+        assert_eq!(String::from("😀a𝔘b").into_substring(2..10), "𝔘b");
+    }
+
+    #[test]
+    fn test_into_substring_matches_substring() {
+        let original = "fõøbα®";
+        for start in 0..8 {
+            for end in 0..8 {
+                let s = String::from(original).into_substring(start..end);
+                assert_eq!(s, original.substring(start, end));
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_substring_reuses_allocation() {
+        let mut s = String::with_capacity(64);
+        s.push_str("fõøbα®");
+        let ptr = s.as_ptr();
+        let s = s.into_substring(1..5);
+        assert_eq!(s, "õøbα");
+        assert_eq!(s.capacity(), 64);
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_into_substring_empty() {
+        let (start, end) = (4, 2);
+        let s = String::from("fõøbα®");
+        let capacity = s.capacity();
+        let s = s.into_substring(start..end);
+        assert_eq!(s, "");
+        assert_eq!(s.capacity(), capacity);
+        assert_eq!(String::from("fõøbα®").into_substring(10..), "");
+    }
+}
@@ -0,0 +1,85 @@
+//! Mutable substrings.
+
+use bounds;
+use core::ops::RangeBounds;
+use unit::char_range_to_byte_range;
+
+/// Provides a [`substring_mut()`] method.
+///
+/// [`substring_mut()`]: trait.SubstringMut.html#tymethod.substring_mut
+pub trait SubstringMut {
+    /// Obtains a mutable string slice containing the characters within `range`.
+    ///
+    /// The range is a character range, clamped to the bounds of the string as with
+    /// [`substring()`]. A range whose end is not after its start results in an empty string,
+    /// positioned at its start.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::SubstringMut;
+    ///
+    /// let mut s = String::from("fõøbar");
+    /// s.substring_mut(3..).make_ascii_uppercase();
+    /// assert_eq!(s, "fõøBAR");
+    /// ```
+    ///
+    /// [`substring()`]: trait.Substring.html#tymethod.substring
+    #[must_use]
+    fn substring_mut<R: RangeBounds<usize>>(&mut self, range: R) -> &mut str;
+}
+
+impl SubstringMut for str {
+    fn substring_mut<R: RangeBounds<usize>>(&mut self, range: R) -> &mut str {
+        let resolved = bounds::resolve(&range);
+        let bytes = char_range_to_byte_range(self, resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked_mut!(self, bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubstringMut;
+    use std::string::String;
+
+    #[test]
+    fn test_substring_mut() {
+        let mut s = String::from("foobar");
+        assert_eq!(s.substring_mut(2..5), "oba");
+        s.substring_mut(2..5).make_ascii_uppercase();
+        assert_eq!(s, "foOBAr");
+    }
+
+    #[test]
+    fn test_substring_mut_multiple_byte_characters() {
+        let mut s = String::from("fõøbα®");
+        s.substring_mut(..=3).make_ascii_uppercase();
+        assert_eq!(s, "FõøBα®");
+
+        let mut s = String::from("😀a𝔘b");
+        assert_eq!(s.substring_mut(1..3), "a𝔘");
+        s.substring_mut(1..).make_ascii_uppercase();
+        assert_eq!(s, "😀A𝔘B");
+    }
+
+    #[test]
+    fn test_substring_mut_clamped() {
+        let mut s = String::from("foobar");
+        s.substring_mut(3..100).make_ascii_uppercase();
+        assert_eq!(s, "fooBAR");
+        assert_eq!(s.substring_mut(10..20), "");
+    }
+
+    #[test]
+    fn test_substring_mut_empty() {
+        let mut s = String::from("fõøbar");
+        let (start, end) = (4, 2);
+        let expected = s[6..].as_ptr();
+        let empty = s.substring_mut(start..end);
+        assert_eq!(empty, "");
+        assert_eq!(empty.as_ptr(), expected);
+        empty.make_ascii_uppercase();
+        assert_eq!(s.substring_mut(3..3), "");
+        assert_eq!(s, "fõøbar");
+    }
+}
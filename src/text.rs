@@ -0,0 +1,75 @@
+//! Substrings indexed by the most precise unit available.
+
+use bounds;
+use core::ops::RangeBounds;
+use unit::{self, Unit};
+
+/// Returns the unit used by [`text_substring()`].
+///
+/// This is the most precise unit of text the crate has been compiled to support. Currently, this
+/// is always [`Unit::Chars`]. If a more precise unit, such as grapheme clusters, becomes available
+/// behind a feature, enabling that feature will change the unit returned here.
+///
+/// [`text_substring()`]: trait.TextSubstring.html#tymethod.text_substring
+/// [`Unit::Chars`]: enum.Unit.html#variant.Chars
+pub fn active_unit() -> Unit {
+    Unit::Chars
+}
+
+/// Provides a [`text_substring()`] method indexed by the unit returned by [`active_unit()`].
+///
+/// This allows libraries to index text by the most precise unit available without needing to
+/// know which features of this crate are enabled. As a consequence, the results of
+/// [`text_substring()`] depend on the features enabled.
+///
+/// [`active_unit()`]: fn.active_unit.html
+/// [`text_substring()`]: trait.TextSubstring.html#tymethod.text_substring
+pub trait TextSubstring {
+    /// Obtains a string slice containing the units within `range`, where units are those
+    /// returned by [`active_unit()`].
+    ///
+    /// The range is clamped to the bounds of the string, and a range whose end is not after its
+    /// start results in an empty string.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::{active_unit, TextSubstring, Unit};
+    ///
+    /// if active_unit() == Unit::Chars {
+    ///     assert_eq!("a\u{303}b".text_substring(0..1), "a");
+    /// }
+    /// ```
+    ///
+    /// [`active_unit()`]: fn.active_unit.html
+    #[must_use]
+    fn text_substring<R: RangeBounds<usize>>(&self, range: R) -> &str;
+}
+
+impl TextSubstring for str {
+    fn text_substring<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        let resolved = bounds::resolve(&range);
+        let bytes = unit::to_byte_range(self, active_unit(), resolved.start, resolved.end);
+        // SAFETY: `bytes` lies on UTF-8 sequence boundaries within the bounds of `self`.
+        slice_unchecked!(self, bytes.start, bytes.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{active_unit, TextSubstring};
+    use unit::Unit;
+
+    #[test]
+    fn test_active_unit() {
+        assert_eq!(active_unit(), Unit::Chars);
+    }
+
+    #[test]
+    fn test_text_substring_chars() {
+        // "ã" is made up of "a" and a combining tilde.
+        assert_eq!("a\u{303}b".text_substring(0..1), "a");
+        assert_eq!("a\u{303}b".text_substring(1..), "\u{303}b");
+        assert_eq!("fõøbα®".text_substring(2..5), "øbα");
+        assert_eq!("fõøbα®".text_substring(5..10), "®");
+    }
+}
@@ -0,0 +1,336 @@
+//! Truncation of strings to a budget, marked with an ellipsis.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use unit::{Measure, Unit};
+use word::Words;
+
+/// The position at which a [`Truncator`] removes characters.
+///
+/// [`Truncator`]: struct.Truncator.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TruncatePosition {
+    /// Remove characters from the start, keeping the end.
+    Start,
+    /// Remove characters from the middle, keeping both the start and the end.
+    Middle,
+    /// Remove characters from the end, keeping the start.
+    End,
+}
+
+/// A configurable truncation of strings to a budget.
+///
+/// A string exceeding the budget, as measured in the configured [`Unit`], has characters removed
+/// at the configured position and replaced by an ellipsis. The ellipsis counts against the budget
+/// in the same unit, and characters are never split. A string within the budget is borrowed
+/// unchanged.
+///
+/// When measured in [`Unit::Words`], whole words are removed, along with the whitespace and
+/// punctuation separating them from the ellipsis.
+///
+/// By default, strings are measured in [`Unit::Chars`], the budget is unlimited, characters are
+/// removed from the end, and the ellipsis is `"…"`.
+///
+/// Example:
+/// ```
+/// use substring::{TruncatePosition, Truncator, Unit};
+///
+/// let truncator = Truncator::new()
+///     .budget(8)
+///     .position(TruncatePosition::Middle)
+///     .ellipsis("..");
+/// assert_eq!(truncator.truncate("fõøbα®fõøbα®"), "fõø..bα®");
+/// assert_eq!(truncator.unit(Unit::Bytes).truncate("fõøbα®"), "fõ..®");
+/// ```
+///
+/// [`Unit`]: enum.Unit.html
+/// [`Unit::Chars`]: enum.Unit.html#variant.Chars
+/// [`Unit::Words`]: enum.Unit.html#variant.Words
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Truncator<'e> {
+    unit: Unit,
+    budget: usize,
+    position: TruncatePosition,
+    ellipsis: &'e str,
+}
+
+impl<'e> Truncator<'e> {
+    /// Creates a truncator with the default configuration.
+    pub fn new() -> Self {
+        Truncator {
+            unit: Unit::Chars,
+            budget: usize::max_value(),
+            position: TruncatePosition::End,
+            ellipsis: "…",
+        }
+    }
+
+    /// Measures strings and the ellipsis in `unit`.
+    pub fn unit(self, unit: Unit) -> Self {
+        Truncator { unit, ..self }
+    }
+
+    /// Truncates strings to at most `budget` units, including the ellipsis.
+    pub fn budget(self, budget: usize) -> Self {
+        Truncator { budget, ..self }
+    }
+
+    /// Removes characters at `position`.
+    pub fn position(self, position: TruncatePosition) -> Self {
+        Truncator { position, ..self }
+    }
+
+    /// Marks truncated strings with `ellipsis`.
+    ///
+    /// If the ellipsis alone exceeds the budget, truncated strings consist of as much of the
+    /// ellipsis as fits within the budget.
+    pub fn ellipsis(self, ellipsis: &'e str) -> Self {
+        Truncator { ellipsis, ..self }
+    }
+
+    /// Returns the cost of `s` in the configured unit.
+    fn measure(&self, s: &str) -> usize {
+        match (self.unit, self.unit.measure()) {
+            (Unit::Bytes, _) => s.len(),
+            (_, Measure::PerChar(cost)) => s.chars().map(cost).sum(),
+            (_, Measure::Words) => Words::new(s).count(),
+        }
+    }
+
+    /// Returns the byte length of the longest prefix of `s` costing at most `budget`, along with
+    /// its cost.
+    ///
+    /// In words, the prefix ends at the end of its last word.
+    fn prefix(&self, s: &str, budget: usize) -> (usize, usize) {
+        let cost_of = match self.unit.measure() {
+            Measure::PerChar(cost_of) => cost_of,
+            Measure::Words => {
+                return Words::new(s)
+                    .take(budget)
+                    .enumerate()
+                    .last()
+                    .map_or((0, 0), |(index, word)| (word.end, index + 1));
+            }
+        };
+        let mut cost = 0;
+        for (index, c) in s.char_indices() {
+            if cost + cost_of(c) > budget {
+                return (index, cost);
+            }
+            cost += cost_of(c);
+        }
+        (s.len(), cost)
+    }
+
+    /// Returns the byte index of the longest suffix of `s` costing at most `budget`.
+    ///
+    /// In words, the suffix starts at the start of its first word, unless it is all of `s`.
+    fn suffix(&self, s: &str, budget: usize) -> usize {
+        let cost_of = match self.unit.measure() {
+            Measure::PerChar(cost_of) => cost_of,
+            Measure::Words => {
+                return match Words::new(s).count().checked_sub(budget) {
+                    Some(skipped) => Words::new(s)
+                        .nth(skipped)
+                        .map_or(s.len(), |word| word.start),
+                    None => 0,
+                };
+            }
+        };
+        let mut cost = 0;
+        for (index, c) in s.char_indices().rev() {
+            if cost + cost_of(c) > budget {
+                return index + c.len_utf8();
+            }
+            cost += cost_of(c);
+        }
+        0
+    }
+
+    /// Truncates `s` to the budget.
+    ///
+    /// `s` is borrowed unchanged if it is within the budget.
+    pub fn truncate<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.measure(s) <= self.budget {
+            return Cow::Borrowed(s);
+        }
+        let ellipsis_cost = self.measure(self.ellipsis);
+        if ellipsis_cost > self.budget {
+            let (end, _) = self.prefix(self.ellipsis, self.budget);
+            return Cow::Owned(String::from(&self.ellipsis[..end]));
+        }
+        let budget = self.budget - ellipsis_cost;
+
+        let (head, tail) = match self.position {
+            TruncatePosition::Start => (0, self.suffix(s, budget)),
+            TruncatePosition::Middle => {
+                let (head, head_cost) = self.prefix(s, budget - budget / 2);
+                (head, self.suffix(&s[head..], budget - head_cost) + head)
+            }
+            TruncatePosition::End => (self.prefix(s, budget).0, s.len()),
+        };
+        let mut result = String::with_capacity(head + self.ellipsis.len() + s.len() - tail);
+        result.push_str(&s[..head]);
+        result.push_str(self.ellipsis);
+        result.push_str(&s[tail..]);
+        Cow::Owned(result)
+    }
+}
+
+impl<'e> Default for Truncator<'e> {
+    fn default() -> Self {
+        Truncator::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TruncatePosition, Truncator};
+    use alloc::borrow::Cow;
+    use unit::Unit;
+
+    const UNITS: [Unit; 5] = [
+        Unit::Bytes,
+        Unit::Chars,
+        Unit::Utf16,
+        Unit::Width,
+        Unit::Words,
+    ];
+    const POSITIONS: [TruncatePosition; 3] = [
+        TruncatePosition::Start,
+        TruncatePosition::Middle,
+        TruncatePosition::End,
+    ];
+
+    #[test]
+    fn test_default() {
+        let s = "fõøbα®";
+        assert_eq!(Truncator::new().truncate(s), s);
+        assert_eq!(Truncator::new().budget(4).truncate(s), "fõø…");
+        assert_eq!(Truncator::default(), Truncator::new());
+    }
+
+    #[test]
+    fn test_matrix() {
+        // "a😀ö😀b" is 12 bytes, 5 characters, 7 UTF-16 code units, and 7 columns long.
+        let s = "a😀ö😀b";
+        let expected = [
+            (Unit::Bytes, TruncatePosition::Start, "..b"),
+            (Unit::Bytes, TruncatePosition::Middle, "a..b"),
+            (Unit::Bytes, TruncatePosition::End, "a.."),
+            (Unit::Chars, TruncatePosition::Start, s),
+            (Unit::Chars, TruncatePosition::Middle, s),
+            (Unit::Chars, TruncatePosition::End, s),
+            (Unit::Utf16, TruncatePosition::Start, "..ö😀b"),
+            (Unit::Utf16, TruncatePosition::Middle, "a..😀b"),
+            (Unit::Utf16, TruncatePosition::End, "a😀ö.."),
+            (Unit::Width, TruncatePosition::Start, "..ö😀b"),
+            (Unit::Width, TruncatePosition::Middle, "a..😀b"),
+            (Unit::Width, TruncatePosition::End, "a😀ö.."),
+        ];
+        for &(unit, position, result) in expected.iter() {
+            let truncated = Truncator::new()
+                .unit(unit)
+                .budget(6)
+                .position(position)
+                .ellipsis("..")
+                .truncate(s);
+            assert_eq!(truncated, result, "{:?} {:?}", unit, position);
+        }
+    }
+
+    #[test]
+    fn test_within_budget() {
+        let s = "a😀ö😀b";
+        for &unit in UNITS.iter() {
+            for &position in POSITIONS.iter() {
+                let truncator = Truncator::new().unit(unit).position(position);
+                for &budget in [15, 100].iter() {
+                    match truncator.budget(budget).truncate(s) {
+                        Cow::Borrowed(borrowed) => assert_eq!(borrowed, s),
+                        Cow::Owned(_) => panic!("expected a borrowed string"),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_within_budget_exactly() {
+        for &(unit, budget) in [
+            (Unit::Bytes, 12),
+            (Unit::Chars, 5),
+            (Unit::Utf16, 7),
+            (Unit::Width, 7),
+            (Unit::Words, 3),
+        ]
+        .iter()
+        {
+            let truncator = Truncator::new().unit(unit);
+            if let Cow::Owned(_) = truncator.budget(budget).truncate("a😀ö😀b") {
+                panic!("expected a borrowed string");
+            }
+            assert!(truncator.budget(budget - 1).truncate("a😀ö😀b") != "a😀ö😀b");
+        }
+    }
+
+    #[test]
+    fn test_never_exceeds_budget() {
+        let s = "a😀ö😀bfõø日本 don't.stop";
+        for &unit in UNITS.iter() {
+            let truncator = Truncator::new().unit(unit);
+            for &position in POSITIONS.iter() {
+                for budget in 0..30 {
+                    let truncated = truncator.position(position).budget(budget).truncate(s);
+                    assert!(truncator.measure(&truncated) <= budget);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_words() {
+        let s = "The quick, brown fox.";
+        let truncator = Truncator::new().unit(Unit::Words).budget(3);
+        assert_eq!(truncator.truncate(s), "The quick, brown…");
+        assert_eq!(
+            truncator.position(TruncatePosition::Start).truncate(s),
+            "…quick, brown fox."
+        );
+        assert_eq!(
+            truncator.position(TruncatePosition::Middle).truncate(s),
+            "The quick…fox."
+        );
+        assert_eq!(
+            truncator.ellipsis(" [more]").budget(2).truncate(s),
+            "The [more]"
+        );
+        assert_eq!(truncator.ellipsis("[more]").budget(0).truncate(s), "");
+    }
+
+    #[test]
+    fn test_ellipsis_exceeds_budget() {
+        let truncator = Truncator::new().ellipsis("[…]").budget(2);
+        assert_eq!(truncator.truncate("foobar"), "[…");
+        assert_eq!(truncator.unit(Unit::Bytes).truncate("foobar"), "[");
+        assert_eq!(truncator.budget(0).truncate("foobar"), "");
+    }
+
+    #[test]
+    fn test_empty_ellipsis() {
+        let truncator = Truncator::new().ellipsis("").budget(3);
+        assert_eq!(truncator.truncate("fõøbα®"), "fõø");
+        assert_eq!(
+            truncator
+                .position(TruncatePosition::Start)
+                .truncate("fõøbα®"),
+            "bα®"
+        );
+        assert_eq!(
+            truncator
+                .position(TruncatePosition::Middle)
+                .truncate("fõøbα®"),
+            "fõ®"
+        );
+    }
+}
@@ -0,0 +1,396 @@
+//! Units by which strings can be indexed.
+
+use boundary::{snap_byte_range, Snap};
+#[cfg(test)]
+use core::cell::Cell;
+use core::ops::Range;
+#[cfg(feature = "stats")]
+use stats;
+use utf16::{self, SurrogatePolicy};
+use width;
+use word;
+
+#[cfg(test)]
+thread_local! {
+    /// The number of character walks performed by the current thread, for testing that methods
+    /// avoid walking.
+    pub(crate) static WALKS: Cell<usize> = Cell::new(0);
+}
+
+/// A unit by which a string can be indexed.
+///
+/// Whichever unit is chosen, a bound landing inside a single character (such as a byte index in
+/// the middle of a multi-byte UTF-8 sequence, a UTF-16 index between the two halves of a surrogate
+/// pair, or a column in the middle of a wide character) is snapped inward, so that partially
+/// covered characters are excluded.
+///
+/// More variants may be added in the future; matching on this enum should therefore include a
+/// wildcard arm.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(rustc_1_40, non_exhaustive)]
+pub enum Unit {
+    /// Bytes of the UTF-8 encoding.
+    Bytes,
+    /// Characters, as in [*Unicode Scalar Values*].
+    ///
+    /// [*Unicode Scalar Values*]: http://www.unicode.org/glossary/#unicode_scalar_value
+    Chars,
+    /// Code units of the UTF-16 encoding.
+    Utf16,
+    /// Display columns, as counted by [`width_substring()`].
+    ///
+    /// Zero width characters, such as combining marks, are kept with the character they follow.
+    ///
+    /// [`width_substring()`]: trait.CharSubstring.html#tymethod.width_substring
+    Width,
+    /// Words, as identified by [`word_substring()`].
+    ///
+    /// A range of words spans from the start of its first word to the end of its last, so the
+    /// whitespace and punctuation around it are excluded. Converting a byte range to words
+    /// includes only the words lying entirely within it.
+    ///
+    /// [`word_substring()`]: trait.WordSubstring.html#tymethod.word_substring
+    Words,
+}
+
+/// How lengths are measured in a [`Unit`].
+///
+/// [`Unit`]: enum.Unit.html
+#[derive(Clone, Copy)]
+pub(crate) enum Measure {
+    /// Every character is measured on its own, as the number of units returned by the function.
+    PerChar(fn(char) -> usize),
+    /// Words are measured, each spanning any number of characters.
+    Words,
+}
+
+impl Unit {
+    /// Returns how lengths are measured in this unit.
+    pub(crate) fn measure(self) -> Measure {
+        match self {
+            Unit::Bytes => Measure::PerChar(char::len_utf8),
+            Unit::Chars => Measure::PerChar(|_| 1),
+            Unit::Utf16 => Measure::PerChar(char::len_utf16),
+            Unit::Width => Measure::PerChar(width::char_width),
+            Unit::Words => Measure::Words,
+        }
+    }
+}
+
+/// Returns the byte range of the characters within `start..end`, clamped to the bounds of `s`.
+///
+/// An unbounded `end` extends to the end of `s`. If `end` is not after `start`, the returned range
+/// is empty and positioned at the start boundary.
+pub(crate) fn char_range_to_byte_range(s: &str, start: usize, end: Option<usize>) -> Range<usize> {
+    #[cfg(test)]
+    WALKS.with(|walks| walks.set(walks.get() + 1));
+
+    let (start_byte, end_byte) = ::char_range_bytes(s, start, end);
+    start_byte..end_byte
+}
+
+/// Returns the byte range of the bytes within `start..end`, snapped inward to character
+/// boundaries and clamped to the bounds of `s`.
+pub(crate) fn bytes_to_byte_range(s: &str, start: usize, end: Option<usize>) -> Range<usize> {
+    snap_byte_range(s, start, end, Snap::Inward)
+}
+
+/// Returns the byte range of the UTF-16 code units within `start..end`, snapped inward to
+/// character boundaries and clamped to the bounds of `s`.
+fn utf16_range_to_byte_range(s: &str, start: usize, end: Option<usize>) -> Range<usize> {
+    match utf16::utf16_range_to_byte_range(s, start, end, SurrogatePolicy::SnapIn) {
+        Ok(bytes) => bytes,
+        Err(_) => unreachable!("snapping inward never fails"),
+    }
+}
+
+/// Returns the byte range of the `unit`s within `start..end`, clamped to the bounds of `s`.
+pub(crate) fn to_byte_range(s: &str, unit: Unit, start: usize, end: Option<usize>) -> Range<usize> {
+    let bytes = match unit {
+        Unit::Bytes => bytes_to_byte_range(s, start, end),
+        Unit::Chars => char_range_to_byte_range(s, start, end),
+        Unit::Utf16 => utf16_range_to_byte_range(s, start, end),
+        Unit::Width => width::width_range_to_byte_range(s, start, end),
+        Unit::Words => word::word_range_to_byte_range(s, start, end),
+    };
+    #[cfg(feature = "stats")]
+    stats::observe(s, unit, start, end, &bytes);
+    bytes
+}
+
+/// Returns the range of `unit`s covering the bytes within `start..end`.
+///
+/// The byte range is first snapped inward to character boundaries and clamped to the bounds of
+/// `s`.
+pub(crate) fn from_byte_range(
+    s: &str,
+    unit: Unit,
+    start: usize,
+    end: Option<usize>,
+) -> Range<usize> {
+    let byte_range = bytes_to_byte_range(s, start, end);
+    // SAFETY: `byte_range` lies on character boundaries within the bounds of `s`.
+    let prefix = slice_unchecked!(s, 0, byte_range.start);
+    let covered = slice_unchecked!(s, byte_range.start, byte_range.end);
+    let (start, len) = match unit {
+        Unit::Bytes => (prefix.len(), covered.len()),
+        Unit::Chars => (prefix.chars().count(), covered.chars().count()),
+        Unit::Utf16 => (
+            prefix.chars().map(char::len_utf16).sum(),
+            covered.chars().map(char::len_utf16).sum(),
+        ),
+        Unit::Width => (
+            prefix.chars().map(width::char_width).sum(),
+            covered.chars().map(width::char_width).sum(),
+        ),
+        Unit::Words => {
+            let words = word::byte_range_to_word_range(s, &byte_range);
+            (words.start, words.len())
+        }
+    };
+    start..(start + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_byte_range, to_byte_range, Measure, Unit};
+    use {CharSubstring, Substring, WordSubstring};
+
+    const CORPUS: [&str; 7] = [
+        "",
+        "foobar",
+        "fõøbα®",
+        "ã",
+        "😀a𝔘b",
+        "日本語😀",
+        "\u{301}e\u{301}日\u{200d}",
+    ];
+
+    #[test]
+    fn test_measure() {
+        for &c in ['a', 'õ', '日', '😀', '\u{301}'].iter() {
+            let lens = [Unit::Bytes, Unit::Chars, Unit::Utf16, Unit::Width]
+                .iter()
+                .map(|unit| match unit.measure() {
+                    Measure::PerChar(len_of) => len_of(c),
+                    Measure::Words => panic!("{:?} is measured per character", unit),
+                })
+                .collect::<::std::vec::Vec<_>>();
+            assert_eq!(
+                lens,
+                [c.len_utf8(), 1, c.len_utf16(), ::width::char_width(c)]
+            );
+        }
+        match Unit::Words.measure() {
+            Measure::Words => {}
+            Measure::PerChar(_) => panic!("words are not measured per character"),
+        }
+    }
+
+    #[test]
+    fn test_chars_matches_substring() {
+        for s in CORPUS.iter() {
+            let char_len = s.chars().count();
+            for start in 0..(char_len + 2) {
+                for end in 0..(char_len + 2) {
+                    let range = to_byte_range(s, Unit::Chars, start, Some(end));
+                    assert_eq!(&s[range], s.substring(start, end));
+                }
+                let range = to_byte_range(s, Unit::Chars, start, None);
+                assert_eq!(&s[range], s.substring(start, char_len));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bytes_matches_boundaries() {
+        for s in CORPUS.iter() {
+            for start in 0..(s.len() + 2) {
+                for end in 0..(s.len() + 2) {
+                    let range = to_byte_range(s, Unit::Bytes, start, Some(end));
+                    assert!(s.is_char_boundary(range.start));
+                    assert!(s.is_char_boundary(range.end));
+                    assert!(range.start >= start.min(s.len()));
+                    assert!(range.end <= end.max(range.start));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bytes_snaps_inward() {
+        // "õ" occupies bytes 1..3 and "ø" occupies bytes 3..5.
+        assert_eq!(to_byte_range("fõøbα®", Unit::Bytes, 2, Some(5)), 3..5);
+        assert_eq!(to_byte_range("fõøbα®", Unit::Bytes, 1, Some(4)), 1..3);
+        assert_eq!(to_byte_range("fõøbα®", Unit::Bytes, 2, Some(4)), 3..3);
+        assert_eq!(to_byte_range("fõøbα®", Unit::Bytes, 5, None), 5..10);
+    }
+
+    #[test]
+    fn test_utf16_matches_encoding() {
+        for s in CORPUS.iter() {
+            // The UTF-16 index at which each character begins, paired with its byte index.
+            let mut offsets = s
+                .char_indices()
+                .scan(0, |units, (index, c)| {
+                    let offset = *units;
+                    *units += c.len_utf16();
+                    Some((offset, index))
+                })
+                .collect::<::std::vec::Vec<_>>();
+            offsets.push((s.encode_utf16().count(), s.len()));
+            for &(start_units, start_byte) in offsets.iter() {
+                for &(end_units, end_byte) in offsets.iter() {
+                    let range = to_byte_range(s, Unit::Utf16, start_units, Some(end_units));
+                    if end_byte < start_byte {
+                        assert_eq!(range, start_byte..start_byte);
+                    } else {
+                        assert_eq!(range, start_byte..end_byte);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_utf16_snaps_inward() {
+        // "😀" occupies UTF-16 units 0..2 and bytes 0..4.
+        assert_eq!(to_byte_range("😀a𝔘b", Unit::Utf16, 1, Some(3)), 4..5);
+        assert_eq!(to_byte_range("😀a𝔘b", Unit::Utf16, 0, Some(4)), 0..5);
+        assert_eq!(to_byte_range("😀a𝔘b", Unit::Utf16, 1, Some(2)), 4..4);
+        assert_eq!(to_byte_range("😀a𝔘b", Unit::Utf16, 3, None), 5..10);
+        assert_eq!(to_byte_range("😀a𝔘b", Unit::Utf16, 10, None), 10..10);
+    }
+
+    #[test]
+    fn test_width_matches_width_substring() {
+        for s in CORPUS.iter() {
+            for start in 0..(2 * s.len() + 2) {
+                for end in 0..(2 * s.len() + 2) {
+                    let range = to_byte_range(s, Unit::Width, start, Some(end));
+                    assert_eq!(&s[range], s.width_substring(start..end));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_width_snaps_inward() {
+        // "日" occupies columns 0..2 and bytes 0..3, and "\u{301}" occupies no columns.
+        let s = "日a\u{301}本";
+        assert_eq!(to_byte_range(s, Unit::Width, 1, Some(3)), 3..6);
+        assert_eq!(to_byte_range(s, Unit::Width, 0, Some(4)), 0..6);
+        assert_eq!(to_byte_range(s, Unit::Width, 1, Some(2)), 3..3);
+        assert_eq!(to_byte_range(s, Unit::Width, 3, None), 6..9);
+    }
+
+    #[test]
+    fn test_width_round_trip() {
+        // Zero width characters have no column of their own, so only ranges of columns round
+        // trip.
+        for s in CORPUS.iter() {
+            for start in 0..(2 * s.len() + 2) {
+                for end in start..(2 * s.len() + 2) {
+                    let bytes = to_byte_range(s, Unit::Width, start, Some(end));
+                    let columns = from_byte_range(s, Unit::Width, bytes.start, Some(bytes.end));
+                    if bytes.start < bytes.end {
+                        assert!(start <= columns.start && columns.end <= end);
+                    }
+                    assert_eq!(
+                        to_byte_range(s, Unit::Width, columns.start, Some(columns.end)),
+                        bytes
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_words_matches_word_substring() {
+        for s in CORPUS
+            .iter()
+            .chain([" The quick, brown fox ", "don't 3.14."].iter())
+        {
+            for start in 0..8 {
+                for end in 0..8 {
+                    let range = to_byte_range(s, Unit::Words, start, Some(end));
+                    assert_eq!(&s[range], s.word_substring(start..end));
+                }
+                let range = to_byte_range(s, Unit::Words, start, None);
+                assert_eq!(&s[range], s.word_substring(start..));
+            }
+        }
+    }
+
+    #[test]
+    fn test_words_round_trip() {
+        // Whitespace and punctuation belong to no word, so only ranges of words round trip.
+        for s in CORPUS.iter().chain([" The quick, brown fox "].iter()) {
+            for start in 0..8 {
+                for end in start..8 {
+                    let bytes = to_byte_range(s, Unit::Words, start, Some(end));
+                    let words = from_byte_range(s, Unit::Words, bytes.start, Some(bytes.end));
+                    if bytes.start < bytes.end {
+                        let count = from_byte_range(s, Unit::Words, 0, None).end;
+                        assert_eq!(words, start..end.min(count));
+                    }
+                    assert_eq!(
+                        to_byte_range(s, Unit::Words, words.start, Some(words.end)),
+                        bytes
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_byte_range_round_trip() {
+        for s in CORPUS.iter() {
+            for &unit in [Unit::Bytes, Unit::Chars, Unit::Utf16].iter() {
+                for start in 0..(s.len() + 2) {
+                    for end in start..(s.len() + 2) {
+                        let units = from_byte_range(s, unit, start, Some(end));
+                        let bytes = to_byte_range(s, unit, units.start, Some(units.end));
+                        assert_eq!(bytes, to_byte_range(s, Unit::Bytes, start, Some(end)));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_byte_range() {
+        assert_eq!(from_byte_range("😀a𝔘b", Unit::Chars, 4, Some(9)), 1..3);
+        assert_eq!(from_byte_range("😀a𝔘b", Unit::Utf16, 4, Some(9)), 2..5);
+        assert_eq!(from_byte_range("😀a𝔘b", Unit::Utf16, 1, None), 2..6);
+        assert_eq!(from_byte_range("😀a𝔘b", Unit::Bytes, 1, Some(7)), 4..5);
+        assert_eq!(from_byte_range("😀a𝔘b", Unit::Width, 4, Some(9)), 2..4);
+        assert_eq!(from_byte_range("fõø b, α®", Unit::Words, 0, Some(6)), 0..1);
+        assert_eq!(from_byte_range("fõø b, α®", Unit::Words, 1, None), 1..3);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_fixtures_round_trip() {
+        use fixtures;
+
+        for &unit in [Unit::Bytes, Unit::Chars, Unit::Utf16].iter() {
+            let fixtures = [
+                fixtures::every_scalar_width(0, 64),
+                fixtures::combining_storm(0, 8, 7),
+                fixtures::emoji_zoo(0, 16),
+                fixtures::rtl_mix(0, 64),
+                fixtures::worst_case_for(unit, 64),
+            ];
+            for s in fixtures.iter() {
+                for start in 0..(s.len() + 2) {
+                    for end in start..(s.len() + 2) {
+                        let units = from_byte_range(s, unit, start, Some(end));
+                        let bytes = to_byte_range(s, unit, units.start, Some(units.end));
+                        assert_eq!(bytes, to_byte_range(s, Unit::Bytes, start, Some(end)));
+                    }
+                }
+            }
+        }
+    }
+}
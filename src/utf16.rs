@@ -0,0 +1,217 @@
+//! Resolution of UTF-16 code unit indices within UTF-8 text.
+
+use core::ops::Range;
+use core::str::CharIndices;
+use error::SubstringError;
+
+/// How a UTF-16 index landing between the two halves of a surrogate pair is resolved.
+///
+/// The default policy is [`SnapIn`], which is the policy used wherever a [`Unit::Utf16`] range is
+/// accepted without a policy.
+///
+/// [`SnapIn`]: #variant.SnapIn
+/// [`Unit::Utf16`]: enum.Unit.html#variant.Utf16
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SurrogatePolicy {
+    /// Include the character encoded by the surrogate pair.
+    SnapOut,
+    /// Exclude the character encoded by the surrogate pair.
+    SnapIn,
+    /// Return a [`SubstringError::SplitSurrogatePair`] naming the index.
+    ///
+    /// [`SubstringError::SplitSurrogatePair`]: enum.SubstringError.html#variant.SplitSurrogatePair
+    Error,
+}
+
+impl Default for SurrogatePolicy {
+    fn default() -> Self {
+        SurrogatePolicy::SnapIn
+    }
+}
+
+/// The byte position of a UTF-16 index.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Position {
+    /// The index lies on a character boundary, or past the end of the string.
+    Boundary(usize),
+    /// The index lies between the two halves of the surrogate pair encoding the character within
+    /// `before..after`.
+    Inside { before: usize, after: usize },
+}
+
+/// A forward walk over UTF-8 text, tracking the number of UTF-16 code units passed.
+struct Utf16Cursor<'a> {
+    chars: CharIndices<'a>,
+    len: usize,
+    /// The number of code units before `byte`.
+    units: usize,
+    /// The byte index of the next character.
+    byte: usize,
+    /// The byte index of the last character passed.
+    previous_byte: usize,
+}
+
+impl<'a> Utf16Cursor<'a> {
+    fn new(s: &'a str) -> Self {
+        Utf16Cursor {
+            chars: s.char_indices(),
+            len: s.len(),
+            units: 0,
+            byte: 0,
+            previous_byte: 0,
+        }
+    }
+
+    /// Returns the byte position of the code unit index `target`.
+    ///
+    /// Targets must not decrease between calls by more than the halves of a single surrogate pair.
+    fn seek(&mut self, target: usize) -> Position {
+        while self.units < target {
+            match self.chars.next() {
+                Some((index, c)) => {
+                    self.previous_byte = index;
+                    self.units += c.len_utf16();
+                    self.byte = index + c.len_utf8();
+                }
+                None => return Position::Boundary(self.len),
+            }
+        }
+        if self.units > target {
+            Position::Inside {
+                before: self.previous_byte,
+                after: self.byte,
+            }
+        } else {
+            Position::Boundary(self.byte)
+        }
+    }
+}
+
+/// Resolves `position`, snapping it forward or backward out of a surrogate pair according to
+/// `policy`.
+fn resolve(
+    position: Position,
+    index: usize,
+    forward_in: bool,
+    policy: SurrogatePolicy,
+) -> Result<usize, SubstringError> {
+    match position {
+        Position::Boundary(byte) => Ok(byte),
+        Position::Inside { before, after } => match policy {
+            SurrogatePolicy::SnapIn if forward_in => Ok(after),
+            SurrogatePolicy::SnapIn => Ok(before),
+            SurrogatePolicy::SnapOut if forward_in => Ok(before),
+            SurrogatePolicy::SnapOut => Ok(after),
+            SurrogatePolicy::Error => Err(SubstringError::SplitSurrogatePair { index }),
+        },
+    }
+}
+
+/// Returns the byte range of the UTF-16 code units within `start..end`, clamped to the bounds of
+/// `s`, with indices inside surrogate pairs resolved according to `policy`.
+///
+/// If `end` is not after `start`, the returned range is empty and positioned at the start, and
+/// `end` is not checked against `policy`.
+pub(crate) fn utf16_range_to_byte_range(
+    s: &str,
+    start: usize,
+    end: Option<usize>,
+    policy: SurrogatePolicy,
+) -> Result<Range<usize>, SubstringError> {
+    let mut cursor = Utf16Cursor::new(s);
+    let start_byte = resolve(cursor.seek(start), start, true, policy)?;
+    let end_byte = match end {
+        Some(end) if end <= start => start_byte,
+        Some(end) => resolve(cursor.seek(end), end, false, policy)?,
+        None => s.len(),
+    };
+    if end_byte < start_byte {
+        Ok(start_byte..start_byte)
+    } else {
+        Ok(start_byte..end_byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{utf16_range_to_byte_range, SurrogatePolicy};
+    use error::SubstringError;
+
+    /// "😀" occupies UTF-16 units 0..2 and bytes 0..4, and "𝔘" occupies units 3..5 and bytes
+    /// 5..9.
+    const S: &str = "😀a𝔘b";
+
+    #[test]
+    fn test_boundaries_unaffected_by_policy() {
+        for &policy in [
+            SurrogatePolicy::SnapOut,
+            SurrogatePolicy::SnapIn,
+            SurrogatePolicy::Error,
+        ]
+        .iter()
+        {
+            assert_eq!(utf16_range_to_byte_range(S, 0, Some(3), policy), Ok(0..5));
+            assert_eq!(utf16_range_to_byte_range(S, 2, Some(5), policy), Ok(4..9));
+            assert_eq!(utf16_range_to_byte_range(S, 3, None, policy), Ok(5..10));
+            assert_eq!(utf16_range_to_byte_range(S, 10, None, policy), Ok(10..10));
+            assert_eq!(utf16_range_to_byte_range(S, 2, Some(10), policy), Ok(4..10));
+        }
+    }
+
+    #[test]
+    fn test_policy_matrix() {
+        // Each case is a range with its bounds on either half of the two surrogate pairs, followed
+        // by the expected results for `SnapOut`, `SnapIn`, and `Error`.
+        let cases = [
+            (1, Some(3), Ok(0..5), Ok(4..5), Err(1)),
+            (0, Some(4), Ok(0..9), Ok(0..5), Err(4)),
+            (1, Some(4), Ok(0..9), Ok(4..5), Err(1)),
+            (2, Some(4), Ok(4..9), Ok(4..5), Err(4)),
+            (4, Some(6), Ok(5..10), Ok(9..10), Err(4)),
+            (4, None, Ok(5..10), Ok(9..10), Err(4)),
+            (1, Some(2), Ok(0..4), Ok(4..4), Err(1)),
+            (4, Some(5), Ok(5..9), Ok(9..9), Err(4)),
+        ];
+        for (start, end, snap_out, snap_in, error) in cases.iter() {
+            assert_eq!(
+                utf16_range_to_byte_range(S, *start, *end, SurrogatePolicy::SnapOut),
+                *snap_out
+            );
+            assert_eq!(
+                utf16_range_to_byte_range(S, *start, *end, SurrogatePolicy::SnapIn),
+                *snap_in
+            );
+            assert_eq!(
+                utf16_range_to_byte_range(S, *start, *end, SurrogatePolicy::Error),
+                error
+                    .clone()
+                    .map_err(|index| SubstringError::SplitSurrogatePair { index })
+            );
+        }
+    }
+
+    #[test]
+    fn test_both_bounds_inside_same_pair() {
+        assert_eq!(
+            utf16_range_to_byte_range(S, 1, Some(1), SurrogatePolicy::SnapOut),
+            Ok(0..0)
+        );
+        assert_eq!(
+            utf16_range_to_byte_range(S, 1, Some(1), SurrogatePolicy::SnapIn),
+            Ok(4..4)
+        );
+    }
+
+    #[test]
+    fn test_reversed_end_not_checked() {
+        assert_eq!(
+            utf16_range_to_byte_range(S, 3, Some(1), SurrogatePolicy::Error),
+            Ok(5..5)
+        );
+    }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(SurrogatePolicy::default(), SurrogatePolicy::SnapIn);
+    }
+}
@@ -0,0 +1,138 @@
+//! Substrings of UTF-16 encoded text.
+
+use bounds;
+use core::ops::RangeBounds;
+
+fn is_high_surrogate(unit: u16) -> bool {
+    unit >= 0xd800 && unit < 0xdc00
+}
+
+fn is_low_surrogate(unit: u16) -> bool {
+    unit >= 0xdc00 && unit < 0xe000
+}
+
+/// Returns the index of the code unit at which the character with index `n` begins, counting
+/// from the code unit index `from`, or the length of `units` if there are no more than `n` such
+/// characters.
+fn nth_char_start(units: &[u16], from: usize, n: usize) -> usize {
+    let mut index = from;
+    for _ in 0..n {
+        if index == units.len() {
+            break;
+        }
+        let paired = is_high_surrogate(units[index])
+            && units
+                .get(index + 1)
+                .map_or(false, |&unit| is_low_surrogate(unit));
+        index += if paired { 2 } else { 1 };
+    }
+    index
+}
+
+/// Provides a [`wide_substring()`] method for UTF-16 encoded text.
+///
+/// This is implemented for slices of `u16` code units, such as those underlying the `U16Str` and
+/// `U16CStr` types of the [`widestring`] crate, which convert to and from slices with
+/// `as_slice()` and `from_slice()`.
+///
+/// [`wide_substring()`]: trait.WideSubstring.html#tymethod.wide_substring
+/// [`widestring`]: https://docs.rs/widestring
+pub trait WideSubstring {
+    /// Obtains the code units of the characters within `range`.
+    ///
+    /// Indices count characters, so a surrogate pair counts as one character and is never split.
+    /// A surrogate which is not part of a pair counts as one character of its own, rather than
+    /// being an error. The range is clamped to the number of characters, and a range whose end is
+    /// not after its start results in an empty slice, positioned at the start.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::WideSubstring;
+    ///
+    /// let units: Vec<u16> = "a😀b".encode_utf16().collect();
+    /// assert_eq!(units.wide_substring(1..2), [0xd83d, 0xde00]);
+    /// assert_eq!(units.wide_substring(2..), ['b' as u16]);
+    /// ```
+    #[must_use]
+    fn wide_substring<R: RangeBounds<usize>>(&self, range: R) -> &[u16];
+}
+
+impl WideSubstring for [u16] {
+    fn wide_substring<R: RangeBounds<usize>>(&self, range: R) -> &[u16] {
+        let resolved = bounds::resolve(&range);
+        let start = nth_char_start(self, 0, resolved.start);
+        let end = match resolved.end {
+            Some(end) if end <= resolved.start => start,
+            Some(end) => nth_char_start(self, start, end - resolved.start),
+            None => self.len(),
+        };
+        &self[start..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WideSubstring;
+    use std::vec::Vec;
+    use Substring;
+
+    fn units(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    #[test]
+    fn test_wide_substring() {
+        let s = units("fõøbα®");
+        assert_eq!(s.wide_substring(2..5), &units("øbα")[..]);
+        assert_eq!(s.wide_substring(..), &s[..]);
+        assert_eq!(s.wide_substring(4..10), &units("α®")[..]);
+    }
+
+    #[test]
+    fn test_surrogate_pairs_at_bounds() {
+        // "😀" occupies code units 1..3, and "𝔘" occupies code units 4..6.
+        let s = units("a😀b𝔘c");
+        assert_eq!(s.wide_substring(1..2), &s[1..3]);
+        assert_eq!(s.wide_substring(1..4), &s[1..6]);
+        assert_eq!(s.wide_substring(2..3), &s[3..4]);
+        assert_eq!(s.wide_substring(3..), &s[4..]);
+        assert_eq!(s.wide_substring(..=1), &s[..3]);
+    }
+
+    #[test]
+    fn test_lone_surrogates() {
+        // A lone low surrogate, a lone high surrogate followed by a non-surrogate, and a lone high
+        // surrogate at the end.
+        let s = [0xdc00, 0x61, 0xd800, 0x62, 0xd83d, 0xde00, 0xd800];
+        assert_eq!(s.wide_substring(0..1), [0xdc00]);
+        assert_eq!(s.wide_substring(1..3), [0x61, 0xd800]);
+        assert_eq!(s.wide_substring(4..5), [0xd83d, 0xde00]);
+        assert_eq!(s.wide_substring(5..), [0xd800]);
+        assert_eq!(s.wide_substring(6..), []);
+    }
+
+    #[test]
+    fn test_empty() {
+        let s = units("a😀b");
+        let (start, end) = (2, 1);
+        assert_eq!(s.wide_substring(start..end), []);
+        assert_eq!(s.wide_substring(start..end).as_ptr(), s[3..].as_ptr());
+        assert_eq!(s.wide_substring(10..), []);
+        assert_eq!(s.wide_substring(10..).as_ptr(), s[4..].as_ptr());
+    }
+
+    #[test]
+    fn test_matches_str() {
+        for s in ["", "foobar", "fõøbα®", "😀a𝔘b"].iter() {
+            let wide = units(s);
+            for start in 0..7 {
+                for end in 0..7 {
+                    assert_eq!(
+                        wide.wide_substring(start..end),
+                        &units(s.substring(start, end))[..]
+                    );
+                }
+            }
+        }
+    }
+}
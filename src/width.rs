@@ -0,0 +1,130 @@
+//! Resolution of display column indices within text.
+//!
+//! Column widths approximate those given by [UAX #11] for a terminal: East Asian wide and
+//! fullwidth characters, including common emoji, occupy two columns; combining marks, variation
+//! selectors, zero width characters, and control characters occupy none; and every other
+//! character occupies one.
+//!
+//! [UAX #11]: https://www.unicode.org/reports/tr11/
+
+use core::ops::Range;
+use wrap::{is_extending, is_ideographic};
+
+/// Returns the number of columns occupied by `c` when displayed.
+pub(crate) fn char_width(c: char) -> usize {
+    if is_extending(c) || c.is_control() {
+        return 0;
+    }
+    match c {
+        '\u{200b}'..='\u{200f}' | '\u{2060}'..='\u{2064}' | '\u{feff}' => 0,
+        '\u{1100}'..='\u{115f}'
+        | '\u{2e80}'..='\u{2fff}'
+        | '\u{3100}'..='\u{33ff}'
+        | '\u{a000}'..='\u{a4cf}'
+        | '\u{ac00}'..='\u{d7a3}'
+        | '\u{fe30}'..='\u{fe4f}'
+        | '\u{ffe0}'..='\u{ffe6}'
+        | '\u{1f300}'..='\u{1f64f}'
+        | '\u{1f900}'..='\u{1f9ff}'
+        | '\u{30000}'..='\u{3fffd}' => 2,
+        _ if is_ideographic(c) => 2,
+        _ => 1,
+    }
+}
+
+/// Returns the byte range of the columns within `start..end`, clamped to the bounds of `s`.
+///
+/// A wide character cut by `start` is skipped, and a wide character cut by `end` is excluded,
+/// along with any zero width characters following either. Zero width characters immediately
+/// following the last included character are included. If `end` is not after `start`, the
+/// returned range is empty and positioned at the start.
+pub(crate) fn width_range_to_byte_range(s: &str, start: usize, end: Option<usize>) -> Range<usize> {
+    let empty = end.map_or(false, |end| end <= start);
+    let end = if empty { None } else { end };
+    let mut column = 0;
+    let mut start_byte = None;
+    let mut end_byte = s.len();
+    for (index, c) in s.char_indices() {
+        let width = char_width(c);
+        if start_byte.is_none() && column >= start && (width > 0 || start == 0) {
+            start_byte = Some(index);
+            if empty {
+                break;
+            }
+        }
+        match end {
+            Some(end) if column + width > end => {
+                end_byte = index;
+                break;
+            }
+            _ => {}
+        }
+        column += width;
+    }
+    // Every character before the start column fits before the end column, so the end cannot be
+    // reached before the start.
+    let start_byte = start_byte.unwrap_or(end_byte);
+    if empty {
+        start_byte..start_byte
+    } else {
+        start_byte..end_byte
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{char_width, width_range_to_byte_range};
+
+    #[test]
+    fn test_char_width() {
+        assert_eq!(char_width('a'), 1);
+        assert_eq!(char_width('õ'), 1);
+        assert_eq!(char_width('日'), 2);
+        assert_eq!(char_width('한'), 2);
+        assert_eq!(char_width('Ａ'), 2);
+        assert_eq!(char_width('ｱ'), 1);
+        assert_eq!(char_width('😀'), 2);
+        assert_eq!(char_width('\u{301}'), 0);
+        assert_eq!(char_width('\u{200d}'), 0);
+        assert_eq!(char_width('\t'), 0);
+    }
+
+    #[test]
+    fn test_boundaries() {
+        // "日" occupies columns 0..2 and bytes 0..3, and "a" occupies column 2 and bytes 3..4.
+        let s = "日a本";
+        assert_eq!(width_range_to_byte_range(s, 0, Some(2)), 0..3);
+        assert_eq!(width_range_to_byte_range(s, 2, Some(3)), 3..4);
+        assert_eq!(width_range_to_byte_range(s, 2, None), 3..7);
+        assert_eq!(width_range_to_byte_range(s, 0, Some(10)), 0..7);
+        assert_eq!(width_range_to_byte_range(s, 10, None), 7..7);
+    }
+
+    #[test]
+    fn test_cut_wide_characters() {
+        let s = "日a本";
+        assert_eq!(width_range_to_byte_range(s, 1, Some(3)), 3..4);
+        assert_eq!(width_range_to_byte_range(s, 0, Some(4)), 0..4);
+        assert_eq!(width_range_to_byte_range(s, 1, Some(4)), 3..4);
+        assert_eq!(width_range_to_byte_range(s, 4, None), 7..7);
+        assert_eq!(width_range_to_byte_range(s, 1, Some(2)), 3..3);
+    }
+
+    #[test]
+    fn test_zero_width() {
+        let s = "e\u{301}日\u{301}x";
+        assert_eq!(width_range_to_byte_range(s, 0, Some(1)), 0..3);
+        assert_eq!(width_range_to_byte_range(s, 1, None), 3..9);
+        assert_eq!(width_range_to_byte_range(s, 0, Some(2)), 0..3);
+        assert_eq!(width_range_to_byte_range(s, 2, None), 8..9);
+        assert_eq!(width_range_to_byte_range("\u{301}a", 0, Some(1)), 0..3);
+    }
+
+    #[test]
+    fn test_empty() {
+        let s = "日a本";
+        assert_eq!(width_range_to_byte_range(s, 2, Some(2)), 3..3);
+        assert_eq!(width_range_to_byte_range(s, 3, Some(1)), 4..4);
+        assert_eq!(width_range_to_byte_range("", 0, None), 0..0);
+    }
+}
@@ -0,0 +1,367 @@
+//! Substrings indexed by words.
+//!
+//! Words follow a simplified subset of [UAX #29]: a word is a run of letters and digits, which
+//! may contain an apostrophe or full stop between two letters or digits, as in "don't" or "3.14".
+//! Each ideograph is a word of its own. Combining marks belong to the word they follow.
+//! Whitespace, punctuation, and symbols separate words and are never words themselves.
+//!
+//! [UAX #29]: https://www.unicode.org/reports/tr29/
+
+use bounds;
+use core::ops::{Range, RangeBounds};
+use segment::Segmenter;
+use wrap::{is_extending, is_ideographic};
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() && !is_ideographic(c)
+}
+
+/// Returns whether `c` may join two runs of letters and digits into a single word.
+fn is_mid_word(c: char) -> bool {
+    c == '\'' || c == '’' || c == '.'
+}
+
+/// An iterator over the byte ranges of the words of a string.
+pub(crate) struct Words<'a> {
+    s: &'a str,
+    /// The byte index from which to search for the next word.
+    byte: usize,
+}
+
+impl<'a> Words<'a> {
+    pub(crate) fn new(s: &'a str) -> Self {
+        Words { s, byte: 0 }
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        // SAFETY: `self.byte` always lies on a UTF-8 sequence boundary within `self.s`.
+        let rest = slice_unchecked!(self.s, self.byte, self.s.len());
+        let (offset, first) = rest
+            .char_indices()
+            .find(|&(_, c)| is_word_char(c) || is_ideographic(c))?;
+        let start = self.byte + offset;
+
+        let mut end = start + first.len_utf8();
+        // SAFETY: `end` lies on a UTF-8 sequence boundary, after `first`.
+        let mut chars = slice_unchecked!(self.s, end, self.s.len()).chars();
+        while let Some(c) = chars.clone().next() {
+            let continues = if is_ideographic(first) {
+                is_extending(c)
+            } else if is_mid_word(c) {
+                let mut lookahead = chars.clone();
+                lookahead.next();
+                lookahead.next().map_or(false, is_word_char)
+            } else {
+                is_word_char(c) || is_extending(c)
+            };
+            if !continues {
+                break;
+            }
+            chars.next();
+            end += c.len_utf8();
+        }
+
+        self.byte = end;
+        Some(start..end)
+    }
+}
+
+/// The progress of finding the starts of words one character at a time.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum WordState {
+    /// Outside of any word.
+    Outside,
+    /// Within a run of letters and digits.
+    Run,
+    /// After a run of letters and digits followed by a character which may join it to another.
+    AfterMid,
+    /// Within an ideograph.
+    Ideograph,
+}
+
+impl WordState {
+    /// Advances past `c`, returning the new state along with whether `c` starts a word.
+    ///
+    /// Feeding every character of a string in order finds the same word starts as [`Words`].
+    ///
+    /// [`Words`]: struct.Words.html
+    pub(crate) fn next(self, c: char) -> (WordState, bool) {
+        match self {
+            WordState::Run if is_word_char(c) || is_extending(c) => (WordState::Run, false),
+            WordState::Run if is_mid_word(c) => (WordState::AfterMid, false),
+            WordState::AfterMid if is_word_char(c) => (WordState::Run, false),
+            WordState::Ideograph if is_extending(c) => (WordState::Ideograph, false),
+            _ if is_ideographic(c) => (WordState::Ideograph, true),
+            _ if is_word_char(c) => (WordState::Run, true),
+            _ => (WordState::Outside, false),
+        }
+    }
+}
+
+/// Provides a [`word_substring()`] method indexed by words.
+///
+/// See the [module documentation] for how words are identified.
+///
+/// [module documentation]: index.html
+/// [`word_substring()`]: trait.WordSubstring.html#tymethod.word_substring
+pub trait WordSubstring {
+    /// Obtains a string slice spanning the words within `range`.
+    ///
+    /// The slice extends from the start of the first word to the end of the last word, including
+    /// any separators between them, but no separators before the first word or after the last.
+    /// Punctuation is not a word, so it is only included where it lies between two words. The
+    /// range is clamped to the number of words in the string, and a range whose end is not after
+    /// its start results in an empty string, positioned at the start of the word it starts at.
+    ///
+    /// Example:
+    /// ```
+    /// use substring::WordSubstring;
+    ///
+    /// assert_eq!("The quick brown fox".word_substring(1..3), "quick brown");
+    /// assert_eq!("Hello, world!".word_substring(..), "Hello, world");
+    /// ```
+    #[must_use]
+    fn word_substring<R: RangeBounds<usize>>(&self, range: R) -> &str;
+}
+
+/// Returns the byte range of `s` from the start of the first segment in `range` to the end of the
+/// last, where `segments` yields the byte ranges of the segments of `s` in order.
+///
+/// The range is clamped to the number of segments. If it is empty, the returned range is empty and
+/// positioned at the start of the segment it starts at, or at the end of `s`.
+pub(crate) fn segment_byte_range<I, R>(s: &str, mut segments: I, range: &R) -> Range<usize>
+where
+    I: Iterator<Item = Range<usize>>,
+    R: RangeBounds<usize>,
+{
+    let resolved = bounds::resolve(range);
+    let start_byte = match segments.nth(resolved.start) {
+        Some(segment) => segment,
+        None => s.len()..s.len(),
+    };
+    let end_byte = match resolved.end {
+        Some(end) if end <= resolved.start => start_byte.start,
+        Some(end) => segments
+            .take(end - resolved.start - 1)
+            .last()
+            .map_or(start_byte.end, |segment| segment.end),
+        None => segments
+            .last()
+            .map_or(start_byte.end, |segment| segment.end),
+    };
+    start_byte.start..end_byte
+}
+
+/// Returns the byte range of the words within `start..end`, clamped to the number of words in `s`.
+///
+/// An unbounded `end` extends to the last word of `s`.
+pub(crate) fn word_range_to_byte_range(s: &str, start: usize, end: Option<usize>) -> Range<usize> {
+    match end {
+        Some(end) => segment_byte_range(s, Words::new(s), &(start..end)),
+        None => segment_byte_range(s, Words::new(s), &(start..)),
+    }
+}
+
+/// Returns the range of the words of `s` lying entirely within the bytes `range`.
+///
+/// If no word lies entirely within `range`, the returned range is empty and positioned at the
+/// first word starting at or after `range.start`.
+pub(crate) fn byte_range_to_word_range(s: &str, range: &Range<usize>) -> Range<usize> {
+    let mut start = 0;
+    let mut end = 0;
+    for word in Words::new(s) {
+        if word.start < range.start {
+            start += 1;
+        }
+        if word.end > range.end {
+            break;
+        }
+        end += 1;
+    }
+    start..end.max(start)
+}
+
+/// Returns the slice of `s` within the [`segment_byte_range()`] of `range`.
+///
+/// [`segment_byte_range()`]: fn.segment_byte_range.html
+pub(crate) fn segment_substring<'a, I, R>(s: &'a str, segments: I, range: &R) -> &'a str
+where
+    I: Iterator<Item = Range<usize>>,
+    R: RangeBounds<usize>,
+{
+    let byte_range = segment_byte_range(s, segments, range);
+    // SAFETY: Both byte indices are the bounds of segments of `s`, or are its length.
+    slice_unchecked!(s, byte_range.start, byte_range.end)
+}
+
+impl WordSubstring for str {
+    fn word_substring<R: RangeBounds<usize>>(&self, range: R) -> &str {
+        segment_substring(self, Words::new(self), &range)
+    }
+}
+
+/// A [`Segmenter`] dividing a string into its words.
+///
+/// See the [module documentation] for how words are identified.
+///
+/// Example:
+/// ```
+/// use substring::{ByWords, CharSubstring};
+///
+/// assert_eq!("The quick brown fox".substring_by(ByWords, 1..3), "quick brown");
+/// ```
+///
+/// [module documentation]: index.html
+/// [`Segmenter`]: trait.Segmenter.html
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ByWords;
+
+impl Segmenter for ByWords {
+    fn next_segment(&self, s: &str, from: usize) -> Option<Range<usize>> {
+        if !s.is_char_boundary(from) {
+            return None;
+        }
+        Words { s, byte: from }.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        byte_range_to_word_range, word_range_to_byte_range, ByWords, Segmenter, WordState,
+        WordSubstring, Words,
+    };
+    use std::vec::Vec;
+
+    fn words(s: &str) -> Vec<&str> {
+        Words::new(s).map(|range| &s[range]).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words("The quick brown fox"),
+            ["The", "quick", "brown", "fox"]
+        );
+        assert_eq!(words("  Hello,  world!  "), ["Hello", "world"]);
+        assert_eq!(words(""), Vec::<&str>::new());
+        assert_eq!(words(" -- "), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_words_mid_word_punctuation() {
+        assert_eq!(words("don't stop"), ["don't", "stop"]);
+        assert_eq!(words("pi is 3.14."), ["pi", "is", "3.14"]);
+        assert_eq!(words("well-known 'quote'"), ["well", "known", "quote"]);
+    }
+
+    #[test]
+    fn test_words_combining_marks() {
+        assert_eq!(words("cafe\u{301} au lait"), ["cafe\u{301}", "au", "lait"]);
+    }
+
+    #[test]
+    fn test_words_ideographs() {
+        assert_eq!(words("日本語 text"), ["日", "本", "語", "text"]);
+    }
+
+    #[test]
+    fn test_word_state_matches_words() {
+        for s in [
+            "The quick brown fox",
+            "don't stop 'til 3.14..",
+            "a'日\u{301}b.本\u{300}c’d",
+            "cafe\u{301}\u{301} au-lait.",
+            "\u{301}x. .y",
+        ]
+        .iter()
+        {
+            let mut state = WordState::Outside;
+            let mut starts = Vec::new();
+            for (index, c) in s.char_indices() {
+                let (next, starts_word) = state.next(c);
+                if starts_word {
+                    starts.push(index);
+                }
+                state = next;
+            }
+            assert_eq!(
+                starts,
+                Words::new(s).map(|word| word.start).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_word_range_to_byte_range() {
+        let s = " The quick, brown fox ";
+        assert_eq!(word_range_to_byte_range(s, 1, Some(3)), 5..17);
+        assert_eq!(word_range_to_byte_range(s, 2, None), 12..21);
+        assert_eq!(word_range_to_byte_range(s, 2, Some(2)), 12..12);
+        assert_eq!(word_range_to_byte_range(s, 3, Some(1)), 18..18);
+        assert_eq!(word_range_to_byte_range(s, 4, Some(6)), 22..22);
+    }
+
+    #[test]
+    fn test_byte_range_to_word_range() {
+        let s = " The quick, brown fox ";
+        assert_eq!(byte_range_to_word_range(s, &(5..17)), 1..3);
+        assert_eq!(byte_range_to_word_range(s, &(4..18)), 1..3);
+        assert_eq!(byte_range_to_word_range(s, &(6..17)), 2..3);
+        assert_eq!(byte_range_to_word_range(s, &(6..16)), 2..2);
+        assert_eq!(byte_range_to_word_range(s, &(0..22)), 0..4);
+        assert_eq!(byte_range_to_word_range(s, &(22..22)), 4..4);
+    }
+
+    #[test]
+    fn test_word_substring() {
+        let s = "The quick brown fox";
+        assert_eq!(s.word_substring(1..3), "quick brown");
+        assert_eq!(s.word_substring(..), s);
+        assert_eq!(s.word_substring(2..), "brown fox");
+        assert_eq!(s.word_substring(..=0), "The");
+        assert_eq!(s.word_substring(3..10), "fox");
+    }
+
+    #[test]
+    fn test_word_substring_multiple_spaces_and_tabs() {
+        let s = "\t one  \t two\t\tthree   ";
+        assert_eq!(s.word_substring(..), "one  \t two\t\tthree");
+        assert_eq!(s.word_substring(1..2), "two");
+        assert_eq!(s.word_substring(0..2), "one  \t two");
+    }
+
+    #[test]
+    fn test_word_substring_non_ascii() {
+        let s = "Größe und Maß, ὈΔΥΣΣΕΎΣ";
+        assert_eq!(s.word_substring(1..3), "und Maß");
+        assert_eq!(s.word_substring(2..), "Maß, ὈΔΥΣΣΕΎΣ");
+    }
+
+    #[test]
+    fn test_word_substring_empty() {
+        let s = "The quick brown fox";
+        let (start, end) = (3, 1);
+        assert_eq!(s.word_substring(start..end), "");
+        assert_eq!(s.word_substring(start..end).as_ptr(), s[16..].as_ptr());
+        assert_eq!(s.word_substring(2..2), "");
+        assert_eq!(s.word_substring(4..), "");
+        assert_eq!(s.word_substring(4..).as_ptr(), s[19..].as_ptr());
+        assert_eq!("".word_substring(..), "");
+    }
+
+    #[test]
+    fn test_by_words() {
+        let s = "fõø bαr";
+        assert_eq!(ByWords.next_segment(s, 0), Some(0..5));
+        assert_eq!(ByWords.next_segment(s, 5), Some(6..10));
+        assert_eq!(ByWords.next_segment(s, 7), Some(7..10));
+        assert_eq!(ByWords.next_segment(s, 10), None);
+        assert_eq!(ByWords.next_segment(s, 2), None);
+        assert_eq!(ByWords.next_segment(s, 11), None);
+    }
+}
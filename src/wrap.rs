@@ -0,0 +1,318 @@
+//! Line break opportunities, word ends, and greedy wrapping.
+//!
+//! Break opportunities follow a simplified subset of [UAX #14]: a line may break after a run of
+//! spaces, after a hyphen-minus, or after a soft hyphen, and must break after a newline. No break
+//! is offered before a combining mark, so that common grapheme clusters are kept together.
+//!
+//! [UAX #14]: https://www.unicode.org/reports/tr14/
+
+use core::iter::Peekable;
+use core::str::CharIndices;
+
+const SOFT_HYPHEN: char = '\u{ad}';
+
+fn is_space(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
+
+/// Returns whether `c` extends the preceding character, as common combining marks, variation
+/// selectors, and the zero width joiner do.
+pub(crate) fn is_extending(c: char) -> bool {
+    match c {
+        '\u{300}'..='\u{36f}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | '\u{200d}'
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{fe00}'..='\u{fe0f}'
+        | '\u{fe20}'..='\u{fe2f}'
+        | '\u{e0100}'..='\u{e01ef}' => true,
+        _ => false,
+    }
+}
+
+/// Returns whether a line may break between `c` and `next`.
+fn is_break_between(c: char, next: char) -> bool {
+    if c == '\n' {
+        return true;
+    }
+    if is_extending(next) {
+        return false;
+    }
+    if is_space(c) {
+        !is_space(next)
+    } else {
+        c == '-' || c == SOFT_HYPHEN
+    }
+}
+
+/// Returns whether `c` is written without spaces between words, as Han ideographs and kana are.
+pub(crate) fn is_ideographic(c: char) -> bool {
+    match c {
+        '\u{3000}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff00}'..='\u{ff60}'
+        | '\u{20000}'..='\u{2ffff}' => true,
+        _ => false,
+    }
+}
+
+/// Returns whether a word ends between `c` and `next`.
+///
+/// A word ends before a space or newline, after a hyphen-minus, and on either side of an
+/// ideograph. As with line breaks, no word ends before a combining mark.
+fn is_word_end(c: char, next: char) -> bool {
+    if is_extending(next) || is_space(c) || c == '\n' {
+        return false;
+    }
+    is_space(next) || next == '\n' || c == '-' || is_ideographic(c) || is_ideographic(next)
+}
+
+/// Returns the byte index at which `s` is cut to at most `max_chars` characters, preferring the
+/// end of a word.
+///
+/// If the first word alone exceeds `max_chars`, the cut is made after exactly `max_chars`
+/// characters. No characters past the budget are examined, except to check whether the budget
+/// itself falls at the end of a word.
+pub(crate) fn word_truncation_end(s: &str, max_chars: usize) -> usize {
+    let mut previous = None;
+    let mut last_word_end = None;
+    for (count, (index, c)) in s.char_indices().enumerate() {
+        let is_end = previous.map_or(false, |previous| is_word_end(previous, c));
+        if count == max_chars {
+            return if is_end {
+                index
+            } else {
+                last_word_end.unwrap_or(index)
+            };
+        }
+        if is_end {
+            last_word_end = Some(index);
+        }
+        previous = Some(c);
+    }
+    s.len()
+}
+
+/// An iterator over the character indices at which a line may break.
+///
+/// This struct is created by the [`break_opportunities()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`break_opportunities()`]: trait.CharSubstring.html#tymethod.break_opportunities
+#[derive(Clone, Debug)]
+pub struct BreakOpportunities<'a> {
+    chars: Peekable<CharIndices<'a>>,
+    /// The character index of the next character to be examined.
+    index: usize,
+}
+
+impl<'a> BreakOpportunities<'a> {
+    pub(crate) fn new(s: &'a str) -> Self {
+        BreakOpportunities {
+            chars: s.char_indices().peekable(),
+            index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for BreakOpportunities<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while let Some((_, c)) = self.chars.next() {
+            self.index += 1;
+            if let Some(&(_, next)) = self.chars.peek() {
+                if is_break_between(c, next) {
+                    return Some(self.index);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// An iterator over the lines of a string wrapped greedily at its break opportunities.
+///
+/// This struct is created by the [`wrap_at_opportunities()`] method on [`CharSubstring`].
+///
+/// [`CharSubstring`]: trait.CharSubstring.html
+/// [`wrap_at_opportunities()`]: trait.CharSubstring.html#tymethod.wrap_at_opportunities
+#[derive(Clone, Debug)]
+pub struct WrapAtOpportunities<'a> {
+    remaining: &'a str,
+    width: usize,
+}
+
+impl<'a> WrapAtOpportunities<'a> {
+    pub(crate) fn new(s: &'a str, width: usize) -> Self {
+        WrapAtOpportunities {
+            remaining: s,
+            width: if width == 0 { 1 } else { width },
+        }
+    }
+
+    /// Returns the byte index at which the next line ends.
+    fn line_end(&self) -> usize {
+        let mut chars = self.remaining.char_indices().peekable();
+        let mut count = 0;
+        // The byte index of the last break opportunity.
+        let mut last_break = None;
+        // The byte index of the last character that does not extend its predecessor.
+        let mut cluster_start = 0;
+        while let Some((index, c)) = chars.next() {
+            if !is_extending(c) {
+                cluster_start = index;
+            }
+            // Trailing spaces hang past the end of a line rather than counting against its width.
+            if count >= self.width && !is_space(c) && c != '\n' {
+                if let Some(last_break) = last_break {
+                    return last_break;
+                }
+                if !is_extending(c) {
+                    return index;
+                }
+                if cluster_start > 0 {
+                    return cluster_start;
+                }
+            }
+            count += 1;
+            let end = index + c.len_utf8();
+            if c == '\n' {
+                return end;
+            }
+            if let Some(&(_, next)) = chars.peek() {
+                if is_break_between(c, next) {
+                    last_break = Some(end);
+                }
+            }
+        }
+        self.remaining.len()
+    }
+}
+
+impl<'a> Iterator for WrapAtOpportunities<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let end = self.line_end();
+        let line = &self.remaining[..end];
+        self.remaining = &self.remaining[end..];
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{word_truncation_end, BreakOpportunities, WrapAtOpportunities};
+    use std::vec::Vec;
+
+    fn opportunities(s: &str) -> Vec<usize> {
+        BreakOpportunities::new(s).collect()
+    }
+
+    fn wrap(s: &str, width: usize) -> Vec<&str> {
+        WrapAtOpportunities::new(s, width).collect()
+    }
+
+    #[test]
+    fn test_opportunities_spaces() {
+        assert_eq!(opportunities("foo bar baz"), [4, 8]);
+        assert_eq!(opportunities("foo   bar"), [6]);
+        assert_eq!(opportunities("foo "), []);
+    }
+
+    #[test]
+    fn test_opportunities_hyphens() {
+        assert_eq!(opportunities("well-known"), [5]);
+        assert_eq!(
+            opportunities("in\u{ad}di\u{ad}vis\u{ad}i\u{ad}ble"),
+            [3, 6, 10, 12]
+        );
+    }
+
+    #[test]
+    fn test_opportunities_newlines() {
+        assert_eq!(opportunities("foo\n\nbar"), [4, 5]);
+        assert_eq!(opportunities("foo\r\nbar"), [5]);
+    }
+
+    #[test]
+    fn test_opportunities_not_before_combining_mark() {
+        assert_eq!(opportunities("a- \u{303}b"), [2]);
+    }
+
+    #[test]
+    fn test_wrap_spaces() {
+        assert_eq!(wrap("foo bar baz", 7), ["foo bar ", "baz"]);
+        assert_eq!(wrap("foo bar baz", 3), ["foo ", "bar ", "baz"]);
+        assert_eq!(wrap("foo     bar", 4), ["foo     ", "bar"]);
+    }
+
+    #[test]
+    fn test_wrap_hyphens() {
+        assert_eq!(wrap("a well-known fact", 8), ["a well-", "known ", "fact"]);
+        assert_eq!(
+            wrap("in\u{ad}di\u{ad}vis\u{ad}i\u{ad}ble", 7),
+            ["in\u{ad}di\u{ad}", "vis\u{ad}i\u{ad}", "ble"]
+        );
+    }
+
+    #[test]
+    fn test_wrap_newlines() {
+        assert_eq!(wrap("foo\nbar baz", 20), ["foo\n", "bar baz"]);
+        assert_eq!(wrap("foo\n\n", 20), ["foo\n", "\n"]);
+    }
+
+    #[test]
+    fn test_wrap_unbreakable_run() {
+        assert_eq!(wrap("abcdefgh ij", 3), ["abc", "def", "gh ", "ij"]);
+        assert_eq!(wrap("fõøbα®", 4), ["fõøb", "α®"]);
+    }
+
+    #[test]
+    fn test_wrap_hard_break_keeps_combining_marks() {
+        assert_eq!(wrap("aba\u{303}b", 3), ["ab", "a\u{303}b"]);
+        assert_eq!(wrap("a\u{303}\u{304}b", 1), ["a\u{303}\u{304}", "b"]);
+    }
+
+    #[test]
+    fn test_wrap_zero_width() {
+        assert_eq!(wrap("ab", 0), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_wrap_empty() {
+        assert!(wrap("", 5).is_empty());
+    }
+
+    #[test]
+    fn test_word_truncation_end() {
+        assert_eq!(word_truncation_end("hello world", 8), 5);
+        assert_eq!(word_truncation_end("hello world", 5), 5);
+        assert_eq!(word_truncation_end("hello world", 6), 5);
+        assert_eq!(word_truncation_end("hello world", 11), 11);
+        assert_eq!(word_truncation_end("hello world", 0), 0);
+    }
+
+    #[test]
+    fn test_word_truncation_end_hyphen() {
+        assert_eq!(word_truncation_end("well-known fact", 7), 5);
+    }
+
+    #[test]
+    fn test_word_truncation_end_keeps_combining_marks() {
+        // The budget falls before a combining mark, so the cut moves back to the previous word.
+        assert_eq!(word_truncation_end("ab cde\u{301}", 6), 2);
+    }
+
+    #[test]
+    fn test_word_truncation_end_leading_spaces() {
+        assert_eq!(word_truncation_end("  hello", 4), 4);
+    }
+}